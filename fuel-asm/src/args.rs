@@ -8,6 +8,7 @@ type Immediate18 = u32;
 
 crate::enum_try_from! {
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumIter)]
+    #[derive(strum::AsRefStr, strum::EnumString)]
     #[cfg_attr(feature = "typescript", wasm_bindgen::prelude::wasm_bindgen)]
     #[repr(u8)]
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -42,6 +43,7 @@ crate::enum_try_from! {
     /// The VM is the only who should match this struct, and it *MUST* always perform
     /// exhaustive match so all offered variants are covered.
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumIter)]
+    #[derive(strum::AsRefStr, strum::EnumString)]
     #[cfg_attr(feature = "typescript", wasm_bindgen::prelude::wasm_bindgen)]
     #[repr(u16)]
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
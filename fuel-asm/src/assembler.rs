@@ -0,0 +1,438 @@
+//! Assembler and disassembler for whole programs.
+//!
+//! The source is processed line by line. Each line may contain a label definition
+//! (`name:`), followed by an instruction in the form accepted by
+//! [`Instruction::from_str`](core::str::FromStr::from_str), or a data directive. Anything
+//! after `//` or `;` is a comment.
+//!
+//! Labels can be used in place of any immediate value. For the absolute jumps (`ji`,
+//! `jnei`, `jnzi`) and any other instruction, a label resolves to the index of the
+//! instruction it points to. For the relative jumps (`jmpf`, `jnzf`, `jnef` and their
+//! backwards counterparts) it resolves to the offset from the jump to the label.
+//!
+//! The data directives are `.word`, which emits a big-endian 32-bit value, and `.byte`,
+//! which emits a single byte.
+//!
+//! ```rust
+//! use fuel_asm::{assembler, op};
+//!
+//! let source = "
+//!     movi $r16 3
+//! loop:
+//!     subi $r16 $r16 1
+//!     jnzb $r16 $zero loop // jump back while `$r16` is not zero
+//!     ret $one
+//! ";
+//!
+//! let bytecode = assembler::assemble(source).unwrap();
+//! let expected: Vec<u8> = [
+//!     op::movi(0x10, 3),
+//!     op::subi(0x10, 0x10, 1),
+//!     op::jnzb(0x10, 0x00, 0),
+//!     op::ret(0x01),
+//! ]
+//! .into_iter()
+//! .collect();
+//! assert_eq!(bytecode, expected);
+//!
+//! let text = assembler::disassemble(&bytecode);
+//! assert_eq!(assembler::assemble(&text).unwrap(), bytecode);
+//! ```
+
+use alloc::{
+    collections::BTreeMap,
+    string::{
+        String,
+        ToString,
+    },
+    vec::Vec,
+};
+use core::fmt::{
+    self,
+    Write,
+};
+
+use crate::{
+    text::{
+        is_symbol,
+        parse_immediate,
+        parse_instruction,
+    },
+    Instruction,
+    Opcode,
+    ParseInstructionError,
+};
+
+/// Failed to assemble a program, see [`AssembleErrorKind`] for the possible causes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssembleError {
+    /// The line of the source where the error occurred, starting at 1.
+    pub line: usize,
+    /// The cause of the error.
+    pub kind: AssembleErrorKind,
+}
+
+/// The cause of an [`AssembleError`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AssembleErrorKind {
+    /// The instruction is malformed.
+    Instruction(ParseInstructionError),
+    /// The label is defined more than once.
+    DuplicateLabel(String),
+    /// The label name is not a valid symbol.
+    InvalidLabel(String),
+    /// The label is used as an instruction offset, but it doesn't point to an
+    /// instruction boundary.
+    MisalignedLabel(String),
+    /// The directive is not known.
+    UnknownDirective(String),
+    /// The directive was given an invalid or out of range value.
+    InvalidDirectiveValue,
+}
+
+impl From<ParseInstructionError> for AssembleErrorKind {
+    fn from(e: ParseInstructionError) -> Self {
+        Self::Instruction(e)
+    }
+}
+
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            AssembleErrorKind::Instruction(e) => write!(f, "{e}"),
+            AssembleErrorKind::DuplicateLabel(label) => {
+                write!(f, "label `{label}` is defined more than once")
+            }
+            AssembleErrorKind::InvalidLabel(label) => {
+                write!(f, "`{label}` is not a valid label name")
+            }
+            AssembleErrorKind::MisalignedLabel(label) => {
+                write!(
+                    f,
+                    "label `{label}` doesn't point to an instruction boundary"
+                )
+            }
+            AssembleErrorKind::UnknownDirective(directive) => {
+                write!(f, "unknown directive `{directive}`")
+            }
+            AssembleErrorKind::InvalidDirectiveValue => {
+                write!(f, "invalid directive value")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AssembleError {}
+
+/// A line of the source with its comment and label definitions removed.
+struct Line<'a> {
+    number: usize,
+    labels: Vec<&'a str>,
+    body: &'a str,
+}
+
+impl<'a> Line<'a> {
+    fn parse(number: usize, line: &'a str) -> Result<Self, AssembleError> {
+        let mut body = line;
+        for marker in ["//", ";"] {
+            if let Some((code, _comment)) = body.split_once(marker) {
+                body = code;
+            }
+        }
+
+        let mut labels = Vec::new();
+        while let Some((label, rest)) = body.split_once(':') {
+            let label = label.trim();
+            if !is_symbol(label) {
+                return Err(AssembleError {
+                    line: number,
+                    kind: AssembleErrorKind::InvalidLabel(label.to_string()),
+                })
+            }
+            labels.push(label);
+            body = rest;
+        }
+
+        Ok(Self {
+            number,
+            labels,
+            body: body.trim(),
+        })
+    }
+
+    /// The number of bytes this line emits, without validating its contents.
+    fn size(&self) -> usize {
+        match self.body.split_whitespace().next() {
+            None => 0,
+            Some(".byte") => 1,
+            Some(_) => Instruction::SIZE,
+        }
+    }
+
+    fn error(&self, kind: AssembleErrorKind) -> AssembleError {
+        AssembleError {
+            line: self.number,
+            kind,
+        }
+    }
+}
+
+/// Assemble the textual `source` into bytecode.
+///
+/// See the [module level documentation](self) for the accepted syntax.
+pub fn assemble(source: &str) -> Result<Vec<u8>, AssembleError> {
+    let lines = source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| Line::parse(i + 1, line))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // First pass: compute the byte offset of every label.
+    let mut labels = BTreeMap::new();
+    let mut offset = 0;
+    for line in &lines {
+        for label in &line.labels {
+            if labels.insert(*label, offset).is_some() {
+                return Err(
+                    line.error(AssembleErrorKind::DuplicateLabel(label.to_string()))
+                )
+            }
+        }
+        offset += line.size();
+    }
+
+    // Second pass: emit the bytecode, resolving labels to immediate values.
+    let mut bytecode = Vec::with_capacity(offset);
+    for line in &lines {
+        let mut tokens = line.body.split_whitespace();
+        match tokens.next() {
+            None => {}
+            Some(directive @ (".word" | ".byte")) => {
+                let value = tokens
+                    .next()
+                    .and_then(parse_immediate)
+                    .filter(|_| tokens.next().is_none())
+                    .ok_or_else(|| {
+                        line.error(AssembleErrorKind::InvalidDirectiveValue)
+                    })?;
+
+                if directive == ".word" {
+                    bytecode.extend(value.to_be_bytes());
+                } else {
+                    let byte = u8::try_from(value).map_err(|_| {
+                        line.error(AssembleErrorKind::InvalidDirectiveValue)
+                    })?;
+                    bytecode.push(byte);
+                }
+            }
+            Some(directive) if directive.starts_with('.') => {
+                return Err(line
+                    .error(AssembleErrorKind::UnknownDirective(directive.to_string())))
+            }
+            Some(_) => {
+                let here = bytecode.len();
+                let instruction = parse_instruction(line.body, |opcode, symbol| {
+                    resolve_label(&labels, opcode, here, symbol)
+                })
+                .map_err(|kind| line.error(kind))?;
+                bytecode.extend(instruction.to_bytes());
+            }
+        }
+    }
+
+    Ok(bytecode)
+}
+
+/// Resolve `label` to an immediate value for the `opcode` instruction located at byte
+/// offset `here`.
+fn resolve_label(
+    labels: &BTreeMap<&str, usize>,
+    opcode: Opcode,
+    here: usize,
+    label: &str,
+) -> Result<u32, AssembleErrorKind> {
+    let target = *labels
+        .get(label)
+        .ok_or(ParseInstructionError::UnknownSymbol)?;
+
+    if target % Instruction::SIZE != 0 || here % Instruction::SIZE != 0 {
+        return Err(AssembleErrorKind::MisalignedLabel(label.to_string()))
+    }
+    let target = target / Instruction::SIZE;
+    let here = here / Instruction::SIZE;
+
+    // Relative jumps skip the jump instruction itself, see the `JumpMode` documentation
+    // in the specification.
+    let offset = match opcode {
+        Opcode::JMPF | Opcode::JNZF | Opcode::JNEF => {
+            target.checked_sub(here).and_then(|d| d.checked_sub(1))
+        }
+        Opcode::JMPB | Opcode::JNZB | Opcode::JNEB => {
+            here.checked_sub(target).and_then(|d| d.checked_sub(1))
+        }
+        _ => Some(target),
+    };
+
+    offset
+        .and_then(|offset| u32::try_from(offset).ok())
+        .ok_or_else(|| ParseInstructionError::ImmediateOutOfRange.into())
+}
+
+/// Disassemble `bytecode` into its textual form, one instruction per line.
+///
+/// Words that aren't valid instructions are emitted as `.word` directives, and trailing
+/// bytes that don't form a whole word as `.byte` directives, so that the output can
+/// always be assembled back into the same bytecode.
+pub fn disassemble(bytecode: &[u8]) -> String {
+    let mut text = String::new();
+    let mut words = bytecode.chunks_exact(Instruction::SIZE);
+
+    for word in words.by_ref() {
+        let word: [u8; 4] = word.try_into().expect("Chunks are exactly one word");
+        // Writing to a `String` never fails.
+        let _ = match Instruction::try_from(word) {
+            Ok(instruction) => writeln!(text, "{instruction}"),
+            Err(_) => writeln!(text, ".word {:#010x}", u32::from_be_bytes(word)),
+        };
+    }
+
+    for byte in words.remainder() {
+        let _ = writeln!(text, ".byte {byte:#04x}");
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        op,
+        GMArgs,
+        RegId,
+    };
+
+    fn bytes(program: &[Instruction]) -> Vec<u8> {
+        program.iter().copied().collect()
+    }
+
+    #[test]
+    fn assemble_resolves_absolute_and_relative_labels() {
+        let source = "
+            start:  movi $r16 0x10      // counter
+                    ji end
+            loop:   subi $r16 $r16 1
+                    jnzb $r16 $zero loop
+                    jmpf $zero end
+                    noop
+            end:    jnei $r16 $zero start
+                    ret $one
+        ";
+
+        let expected = bytes(&[
+            op::movi(0x10, 16),
+            op::ji(6),
+            op::subi(0x10, 0x10, 1),
+            op::jnzb(0x10, RegId::ZERO, 0),
+            op::jmpf(RegId::ZERO, 1),
+            op::noop(),
+            op::jnei(0x10, RegId::ZERO, 0),
+            op::ret(RegId::ONE),
+        ]);
+
+        assert_eq!(assemble(source), Ok(expected));
+    }
+
+    #[test]
+    fn assemble_symbolic_args_and_data() {
+        let source = "
+            gm $r16 GetChainId ; comment
+            gtf $r17 $r16 InputCoinOwner
+            .word 0xdeadbeef
+            .byte 7
+        ";
+
+        let mut expected = bytes(&[
+            op::gm_args(0x10, GMArgs::GetChainId),
+            op::gtf_args(0x11, 0x10, crate::GTFArgs::InputCoinOwner),
+        ]);
+        expected.extend([0xde, 0xad, 0xbe, 0xef, 7]);
+
+        assert_eq!(assemble(source), Ok(expected));
+    }
+
+    #[test]
+    fn assemble_reports_errors_with_line_numbers() {
+        let cases = [
+            (
+                "noop\nfoo $r16",
+                2,
+                AssembleErrorKind::Instruction(ParseInstructionError::UnknownMnemonic),
+            ),
+            (
+                "ji nowhere",
+                1,
+                AssembleErrorKind::Instruction(ParseInstructionError::UnknownSymbol),
+            ),
+            (
+                "a:\nnoop\na: noop",
+                3,
+                AssembleErrorKind::DuplicateLabel("a".into()),
+            ),
+            (
+                "a: jmpb $zero a",
+                1,
+                AssembleErrorKind::Instruction(
+                    ParseInstructionError::ImmediateOutOfRange,
+                ),
+            ),
+            (
+                ".byte 1\na: noop\nji a",
+                3,
+                AssembleErrorKind::MisalignedLabel("a".into()),
+            ),
+            (
+                ".half 1",
+                1,
+                AssembleErrorKind::UnknownDirective(".half".into()),
+            ),
+            (".byte 256", 1, AssembleErrorKind::InvalidDirectiveValue),
+            ("1a: noop", 1, AssembleErrorKind::InvalidLabel("1a".into())),
+        ];
+
+        for (source, line, kind) in cases {
+            assert_eq!(
+                assemble(source),
+                Err(AssembleError { line, kind }),
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn disassemble_roundtrips_through_assemble() {
+        let mut bytecode = bytes(&[
+            op::movi(0x10, 1),
+            op::gtf_args(0x11, 0x10, crate::GTFArgs::ScriptData),
+            op::gtf(0x11, 0x10, 0xfff),
+            op::ret(RegId::ONE),
+        ]);
+        // An invalid opcode, followed by trailing data bytes.
+        bytecode.extend([0x00, 0x01, 0x02, 0x03, 0xff, 0xee]);
+
+        let text = disassemble(&bytecode);
+        assert_eq!(
+            text,
+            "movi $r16 1\n\
+             gtf $r17 $r16 ScriptData\n\
+             gtf $r17 $r16 4095\n\
+             ret $one\n\
+             .word 0x00010203\n\
+             .byte 0xff\n\
+             .byte 0xee\n"
+        );
+        assert_eq!(assemble(&text), Ok(bytecode));
+    }
+}
@@ -54,6 +54,53 @@ fn opcode() {
     }
 }
 
+#[test]
+fn instruction_text() {
+    let r = RegId::new_checked(0x2d).unwrap();
+    let imm = 0x0bfdfd;
+
+    let mut instructions = Vec::new();
+    for opcode_int in 0..=u8::MAX {
+        if let Ok(op) = Opcode::try_from(opcode_int) {
+            instructions.push(op.test_construct(r, RegId::HP, r, RegId::ZERO, imm));
+        }
+    }
+    instructions.extend(GMArgs::iter().map(|arg| op::gm_args(r, arg)));
+    instructions.extend(GTFArgs::iter().map(|arg| op::gtf_args(r, r, arg)));
+
+    for ins in instructions {
+        let text = ins.to_string();
+        let ins_p: Instruction = text.parse().expect("Failed to parse instruction");
+        assert_eq!(ins, ins_p, "{text}");
+    }
+
+    assert_eq!(op::movi(0x10, 42).to_string(), "movi $r16 42");
+    assert_eq!(
+        op::mod_(0x10, 0x11, 0x00).to_string(),
+        "mod $r16 $r17 $zero"
+    );
+    assert_eq!("MOVE $R16, $sp".parse(), Ok(op::move_(0x10, RegId::SP)));
+    assert_eq!(
+        "addi $r16 $r16".parse::<Instruction>(),
+        Err(ParseInstructionError::OperandCount {
+            expected: 3,
+            found: 2
+        })
+    );
+    assert_eq!(
+        "addi $r16 $r16 4096".parse::<Instruction>(),
+        Err(ParseInstructionError::ImmediateOutOfRange)
+    );
+    assert_eq!(
+        "add $r16 $r16 $r64".parse::<Instruction>(),
+        Err(ParseInstructionError::InvalidRegister)
+    );
+    assert_eq!(
+        "ji label".parse::<Instruction>(),
+        Err(ParseInstructionError::UnknownSymbol)
+    );
+}
+
 #[test]
 fn panic_reason_description() {
     let imm24 = 0xbfffff;
@@ -12,6 +12,8 @@
 extern crate alloc;
 
 mod args;
+#[cfg(feature = "alloc")]
+pub mod assembler;
 mod panic_instruction;
 // This is `pub` to make documentation for the private `impl_instructions!` macro more
 // accessible.
@@ -20,6 +22,7 @@ pub mod macros;
 pub mod op;
 mod pack;
 mod panic_reason;
+mod text;
 mod unpack;
 
 #[cfg(test)]
@@ -40,6 +43,10 @@ pub type Word = u64;
 
 pub use panic_instruction::PanicInstruction;
 pub use panic_reason::PanicReason;
+pub use text::{
+    OperandKind,
+    ParseInstructionError,
+};
 
 /// Represents a 6-bit register ID, guaranteed to be masked by construction.
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
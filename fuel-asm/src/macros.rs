@@ -1116,7 +1116,42 @@ macro_rules! impl_instructions {
             }
         }
 
+        impl core::str::FromStr for Opcode {
+            type Err = InvalidOpcode;
+
+            /// Parse an opcode from its mnemonic, ignoring ASCII case.
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $(
+                    if s.eq_ignore_ascii_case(stringify!($Op)) {
+                        return Ok(Opcode::$Op)
+                    }
+                )*
+                Err(InvalidOpcode)
+            }
+        }
+
         impl Opcode {
+            /// The lowercase assembly mnemonic of the opcode, e.g. `"add"`.
+            pub fn mnemonic(&self) -> &'static str {
+                let name = match self {
+                    $(
+                        Self::$Op => stringify!($op),
+                    )*
+                };
+                // Trailing underscores are only used to avoid clashes with Rust keywords
+                // in the short-hand constructor names, e.g. `op::move_`.
+                name.trim_end_matches('_')
+            }
+
+            /// The kinds of the operands of the instruction, in encoding order.
+            pub fn operands(&self) -> &'static [OperandKind] {
+                match self {
+                    $(
+                        Self::$Op => &[$(OperandKind::$field),*],
+                    )*
+                }
+            }
+
             /// Construct the instruction from all possible raw fields, ignoring inapplicable ones.
             #[cfg(test)]
             pub fn test_construct(self, ra: RegId, rb: RegId, rc: RegId, rd: RegId, imm: u32) -> Instruction {
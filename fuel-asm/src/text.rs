@@ -0,0 +1,283 @@
+//! Textual assembly representation of single instructions.
+//!
+//! An instruction is written as its lowercase mnemonic followed by its operands in
+//! encoding order, separated by whitespace or commas:
+//!
+//! ```rust
+//! use fuel_asm::{op, Instruction, GTFArgs, RegId};
+//!
+//! let addi = op::addi(0x10, RegId::HP, 32);
+//! assert_eq!(addi.to_string(), "addi $r16 $hp 32");
+//! assert_eq!("addi $r16, $hp, 0x20".parse::<Instruction>(), Ok(addi));
+//!
+//! let gtf = op::gtf_args(0x10, RegId::ZERO, GTFArgs::ScriptData);
+//! assert_eq!(gtf.to_string(), "gtf $r16 $zero ScriptData");
+//! assert_eq!(gtf.to_string().parse::<Instruction>(), Ok(gtf));
+//! ```
+//!
+//! Registers are written as `$rN`, or by their name for the reserved registers (`$pc`,
+//! `$sp`, `$hp`, ...). Immediates are written in decimal, `0x` hexadecimal or `0b` binary
+//! form. The selector of `GM` and `GTF` may be written as the name of the corresponding
+//! [`GMArgs`] or [`GTFArgs`] variant.
+
+use core::{
+    fmt,
+    str::FromStr,
+};
+
+use crate::{
+    GMArgs,
+    GTFArgs,
+    Instruction,
+    Opcode,
+    RawInstruction,
+    RegId,
+};
+
+/// The kind of a single operand of an instruction.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum OperandKind {
+    /// A 6-bit register ID.
+    RegId,
+    /// A 6-bit immediate value.
+    Imm06,
+    /// A 12-bit immediate value.
+    Imm12,
+    /// A 18-bit immediate value.
+    Imm18,
+    /// A 24-bit immediate value.
+    Imm24,
+}
+
+impl OperandKind {
+    /// The number of bits the operand occupies in the encoded instruction.
+    pub const fn bits(&self) -> u32 {
+        match self {
+            Self::RegId | Self::Imm06 => 6,
+            Self::Imm12 => 12,
+            Self::Imm18 => 18,
+            Self::Imm24 => 24,
+        }
+    }
+}
+
+/// Failed to parse an [`Instruction`] or a [`RegId`] from its textual form.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ParseInstructionError {
+    /// The text doesn't contain a mnemonic.
+    MissingMnemonic,
+    /// The mnemonic doesn't name a known opcode.
+    UnknownMnemonic,
+    /// The instruction was given the wrong number of operands.
+    OperandCount {
+        /// The number of operands the opcode takes.
+        expected: usize,
+        /// The number of operands that were given.
+        found: usize,
+    },
+    /// The operand is not a valid register.
+    InvalidRegister,
+    /// The operand is not a valid immediate value.
+    InvalidImmediate,
+    /// The immediate value doesn't fit into the operand.
+    ImmediateOutOfRange,
+    /// The operand is a symbol that couldn't be resolved.
+    UnknownSymbol,
+}
+
+impl fmt::Display for ParseInstructionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingMnemonic => write!(f, "missing mnemonic"),
+            Self::UnknownMnemonic => write!(f, "unknown mnemonic"),
+            Self::OperandCount { expected, found } => {
+                write!(f, "expected {expected} operands, found {found}")
+            }
+            Self::InvalidRegister => write!(f, "invalid register"),
+            Self::InvalidImmediate => write!(f, "invalid immediate value"),
+            Self::ImmediateOutOfRange => write!(f, "immediate value out of range"),
+            Self::UnknownSymbol => write!(f, "unknown symbol"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseInstructionError {}
+
+/// Names of the reserved registers, indexed by register ID.
+const REGISTER_NAMES: [&str; RegId::WRITABLE.to_u8() as usize] = [
+    "zero", "one", "of", "pc", "ssp", "sp", "fp", "hp", "err", "ggas", "cgas", "bal",
+    "is", "ret", "retl", "flag",
+];
+
+impl fmt::Display for RegId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match REGISTER_NAMES.get(usize::from(*self)) {
+            Some(name) => write!(f, "${name}"),
+            None => write!(f, "$r{}", self.to_u8()),
+        }
+    }
+}
+
+impl FromStr for RegId {
+    type Err = ParseInstructionError;
+
+    /// Parse a register from either its name (`$hp`) or its index (`$r7`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s
+            .strip_prefix('$')
+            .ok_or(ParseInstructionError::InvalidRegister)?;
+
+        if let Some(id) = REGISTER_NAMES
+            .iter()
+            .position(|reserved| reserved.eq_ignore_ascii_case(name))
+        {
+            return Ok(RegId::new(id as u8))
+        }
+
+        name.strip_prefix('r')
+            .or_else(|| name.strip_prefix('R'))
+            .filter(|index| {
+                !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit())
+            })
+            .and_then(|index| index.parse().ok())
+            .and_then(RegId::new_checked)
+            .ok_or(ParseInstructionError::InvalidRegister)
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let opcode = self.opcode();
+        let raw = RawInstruction::from(*self);
+        f.write_str(opcode.mnemonic())?;
+
+        let mut shift = 24;
+        for kind in opcode.operands() {
+            shift -= kind.bits();
+            let value = (raw >> shift) & ((1 << kind.bits()) - 1);
+
+            match (opcode, kind) {
+                (_, OperandKind::RegId) => write!(f, " {}", RegId::new(value as u8))?,
+                (Opcode::GM, _) => match GMArgs::try_from(value) {
+                    Ok(args) => write!(f, " {}", args.as_ref())?,
+                    Err(_) => write!(f, " {value}")?,
+                },
+                (Opcode::GTF, _) => match GTFArgs::try_from(value as u16) {
+                    Ok(args) => write!(f, " {}", args.as_ref())?,
+                    Err(_) => write!(f, " {value}")?,
+                },
+                _ => write!(f, " {value}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = ParseInstructionError;
+
+    /// Parse an instruction from its textual form.
+    ///
+    /// Symbolic immediates other than the `GM` and `GTF` selectors are rejected. Use
+    /// [`crate::assembler::assemble`] to assemble programs that make use of labels.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_instruction(s, |_, _| Err(ParseInstructionError::UnknownSymbol))
+    }
+}
+
+/// Parse an instruction from its textual form, resolving symbolic immediates with
+/// `resolve_symbol`.
+///
+/// The resolver is given the opcode of the instruction and the symbol, and is only
+/// called for symbols that aren't a valid `GM` or `GTF` selector.
+pub(crate) fn parse_instruction<F, E>(
+    s: &str,
+    mut resolve_symbol: F,
+) -> Result<Instruction, E>
+where
+    F: FnMut(Opcode, &str) -> Result<u32, E>,
+    E: From<ParseInstructionError>,
+{
+    let mut tokens = s
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty());
+
+    let opcode: Opcode = tokens
+        .next()
+        .ok_or(ParseInstructionError::MissingMnemonic)?
+        .parse()
+        .map_err(|_| ParseInstructionError::UnknownMnemonic)?;
+    let kinds = opcode.operands();
+
+    let mut raw = RawInstruction::from(u8::from(opcode)) << 24;
+    let mut shift = 24;
+    let mut found = 0;
+    for token in tokens {
+        found += 1;
+        let Some(kind) = kinds.get(found - 1) else {
+            continue
+        };
+        shift -= kind.bits();
+
+        let value = match kind {
+            OperandKind::RegId => u32::from(token.parse::<RegId>()?.to_u8()),
+            _ => match parse_immediate(token) {
+                Some(value) => value,
+                None if is_symbol(token) => match opcode {
+                    Opcode::GM => GMArgs::from_str(token)
+                        .map(u32::from)
+                        .or_else(|_| resolve_symbol(opcode, token))?,
+                    Opcode::GTF => GTFArgs::from_str(token)
+                        .map(|args| u32::from(u16::from(args)))
+                        .or_else(|_| resolve_symbol(opcode, token))?,
+                    _ => resolve_symbol(opcode, token)?,
+                },
+                None => return Err(ParseInstructionError::InvalidImmediate.into()),
+            },
+        };
+
+        if value >> kind.bits() != 0 {
+            return Err(ParseInstructionError::ImmediateOutOfRange.into())
+        }
+        raw |= value << shift;
+    }
+
+    if found != kinds.len() {
+        return Err(ParseInstructionError::OperandCount {
+            expected: kinds.len(),
+            found,
+        }
+        .into())
+    }
+
+    Ok(Instruction::try_from(raw).expect("The opcode was parsed from a known mnemonic"))
+}
+
+/// Parse an unsigned integer in decimal, `0x` hexadecimal or `0b` binary form.
+pub(crate) fn parse_immediate(s: &str) -> Option<u32> {
+    let (digits, radix) = if let Some(hex) = s.strip_prefix("0x") {
+        (hex, 16)
+    } else if let Some(bin) = s.strip_prefix("0b") {
+        (bin, 2)
+    } else {
+        (s, 10)
+    };
+
+    // `from_str_radix` accepts a leading `+`, which isn't valid assembly.
+    if !digits.bytes().all(|b| (b as char).is_digit(radix)) {
+        return None
+    }
+
+    u32::from_str_radix(digits, radix).ok()
+}
+
+/// Check if the text is a valid symbol, i.e. a label or an argument name.
+pub(crate) fn is_symbol(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '.')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
@@ -19,4 +19,8 @@ pub use primitive::Primitive;
 pub mod in_memory;
 pub mod root_calculator;
 
-pub use verify::verify;
+pub use verify::{
+    verify,
+    verify_consistency,
+    verify_multi,
+};
@@ -51,6 +51,17 @@ impl MerkleTree {
         self.tree.prove(proof_index).ok()
     }
 
+    pub fn prove_multi(&self, proof_indices: &[u64]) -> Option<(Bytes32, ProofSet)> {
+        self.tree.prove_multi(proof_indices).ok()
+    }
+
+    pub fn prove_consistency(
+        &self,
+        old_leaves_count: u64,
+    ) -> Option<(Bytes32, ProofSet)> {
+        self.tree.prove_consistency(old_leaves_count).ok()
+    }
+
     pub fn reset(&mut self) {
         self.tree.reset();
     }
@@ -29,6 +29,9 @@ pub enum MerkleTreeError<StorageError> {
     #[display(fmt = "proof index {_0} is not valid")]
     InvalidProofIndex(u64),
 
+    #[display(fmt = "leaves count {_0} is not valid")]
+    InvalidLeavesCount(u64),
+
     #[display(fmt = "cannot load node with key {_0}; the key is not found in storage")]
     LoadError(u64),
 
@@ -188,11 +191,7 @@ where
         // error.
         for side_position in side_positions {
             let key = side_position.in_order_index();
-            let primitive = StorageInspectInfallible::get(&scratch_storage, &key)
-                .or(StorageInspect::get(&self.storage, &key)?)
-                .ok_or(MerkleTreeError::LoadError(key))?
-                .into_owned();
-            let node = Node::from(primitive);
+            let node = self.load_node(&scratch_storage, key)?;
             proof_set.push(*node.hash());
         }
 
@@ -200,6 +199,81 @@ where
         Ok((root, proof_set))
     }
 
+    /// Generate a single proof for the leaves at `proof_indices`.
+    ///
+    /// Side nodes shared by the paths of several leaves, and nodes that can be
+    /// computed from the proven leaves, are included only once or not at all. The
+    /// proof set lists the roots of the subtrees that contain none of the proven
+    /// leaves, in depth-first, left-to-right order. It can be checked with
+    /// [`verify_multi`](crate::binary::verify_multi), given the leaves in ascending
+    /// index order. Duplicate indices are ignored.
+    pub fn prove_multi(
+        &self,
+        proof_indices: &[u64],
+    ) -> Result<(Bytes32, ProofSet), MerkleTreeError<StorageError>> {
+        let mut proof_indices = proof_indices.to_vec();
+        proof_indices.sort_unstable();
+        proof_indices.dedup();
+
+        if let Some(&proof_index) = proof_indices.last() {
+            if proof_index + 1 > self.leaves_count {
+                return Err(MerkleTreeError::InvalidProofIndex(proof_index))
+            }
+        }
+
+        let mut scratch_storage = StorageMap::<NodesTable>::new();
+        let root = match self.root_node(&mut scratch_storage) {
+            None => *Self::empty_root(),
+            Some(ref node) => *node.hash(),
+        };
+
+        let mut proof_set = ProofSet::new();
+        if self.leaves_count > 0 {
+            self.collect_multi_proof_set(
+                &scratch_storage,
+                &proof_indices,
+                0,
+                self.leaves_count,
+                &mut proof_set,
+            )?;
+        }
+
+        Ok((root, proof_set))
+    }
+
+    /// Generate a proof that the tree with `old_leaves_count` leaves is a prefix of
+    /// this tree, following the consistency proofs of RFC 6962. The root of the old
+    /// tree is obtained by loading it at `old_leaves_count`, and the proof can be
+    /// checked with [`verify_consistency`](crate::binary::verify_consistency).
+    pub fn prove_consistency(
+        &self,
+        old_leaves_count: u64,
+    ) -> Result<(Bytes32, ProofSet), MerkleTreeError<StorageError>> {
+        if old_leaves_count > self.leaves_count {
+            return Err(MerkleTreeError::InvalidLeavesCount(old_leaves_count))
+        }
+
+        let mut scratch_storage = StorageMap::<NodesTable>::new();
+        let root = match self.root_node(&mut scratch_storage) {
+            None => *Self::empty_root(),
+            Some(ref node) => *node.hash(),
+        };
+
+        let mut proof_set = ProofSet::new();
+        if old_leaves_count > 0 {
+            self.collect_consistency_proof_set(
+                &scratch_storage,
+                old_leaves_count,
+                0,
+                self.leaves_count,
+                true,
+                &mut proof_set,
+            )?;
+        }
+
+        Ok((root, proof_set))
+    }
+
     pub fn reset(&mut self) {
         self.leaves_count = 0;
         self.head = None;
@@ -208,6 +282,133 @@ where
     // PRIVATE
     //
 
+    /// The hash of the node spanning the subtree of `leaves_count` leaves from
+    /// `start`. The node is stored at in-order index `2 * start + N' - 1`, where
+    /// `N'` is `leaves_count` rounded (or equal) to the next power of 2.
+    ///
+    /// Nodes above imbalanced subtrees are looked up in `scratch_storage`, which
+    /// must have been filled by [`Self::root_node`].
+    fn subtree_hash(
+        &self,
+        scratch_storage: &StorageMap<NodesTable>,
+        start: u64,
+        leaves_count: u64,
+    ) -> Result<Bytes32, MerkleTreeError<StorageError>> {
+        let key = 2 * start + leaves_count.next_power_of_two() - 1;
+        let node = self.load_node(scratch_storage, key)?;
+        Ok(*node.hash())
+    }
+
+    /// Load the node at in-order index `key` from `scratch_storage`, falling back
+    /// to the main storage only if it isn't found there.
+    fn load_node(
+        &self,
+        scratch_storage: &StorageMap<NodesTable>,
+        key: u64,
+    ) -> Result<Node, MerkleTreeError<StorageError>> {
+        let primitive = match StorageInspectInfallible::get(scratch_storage, &key) {
+            Some(primitive) => primitive.into_owned(),
+            None => StorageInspect::get(&self.storage, &key)?
+                .ok_or(MerkleTreeError::LoadError(key))?
+                .into_owned(),
+        };
+        Ok(Node::from(primitive))
+    }
+
+    /// Push the side nodes of the subtree spanning `leaves_count` leaves from
+    /// `start` needed to prove the leaves at `proof_indices`, which must be sorted
+    /// and lie within the subtree.
+    ///
+    /// The subtree is split the same way the tree is built: the left child spans
+    /// the largest power of two number of leaves less than `leaves_count`.
+    fn collect_multi_proof_set(
+        &self,
+        scratch_storage: &StorageMap<NodesTable>,
+        proof_indices: &[u64],
+        start: u64,
+        leaves_count: u64,
+        proof_set: &mut ProofSet,
+    ) -> Result<(), MerkleTreeError<StorageError>> {
+        if proof_indices.is_empty() {
+            let hash = self.subtree_hash(scratch_storage, start, leaves_count)?;
+            proof_set.push(hash);
+            return Ok(())
+        }
+
+        if leaves_count == 1 {
+            return Ok(())
+        }
+
+        let split = leaves_count.next_power_of_two() / 2;
+        let (left_indices, right_indices) = proof_indices
+            .split_at(proof_indices.partition_point(|&index| index < start + split));
+        self.collect_multi_proof_set(
+            scratch_storage,
+            left_indices,
+            start,
+            split,
+            proof_set,
+        )?;
+        self.collect_multi_proof_set(
+            scratch_storage,
+            right_indices,
+            start + split,
+            leaves_count - split,
+            proof_set,
+        )
+    }
+
+    /// Push the nodes proving that the first `old_leaves_count` leaves of the
+    /// subtree spanning `leaves_count` leaves from `start` are consistent with
+    /// the subtree (`SUBPROOF` in RFC 6962). `is_complete` is set while the old
+    /// leaves span the left edge of the whole tree, in which case the verifier
+    /// already knows the root of the old subtree.
+    fn collect_consistency_proof_set(
+        &self,
+        scratch_storage: &StorageMap<NodesTable>,
+        old_leaves_count: u64,
+        start: u64,
+        leaves_count: u64,
+        is_complete: bool,
+        proof_set: &mut ProofSet,
+    ) -> Result<(), MerkleTreeError<StorageError>> {
+        if old_leaves_count == leaves_count {
+            if !is_complete {
+                let hash = self.subtree_hash(scratch_storage, start, leaves_count)?;
+                proof_set.push(hash);
+            }
+            return Ok(())
+        }
+
+        let split = leaves_count.next_power_of_two() / 2;
+        if old_leaves_count <= split {
+            self.collect_consistency_proof_set(
+                scratch_storage,
+                old_leaves_count,
+                start,
+                split,
+                is_complete,
+                proof_set,
+            )?;
+            let hash =
+                self.subtree_hash(scratch_storage, start + split, leaves_count - split)?;
+            proof_set.push(hash);
+        } else {
+            self.collect_consistency_proof_set(
+                scratch_storage,
+                old_leaves_count - split,
+                start + split,
+                leaves_count - split,
+                false,
+                proof_set,
+            )?;
+            let hash = self.subtree_hash(scratch_storage, start, split)?;
+            proof_set.push(hash);
+        }
+
+        Ok(())
+    }
+
     /// A binary Merkle tree can be built from a collection of Merkle Mountain
     /// Range (MMR) peaks. The MMR structure can be accurately defined by the
     /// number of leaves in the leaf row.
@@ -857,6 +1058,71 @@ mod test {
         }
     }
 
+    #[test]
+    fn prove_multi_returns_invalid_proof_index_error_when_an_index_is_out_of_bounds() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        let data = &TEST_DATA[0..5]; // 5 leaves
+        for datum in data.iter() {
+            tree.push(datum).unwrap();
+        }
+
+        let err = tree
+            .prove_multi(&[1, 5, 3])
+            .expect_err("Expected prove_multi() to return Error");
+        assert!(matches!(err, MerkleTreeError::InvalidProofIndex(5)));
+    }
+
+    #[test]
+    fn prove_multi_returns_the_merkle_root_and_proof_set_for_5_leaves() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        let data = &TEST_DATA[0..5]; // 5 leaves
+        for datum in data.iter() {
+            tree.push(datum).unwrap();
+        }
+
+        //          07
+        //          /\
+        //         /  \
+        //       03    \
+        //      /  \    \
+        //     /    \    \
+        //   01      05   \
+        //  /  \    /  \   \
+        // 00  02  04  06  08
+        // 00  01  02  03  04
+
+        let leaf_0 = leaf_sum(data[0]);
+        let leaf_1 = leaf_sum(data[1]);
+        let leaf_2 = leaf_sum(data[2]);
+        let leaf_3 = leaf_sum(data[3]);
+        let leaf_4 = leaf_sum(data[4]);
+
+        let node_1 = node_sum(&leaf_0, &leaf_1);
+        let node_5 = node_sum(&leaf_2, &leaf_3);
+        let node_3 = node_sum(&node_1, &node_5);
+        let node_7 = node_sum(&node_3, &leaf_4);
+
+        {
+            let (root, proof_set) = tree.prove_multi(&[3, 0, 3]).unwrap();
+            assert_eq!(root, node_7);
+            assert_eq!(proof_set, vec![leaf_1, leaf_2, leaf_4]);
+        }
+        {
+            let (root, proof_set) = tree.prove_multi(&[0, 1]).unwrap();
+            assert_eq!(root, node_7);
+            assert_eq!(proof_set, vec![node_5, leaf_4]);
+        }
+        {
+            let (root, proof_set) = tree.prove_multi(&[0, 1, 2, 3, 4]).unwrap();
+            assert_eq!(root, node_7);
+            assert!(proof_set.is_empty());
+        }
+    }
+
     #[test]
     fn reset_reverts_tree_to_empty_state() {
         let mut storage_map = StorageMap::<TestTable>::new();
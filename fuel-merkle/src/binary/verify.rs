@@ -1,5 +1,6 @@
 use crate::{
     binary::{
+        empty_sum,
         leaf_sum,
         node_sum,
     },
@@ -71,12 +72,140 @@ pub fn verify<T: AsRef<[u8]>>(
     sum == *root
 }
 
+/// Verify a multi-leaf proof generated by
+/// [`MerkleTree::prove_multi`](crate::binary::MerkleTree::prove_multi).
+///
+/// `leaves` are the `(index, data)` pairs of the proven leaves, and must be given in
+/// strictly ascending index order.
+pub fn verify_multi<T: AsRef<[u8]>>(
+    root: &Bytes32,
+    leaves: &[(u64, T)],
+    proof_set: &ProofSet,
+    num_leaves: u64,
+) -> bool {
+    let ascending = leaves.windows(2).all(|pair| pair[0].0 < pair[1].0);
+    let in_bounds = leaves.last().is_some_and(|(index, _)| *index < num_leaves);
+    if !ascending || !in_bounds {
+        return false
+    }
+
+    let mut proof_set = proof_set.iter();
+    let Some(sum) = multi_proof_root(leaves, &mut proof_set, 0, num_leaves) else {
+        return false
+    };
+
+    proof_set.next().is_none() && sum == *root
+}
+
+/// Verify a consistency proof generated by
+/// [`MerkleTree::prove_consistency`](crate::binary::MerkleTree::prove_consistency),
+/// showing that the tree with root `old_root` and `old_leaves_count` leaves is a
+/// prefix of the tree with root `new_root` and `new_leaves_count` leaves.
+///
+/// This is the verification algorithm of RFC 9162, section 2.1.4.2.
+pub fn verify_consistency(
+    old_root: &Bytes32,
+    old_leaves_count: u64,
+    new_root: &Bytes32,
+    new_leaves_count: u64,
+    proof_set: &ProofSet,
+) -> bool {
+    if old_leaves_count > new_leaves_count {
+        return false
+    }
+
+    if old_leaves_count == new_leaves_count {
+        return proof_set.is_empty() && old_root == new_root
+    }
+
+    // Every tree extends the empty tree.
+    if old_leaves_count == 0 {
+        return proof_set.is_empty() && old_root == empty_sum()
+    }
+
+    if proof_set.is_empty() {
+        return false
+    }
+
+    // If the old tree is balanced, its root is a node of the new tree and the first
+    // node of the path; otherwise the proof set starts with it.
+    let mut path = proof_set.iter();
+    let first = if old_leaves_count.is_power_of_two() {
+        *old_root
+    } else {
+        *path.next().expect("Proof set is not empty")
+    };
+
+    let mut old_index = old_leaves_count - 1;
+    let mut new_index = new_leaves_count - 1;
+    while old_index & 1 == 1 {
+        old_index >>= 1;
+        new_index >>= 1;
+    }
+
+    let mut old_sum = first;
+    let mut new_sum = first;
+    for side in path {
+        if new_index == 0 {
+            return false
+        }
+
+        if old_index & 1 == 1 || old_index == new_index {
+            old_sum = node_sum(side, &old_sum);
+            new_sum = node_sum(side, &new_sum);
+            while old_index & 1 == 0 && old_index != 0 {
+                old_index >>= 1;
+                new_index >>= 1;
+            }
+        } else {
+            new_sum = node_sum(&new_sum, side);
+        }
+
+        old_index >>= 1;
+        new_index >>= 1;
+    }
+
+    old_sum == *old_root && new_sum == *new_root && new_index == 0
+}
+
+/// Compute the root of the subtree spanning `leaves_count` leaves from `start`,
+/// taking the roots of subtrees without proven leaves from `proof_set`.
+fn multi_proof_root<T: AsRef<[u8]>>(
+    leaves: &[(u64, T)],
+    proof_set: &mut core::slice::Iter<Bytes32>,
+    start: u64,
+    leaves_count: u64,
+) -> Option<Bytes32> {
+    match leaves {
+        [] => proof_set.next().copied(),
+        [(_, data)] if leaves_count == 1 => Some(leaf_sum(data.as_ref())),
+        _ => {
+            let split = leaves_count.next_power_of_two() / 2;
+            let (left_leaves, right_leaves) = leaves
+                .split_at(leaves.partition_point(|(index, _)| *index < start + split));
+            let lhs = multi_proof_root(left_leaves, proof_set, start, split)?;
+            let rhs = multi_proof_root(
+                right_leaves,
+                proof_set,
+                start + split,
+                leaves_count - split,
+            )?;
+            Some(node_sum(&lhs, &rhs))
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::verify;
+    use super::{
+        verify,
+        verify_consistency,
+        verify_multi,
+    };
     use crate::{
         binary::{
             MerkleTree,
+            MerkleTreeError,
             Primitive,
         },
         common::StorageMap,
@@ -201,4 +330,200 @@ mod test {
         );
         assert!(!verification);
     }
+
+    #[test]
+    fn verify_multi_returns_true_for_every_subset_of_leaves() {
+        for leaves_count in 1..=TEST_DATA.len() {
+            let mut storage_map = StorageMap::<TestTable>::new();
+            let mut tree = MerkleTree::new(&mut storage_map);
+
+            let data = &TEST_DATA[0..leaves_count];
+            for datum in data.iter() {
+                tree.push(datum).unwrap();
+            }
+
+            for subset in 1u32..(1 << leaves_count) {
+                let leaves = data
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| subset & (1 << index) != 0)
+                    .map(|(index, datum)| (index as u64, *datum))
+                    .collect::<Vec<_>>();
+                let indices = leaves.iter().map(|(index, _)| *index).collect::<Vec<_>>();
+
+                let (root, proof_set) = tree.prove_multi(&indices).unwrap();
+                assert!(verify_multi(
+                    &root,
+                    &leaves,
+                    &proof_set,
+                    leaves_count as u64
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn verify_multi_shares_side_nodes_between_leaves() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        let data = &TEST_DATA[0..8]; // 8 leaves
+        for datum in data.iter() {
+            tree.push(datum).unwrap();
+        }
+
+        // Proving every other leaf requires one sibling per leaf, while proving
+        // the leaves one by one requires three side nodes per leaf.
+        let (_, proof_set) = tree.prove_multi(&[0, 2, 4, 6]).unwrap();
+        assert_eq!(proof_set.len(), 4);
+
+        let (_, proof_set) = tree.prove_multi(&[0, 1, 2, 3]).unwrap();
+        assert_eq!(proof_set.len(), 1);
+    }
+
+    #[test]
+    fn verify_multi_returns_false_for_invalid_leaves_or_proof_set() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        const LEAVES_COUNT: u64 = 7;
+
+        let data = &TEST_DATA[0..LEAVES_COUNT as usize]; // 7 leaves
+        for datum in data.iter() {
+            tree.push(datum).unwrap();
+        }
+
+        let (root, proof_set) = tree.prove_multi(&[1, 4, 6]).unwrap();
+        let leaves = [(1, data[1]), (4, data[4]), (6, data[6])];
+        assert!(verify_multi(&root, &leaves, &proof_set, LEAVES_COUNT));
+
+        // Wrong data
+        let wrong_data = [(1, data[1]), (4, data[5]), (6, data[6])];
+        assert!(!verify_multi(&root, &wrong_data, &proof_set, LEAVES_COUNT));
+
+        // Wrong indices
+        let wrong_indices = [(1, data[1]), (5, data[4]), (6, data[6])];
+        assert!(!verify_multi(
+            &root,
+            &wrong_indices,
+            &proof_set,
+            LEAVES_COUNT
+        ));
+
+        // Unordered leaves
+        let unordered = [(4, data[4]), (1, data[1]), (6, data[6])];
+        assert!(!verify_multi(&root, &unordered, &proof_set, LEAVES_COUNT));
+
+        // Missing leaf
+        let missing = [(1, data[1]), (4, data[4])];
+        assert!(!verify_multi(&root, &missing, &proof_set, LEAVES_COUNT));
+
+        // Out of bounds
+        let out_of_bounds = [(1, data[1]), (4, data[4]), (7, data[6])];
+        assert!(!verify_multi(
+            &root,
+            &out_of_bounds,
+            &proof_set,
+            LEAVES_COUNT
+        ));
+
+        // Truncated and extended proof sets
+        let truncated = proof_set[..proof_set.len() - 1].to_vec();
+        assert!(!verify_multi(&root, &leaves, &truncated, LEAVES_COUNT));
+        let mut extended = proof_set.clone();
+        extended.push(root);
+        assert!(!verify_multi(&root, &leaves, &extended, LEAVES_COUNT));
+
+        // No leaves
+        let (root, proof_set) = tree.prove_multi(&[]).unwrap();
+        assert!(!verify_multi::<&[u8]>(&root, &[], &proof_set, LEAVES_COUNT));
+    }
+
+    #[test]
+    fn verify_consistency_returns_true_for_prefixes_of_the_tree() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        let mut roots = vec![*MerkleTree::<(), ()>::empty_root()];
+        for datum in TEST_DATA.iter() {
+            tree.push(datum).unwrap();
+            roots.push(tree.root());
+        }
+
+        for new_leaves_count in 0..=TEST_DATA.len() {
+            let new_tree =
+                MerkleTree::load(&mut storage_map, new_leaves_count as u64).unwrap();
+            for old_leaves_count in 0..=new_leaves_count {
+                let (new_root, proof_set) =
+                    new_tree.prove_consistency(old_leaves_count as u64).unwrap();
+                assert_eq!(new_root, roots[new_leaves_count]);
+                assert!(verify_consistency(
+                    &roots[old_leaves_count],
+                    old_leaves_count as u64,
+                    &new_root,
+                    new_leaves_count as u64,
+                    &proof_set,
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn verify_consistency_returns_false_for_rewritten_history() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        let mut old_storage_map = StorageMap::<TestTable>::new();
+        let mut old_tree = MerkleTree::new(&mut old_storage_map);
+
+        for datum in TEST_DATA[0..7].iter() {
+            tree.push(datum).unwrap();
+        }
+        old_tree.push(TEST_DATA[0]).unwrap();
+        old_tree.push(TEST_DATA[1]).unwrap();
+        old_tree.push(TEST_DATA[9]).unwrap();
+        let old_root = old_tree.root();
+
+        let (new_root, proof_set) = tree.prove_consistency(3).unwrap();
+        assert!(!verify_consistency(&old_root, 3, &new_root, 7, &proof_set));
+    }
+
+    #[test]
+    fn verify_consistency_returns_false_for_invalid_proof_set_or_leaves_count() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        for datum in TEST_DATA[0..3].iter() {
+            tree.push(datum).unwrap();
+        }
+        let old_root = tree.root();
+        for datum in TEST_DATA[3..7].iter() {
+            tree.push(datum).unwrap();
+        }
+
+        let (new_root, proof_set) = tree.prove_consistency(3).unwrap();
+        assert!(verify_consistency(&old_root, 3, &new_root, 7, &proof_set));
+
+        // Wrong leaves counts
+        assert!(!verify_consistency(&old_root, 2, &new_root, 7, &proof_set));
+        assert!(!verify_consistency(&old_root, 3, &new_root, 4, &proof_set));
+        assert!(!verify_consistency(&old_root, 7, &new_root, 3, &proof_set));
+
+        // Swapped roots
+        assert!(!verify_consistency(&new_root, 3, &old_root, 7, &proof_set));
+
+        // Truncated, extended and empty proof sets
+        let truncated = proof_set[..proof_set.len() - 1].to_vec();
+        assert!(!verify_consistency(&old_root, 3, &new_root, 7, &truncated));
+        let mut extended = proof_set.clone();
+        extended.push(new_root);
+        assert!(!verify_consistency(&old_root, 3, &new_root, 7, &extended));
+        assert!(!verify_consistency(&old_root, 3, &new_root, 7, &vec![]));
+
+        // The old tree can't be larger than the new tree
+        let err = tree
+            .prove_consistency(8)
+            .expect_err("Expected prove_consistency() to return Error");
+        assert!(matches!(err, MerkleTreeError::InvalidLeavesCount(8)));
+    }
 }
@@ -2,6 +2,8 @@ mod hash;
 mod merkle_tree;
 mod node;
 mod primitive;
+mod proof;
+mod verify;
 
 pub(crate) use hash::zero_sum;
 pub(crate) use node::{
@@ -17,6 +19,14 @@ pub use merkle_tree::{
     MerkleTreeKey,
 };
 pub use primitive::Primitive;
+pub use proof::{
+    ExclusionLeaf,
+    ExclusionLeafData,
+    ExclusionProof,
+    InclusionProof,
+    Proof,
+};
+pub use verify::verify;
 pub mod in_memory;
 
 use crate::common::Bytes32;
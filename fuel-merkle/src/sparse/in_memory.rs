@@ -7,6 +7,7 @@ use crate::{
         self,
         merkle_tree::MerkleTreeKey,
         Primitive,
+        Proof,
     },
     storage::{
         Mappable,
@@ -177,6 +178,10 @@ impl MerkleTree {
     pub fn root(&self) -> Bytes32 {
         self.tree.root()
     }
+
+    pub fn prove(&self, key: MerkleTreeKey) -> Proof {
+        self.tree.prove(key).expect("`Storage` can't return error")
+    }
 }
 
 impl Default for MerkleTree {
@@ -268,4 +273,28 @@ mod test {
             "39f36a7cb4dfb1b46f03d044265df6a491dffc1034121bc1071a34ddce9bb14b";
         assert_eq!(hex::encode(root), expected_root);
     }
+
+    #[test]
+    fn test_prove() {
+        let mut tree = MerkleTree::new();
+
+        tree.update(key(b"\x00\x00\x00\x00"), b"DATA");
+        tree.update(key(b"\x00\x00\x00\x01"), b"DATA");
+
+        let root = tree.root();
+        let proof = tree.prove(key(b"\x00\x00\x00\x00"));
+        assert!(sparse::verify(
+            &root,
+            key(b"\x00\x00\x00\x00"),
+            Some(b"DATA"),
+            &proof
+        ));
+        let proof = tree.prove(key(b"\x00\x00\x00\x02"));
+        assert!(sparse::verify(
+            &root,
+            key(b"\x00\x00\x00\x02"),
+            None,
+            &proof
+        ));
+    }
 }
@@ -8,6 +8,13 @@ use crate::{
     sparse::{
         empty_sum,
         primitive::Primitive,
+        proof::{
+            ExclusionLeaf,
+            ExclusionLeafData,
+            ExclusionProof,
+            InclusionProof,
+            Proof,
+        },
         Node,
         StorageNode,
         StorageNodeError,
@@ -174,6 +181,42 @@ where
         }
     }
 
+    /// Generate a proof for the given key. If the key is present in the tree, this
+    /// is an inclusion proof; otherwise, it is an exclusion proof. The proof can be
+    /// checked against the root with [`verify`](crate::sparse::verify).
+    pub fn prove(
+        &self,
+        key: MerkleTreeKey,
+    ) -> Result<Proof, MerkleTreeError<StorageError>> {
+        let key = key.into();
+        let (path_nodes, side_nodes) = self.path_set(key)?;
+        let proof_set = side_nodes.iter().map(|node| *node.hash()).collect();
+
+        // The first path node is the node found at the position where the requested
+        // leaf would be located.
+        let actual_leaf = path_nodes
+            .first()
+            .expect("The path always contains at least the root");
+        let proof = if actual_leaf.is_placeholder() {
+            Proof::Exclusion(ExclusionProof {
+                proof_set,
+                leaf: ExclusionLeaf::Placeholder,
+            })
+        } else if actual_leaf.leaf_key() == &key {
+            Proof::Inclusion(InclusionProof { proof_set })
+        } else {
+            Proof::Exclusion(ExclusionProof {
+                proof_set,
+                leaf: ExclusionLeaf::Leaf(ExclusionLeafData {
+                    leaf_key: *actual_leaf.leaf_key(),
+                    leaf_value: *actual_leaf.leaf_data(),
+                }),
+            })
+        };
+
+        Ok(proof)
+    }
+
     // PRIVATE
 
     fn path_set(
@@ -0,0 +1,64 @@
+use crate::common::{
+    Bytes32,
+    ProofSet,
+};
+
+/// A proof that a key is either present in or absent from a sparse Merkle tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Proof {
+    Inclusion(InclusionProof),
+    Exclusion(ExclusionProof),
+}
+
+impl Proof {
+    pub fn proof_set(&self) -> &ProofSet {
+        match self {
+            Proof::Inclusion(proof) => &proof.proof_set,
+            Proof::Exclusion(proof) => &proof.proof_set,
+        }
+    }
+
+    pub fn is_inclusion(&self) -> bool {
+        matches!(self, Proof::Inclusion(_))
+    }
+
+    pub fn is_exclusion(&self) -> bool {
+        matches!(self, Proof::Exclusion(_))
+    }
+}
+
+/// Proves that a key is present in the tree. The proof set contains the side nodes
+/// of the path from the leaf to the root, ordered from the leaf up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InclusionProof {
+    pub proof_set: ProofSet,
+}
+
+/// Proves that a key is absent from the tree. The proof set contains the side nodes
+/// of the path from the position where the key would be located to the root, ordered
+/// from the bottom up. The position is occupied by either a placeholder or a leaf
+/// with a different key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExclusionProof {
+    pub proof_set: ProofSet,
+    pub leaf: ExclusionLeaf,
+}
+
+/// The node occupying the position of an excluded key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExclusionLeaf {
+    Leaf(ExclusionLeafData),
+    Placeholder,
+}
+
+/// The key and the hashed value of a leaf occupying the position of an excluded key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExclusionLeafData {
+    pub leaf_key: Bytes32,
+    pub leaf_value: Bytes32,
+}
@@ -0,0 +1,217 @@
+use crate::{
+    common::{
+        path::{
+            Instruction,
+            Path,
+        },
+        Bytes32,
+        Prefix,
+    },
+    sparse::{
+        proof::{
+            ExclusionLeaf,
+            Proof,
+        },
+        zero_sum,
+        MerkleTreeKey,
+        Node,
+    },
+};
+
+/// Verify a sparse Merkle tree proof for `key` against `root`.
+///
+/// Passing `Some(value)` checks that the key is present in the tree with the given
+/// value, and requires an inclusion proof. Passing `None` checks that the key is
+/// absent from the tree, and requires an exclusion proof.
+pub fn verify(
+    root: &Bytes32,
+    key: MerkleTreeKey,
+    value: Option<&[u8]>,
+    proof: &Proof,
+) -> bool {
+    let key: Bytes32 = key.into();
+
+    let leaf_hash = match (proof, value) {
+        (Proof::Inclusion(_), Some(value)) => *Node::create_leaf(&key, value).hash(),
+        (Proof::Exclusion(proof), None) => match &proof.leaf {
+            ExclusionLeaf::Placeholder => *zero_sum(),
+            ExclusionLeaf::Leaf(leaf) if leaf.leaf_key != key => {
+                *Node::new(0, Prefix::Leaf, leaf.leaf_key, leaf.leaf_value).hash()
+            }
+            // A leaf with the same key proves inclusion, not exclusion.
+            ExclusionLeaf::Leaf(_) => return false,
+        },
+        _ => return false,
+    };
+
+    let proof_set = proof.proof_set();
+    if proof_set.len() > Node::max_height() {
+        return false
+    }
+
+    // The proof set is ordered from the leaf up, so the side node at index `i` is the
+    // sibling of the path node at depth `len - 1 - i`.
+    let mut current = leaf_hash;
+    for (i, side) in proof_set.iter().enumerate() {
+        let depth = proof_set.len() - 1 - i;
+        let (left, right) = match key.get_instruction(depth) {
+            Some(Instruction::Left) => (current, *side),
+            Some(Instruction::Right) => (*side, current),
+            None => return false,
+        };
+        current = *Node::new(0, Prefix::Node, left, right).hash();
+    }
+
+    current == *root
+}
+
+#[cfg(test)]
+mod test {
+    use super::verify;
+    use crate::{
+        common::{
+            Bytes32,
+            StorageMap,
+        },
+        sparse::{
+            hash::sum,
+            proof::{
+                ExclusionLeaf,
+                ExclusionProof,
+                InclusionProof,
+                Proof,
+            },
+            MerkleTree,
+            MerkleTreeKey,
+            Primitive,
+        },
+    };
+    use fuel_storage::Mappable;
+    use rand::{
+        prelude::StdRng,
+        Rng,
+        SeedableRng,
+    };
+
+    #[derive(Debug)]
+    struct TestTable;
+
+    impl Mappable for TestTable {
+        type Key = Self::OwnedKey;
+        type OwnedKey = Bytes32;
+        type OwnedValue = Primitive;
+        type Value = Self::OwnedValue;
+    }
+
+    fn key<B: AsRef<[u8]>>(data: B) -> MerkleTreeKey {
+        MerkleTreeKey::new_without_hash(sum(data.as_ref()))
+    }
+
+    #[test]
+    fn verify_returns_true_for_inclusion_proofs_of_present_keys() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+
+        for i in 0_u32..20 {
+            tree.update(key(i.to_be_bytes()), b"DATA").unwrap();
+        }
+        let root = tree.root();
+
+        for i in 0_u32..20 {
+            let proof = tree.prove(key(i.to_be_bytes())).unwrap();
+            assert!(proof.is_inclusion());
+            assert!(verify(&root, key(i.to_be_bytes()), Some(b"DATA"), &proof));
+            assert!(!verify(&root, key(i.to_be_bytes()), Some(b"OTHER"), &proof));
+            assert!(!verify(&root, key(i.to_be_bytes()), None, &proof));
+        }
+    }
+
+    #[test]
+    fn verify_returns_true_for_exclusion_proofs_of_absent_keys() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+
+        for i in 0_u32..20 {
+            tree.update(key(i.to_be_bytes()), b"DATA").unwrap();
+        }
+        let root = tree.root();
+
+        for i in 20_u32..40 {
+            let proof = tree.prove(key(i.to_be_bytes())).unwrap();
+            assert!(proof.is_exclusion());
+            assert!(verify(&root, key(i.to_be_bytes()), None, &proof));
+            assert!(!verify(&root, key(i.to_be_bytes()), Some(b"DATA"), &proof));
+            // The proof is bound to the key it was generated for.
+            assert!(!verify(&root, key(0_u32.to_be_bytes()), None, &proof));
+        }
+    }
+
+    #[test]
+    fn verify_returns_true_for_proofs_from_trees_built_from_a_set() {
+        let rng = &mut StdRng::seed_from_u64(8586);
+        let set = (0..100)
+            .map(|_| (key(rng.gen::<Bytes32>()), rng.gen::<Bytes32>()))
+            .collect::<Vec<_>>();
+
+        let mut storage = StorageMap::<TestTable>::new();
+        let tree = MerkleTree::from_set(&mut storage, set.iter().cloned()).unwrap();
+        let root = tree.root();
+
+        for (k, v) in set.iter() {
+            let proof = tree.prove(*k).unwrap();
+            assert!(verify(&root, *k, Some(v), &proof));
+        }
+
+        let absent = key(rng.gen::<Bytes32>());
+        let proof = tree.prove(absent).unwrap();
+        assert!(verify(&root, absent, None, &proof));
+    }
+
+    #[test]
+    fn verify_returns_true_for_exclusion_proofs_of_empty_and_single_leaf_trees() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+
+        let proof = tree.prove(key(b"\x00")).unwrap();
+        assert_eq!(
+            proof,
+            Proof::Exclusion(ExclusionProof {
+                proof_set: vec![],
+                leaf: ExclusionLeaf::Placeholder,
+            })
+        );
+        assert!(verify(&tree.root(), key(b"\x00"), None, &proof));
+
+        tree.update(key(b"\x01"), b"DATA").unwrap();
+        let proof = tree.prove(key(b"\x00")).unwrap();
+        assert!(matches!(
+            &proof,
+            Proof::Exclusion(ExclusionProof {
+                leaf: ExclusionLeaf::Leaf(_),
+                ..
+            })
+        ));
+        assert!(verify(&tree.root(), key(b"\x00"), None, &proof));
+
+        let proof = tree.prove(key(b"\x01")).unwrap();
+        assert_eq!(
+            proof,
+            Proof::Inclusion(InclusionProof { proof_set: vec![] })
+        );
+        assert!(verify(&tree.root(), key(b"\x01"), Some(b"DATA"), &proof));
+    }
+
+    #[test]
+    fn verify_returns_false_for_exclusion_proofs_after_the_key_is_inserted() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+
+        for i in 0_u32..10 {
+            tree.update(key(i.to_be_bytes()), b"DATA").unwrap();
+        }
+        let proof = tree.prove(key(b"NEW")).unwrap();
+
+        tree.update(key(b"NEW"), b"DATA").unwrap();
+        assert!(!verify(&tree.root(), key(b"NEW"), None, &proof));
+    }
+}
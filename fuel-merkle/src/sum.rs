@@ -1,6 +1,7 @@
 mod hash;
 mod merkle_tree;
 mod node;
+mod verify;
 
 pub(crate) use hash::{
     empty_sum,
@@ -12,3 +13,10 @@ pub use merkle_tree::{
     MerkleTreeError,
 };
 pub(crate) use node::Node;
+pub use verify::verify;
+
+use crate::common::Bytes32;
+use alloc::vec::Vec;
+
+/// The `(fee, digest)` pairs of the side nodes of a sum Merkle tree proof.
+pub type ProofSet = Vec<(u64, Bytes32)>;
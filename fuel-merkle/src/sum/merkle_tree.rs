@@ -6,6 +6,7 @@ use crate::{
     sum::{
         empty_sum,
         Node,
+        ProofSet,
     },
 };
 
@@ -14,12 +15,28 @@ use fuel_storage::{
     StorageMutate,
 };
 
+use alloc::vec::Vec;
 use core::marker::PhantomData;
 
 #[derive(Debug, Clone, derive_more::Display)]
-pub enum MerkleTreeError {
+pub enum MerkleTreeError<StorageError> {
     #[display(fmt = "proof index {_0} is not valid")]
     InvalidProofIndex(u64),
+
+    #[display(
+        fmt = "cannot load node with key {}; the key is not found in storage",
+        "hex::encode(_0)"
+    )]
+    LoadError(Bytes32),
+
+    #[display(fmt = "{}", _0)]
+    StorageError(StorageError),
+}
+
+impl<StorageError> From<StorageError> for MerkleTreeError<StorageError> {
+    fn from(err: StorageError) -> MerkleTreeError<StorageError> {
+        MerkleTreeError::StorageError(err)
+    }
 }
 
 /// The Binary Merkle Sum Tree is an extension to the existing Binary
@@ -95,9 +112,105 @@ where
         Ok(())
     }
 
+    pub fn leaves_count(&self) -> u64 {
+        let mut count = 0;
+        let mut current = self.head.as_ref();
+        while let Some(subtree) = current {
+            count += 1 << subtree.node().height();
+            current = subtree.next();
+        }
+        count
+    }
+
+    /// Generate a proof for the leaf at `proof_index`. The proof set contains the
+    /// `(fee, digest)` pairs of the side nodes on the path from the leaf to the root,
+    /// ordered from the leaf up. It can be checked against the returned root with
+    /// [`verify`](crate::sum::verify).
+    pub fn prove(
+        &mut self,
+        proof_index: u64,
+    ) -> Result<((u64, Bytes32), ProofSet), MerkleTreeError<StorageError>> {
+        // Collect the MMR peaks, ordered from left to right.
+        let mut peaks = Vec::new();
+        let mut current = self.head.as_ref();
+        while let Some(subtree) = current {
+            peaks.push(subtree.node().clone());
+            current = subtree.next();
+        }
+        peaks.reverse();
+
+        // Find the peak whose subtree contains the leaf.
+        let mut peak_start = 0u64;
+        let peak_index = peaks
+            .iter()
+            .position(|peak| {
+                let peak_end = peak_start + (1 << peak.height());
+                let contains = proof_index < peak_end;
+                if !contains {
+                    peak_start = peak_end;
+                }
+                contains
+            })
+            .ok_or(MerkleTreeError::InvalidProofIndex(proof_index))?;
+
+        // Descend from the peak to the leaf, collecting the side nodes. The leaf's
+        // index within the peak encodes the path, one bit per level.
+        let mut proof_set = ProofSet::new();
+        let leaf_index = proof_index - peak_start;
+        let mut node = peaks[peak_index].clone();
+        while node.is_node() {
+            let left_key = node.left_child_key().expect("Node must have a left child");
+            let right_key = node
+                .right_child_key()
+                .expect("Node must have a right child");
+            let (path_key, side_key) = if leaf_index & (1 << (node.height() - 1)) == 0 {
+                (left_key, right_key)
+            } else {
+                (right_key, left_key)
+            };
+            let side_node = self.load_node(&side_key)?;
+            proof_set.push((side_node.fee(), *side_node.hash()));
+            node = self.load_node(&path_key)?;
+        }
+        proof_set.reverse(); // Reorder side nodes from leaf to peak.
+
+        // The peaks to the right of the leaf's peak are joined into a single right
+        // side node, the same way they are joined when building the root.
+        if let Some((last, rest)) = peaks[peak_index + 1..].split_last() {
+            let mut right = last.clone();
+            for peak in rest.iter().rev() {
+                right = Node::create_node(
+                    peak.height() + 1,
+                    peak.fee(),
+                    peak.hash(),
+                    right.fee(),
+                    right.hash(),
+                );
+            }
+            proof_set.push((right.fee(), *right.hash()));
+        }
+
+        // Each peak to the left of the leaf's peak is a left side node.
+        for peak in peaks[..peak_index].iter().rev() {
+            proof_set.push((peak.fee(), *peak.hash()));
+        }
+
+        let root = self.root()?;
+        Ok((root, proof_set))
+    }
+
     // PRIVATE
     //
 
+    fn load_node(&self, key: &Bytes32) -> Result<Node, MerkleTreeError<StorageError>> {
+        let node = self
+            .storage
+            .get(key)?
+            .ok_or(MerkleTreeError::LoadError(*key))?
+            .into_owned();
+        Ok(node)
+    }
+
     fn root_node(&mut self) -> Result<Option<Node>, StorageError> {
         let root_node = match self.head {
             None => None,
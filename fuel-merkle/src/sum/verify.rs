@@ -0,0 +1,190 @@
+use crate::{
+    common::Bytes32,
+    sum::{
+        leaf_sum,
+        node_sum,
+        ProofSet,
+    },
+};
+
+/// Verify that the leaf with the given `fee` and `data` is at `proof_index` in a
+/// sum Merkle tree of `num_leaves` leaves with the given `(fee, digest)` root.
+pub fn verify<T: AsRef<[u8]>>(
+    root: &(u64, Bytes32),
+    fee: u64,
+    data: &T,
+    proof_set: &ProofSet,
+    proof_index: u64,
+    num_leaves: u64,
+) -> bool {
+    let mut sum = (fee, leaf_sum(fee, data.as_ref()));
+
+    if proof_index >= num_leaves {
+        return false
+    }
+
+    if proof_set.is_empty() {
+        return if num_leaves == 1 { *root == sum } else { false }
+    }
+
+    let join = |lhs: (u64, Bytes32), rhs: (u64, Bytes32)| {
+        let fee = lhs.0.checked_add(rhs.0)?;
+        Some((fee, node_sum(lhs.0, &lhs.1, rhs.0, &rhs.1)))
+    };
+
+    let mut height = 1usize;
+    let mut stable_end = proof_index;
+
+    loop {
+        let subtree_start_index = proof_index / (1 << height) * (1 << height);
+        let subtree_end_index = subtree_start_index + (1 << height) - 1;
+
+        if subtree_end_index >= num_leaves {
+            break
+        }
+
+        stable_end = subtree_end_index;
+
+        if proof_set.len() < height {
+            return false
+        }
+
+        let proof_data = proof_set[height - 1];
+        let joined = if proof_index - subtree_start_index < 1 << (height - 1) {
+            join(sum, proof_data)
+        } else {
+            join(proof_data, sum)
+        };
+        let Some(joined) = joined else { return false };
+        sum = joined;
+
+        height += 1;
+    }
+
+    if stable_end != num_leaves - 1 {
+        if proof_set.len() < height {
+            return false
+        }
+        let proof_data = proof_set[height - 1];
+        let Some(joined) = join(sum, proof_data) else {
+            return false
+        };
+        sum = joined;
+        height += 1;
+    }
+
+    while height - 1 < proof_set.len() {
+        let proof_data = proof_set[height - 1];
+        let Some(joined) = join(proof_data, sum) else {
+            return false
+        };
+        sum = joined;
+        height += 1;
+    }
+
+    sum == *root
+}
+
+#[cfg(test)]
+mod test {
+    use super::verify;
+    use crate::{
+        common::{
+            Bytes32,
+            StorageMap,
+        },
+        sum::{
+            MerkleTree,
+            MerkleTreeError,
+            Node,
+        },
+    };
+    use fuel_merkle_test_helpers::TEST_DATA;
+    use fuel_storage::Mappable;
+
+    #[derive(Debug)]
+    struct TestTable;
+
+    impl Mappable for TestTable {
+        type Key = Self::OwnedKey;
+        type OwnedKey = Bytes32;
+        type OwnedValue = Node;
+        type Value = Self::OwnedValue;
+    }
+
+    fn fee(index: usize) -> u64 {
+        100 + index as u64
+    }
+
+    #[test]
+    fn verify_returns_true_for_proofs_of_all_leaves() {
+        for num_leaves in 1..=TEST_DATA.len() {
+            let mut storage_map = StorageMap::<TestTable>::new();
+            let mut tree = MerkleTree::new(&mut storage_map);
+
+            let data = &TEST_DATA[0..num_leaves];
+            for (i, datum) in data.iter().enumerate() {
+                tree.push(fee(i), datum).unwrap();
+            }
+
+            for (i, datum) in data.iter().enumerate() {
+                let (root, proof_set) = tree.prove(i as u64).unwrap();
+                assert_eq!(root, tree.root().unwrap());
+                assert!(verify(
+                    &root,
+                    fee(i),
+                    datum,
+                    &proof_set,
+                    i as u64,
+                    num_leaves as u64
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn verify_returns_false_for_wrong_fee_data_or_index() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        let data = &TEST_DATA[0..7];
+        for (i, datum) in data.iter().enumerate() {
+            tree.push(fee(i), datum).unwrap();
+        }
+
+        let (root, proof_set) = tree.prove(2).unwrap();
+        assert!(verify(&root, fee(2), &data[2], &proof_set, 2, 7));
+        assert!(!verify(&root, fee(2) + 1, &data[2], &proof_set, 2, 7));
+        assert!(!verify(&root, fee(2), &data[3], &proof_set, 2, 7));
+        assert!(!verify(&root, fee(2), &data[2], &proof_set, 3, 7));
+        assert!(!verify(&root, fee(2), &data[2], &proof_set, 2, 4));
+        assert!(!verify(&root, fee(2), &data[2], &proof_set, 7, 7));
+    }
+
+    #[test]
+    fn verify_returns_false_when_the_fee_sum_overflows() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        tree.push(fee(0), TEST_DATA[0]).unwrap();
+        tree.push(fee(1), TEST_DATA[1]).unwrap();
+
+        let (root, mut proof_set) = tree.prove(0).unwrap();
+        proof_set[0].0 = u64::MAX;
+        assert!(!verify(&root, fee(0), &TEST_DATA[0], &proof_set, 0, 2));
+    }
+
+    #[test]
+    fn prove_returns_invalid_proof_index_error_for_index_out_of_bounds() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        for (i, datum) in TEST_DATA[0..5].iter().enumerate() {
+            tree.push(fee(i), datum).unwrap();
+        }
+
+        assert_eq!(tree.leaves_count(), 5);
+        let err = tree.prove(5).expect_err("Expected prove() to return Error");
+        assert!(matches!(err, MerkleTreeError::InvalidProofIndex(5)));
+    }
+}
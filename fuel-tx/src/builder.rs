@@ -24,6 +24,7 @@ use crate::{
     ScriptParameters,
     StorageSlot,
     Transaction,
+    TransactionFee,
     TxParameters,
     TxPointer,
     Witness,
@@ -34,8 +35,13 @@ use crate::{
     Signable,
 };
 
-use fuel_crypto::SecretKey;
+use fuel_crypto::{
+    Message,
+    SecretKey,
+    Signature,
+};
 use fuel_types::{
+    Address,
     AssetId,
     BlockHeight,
     ChainId,
@@ -45,10 +51,35 @@ use fuel_types::{
 };
 
 use alloc::{
-    collections::BTreeMap,
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    sync::Arc,
     vec::Vec,
 };
 
+mod coin_selection;
+mod signer;
+
+pub use coin_selection::{
+    BranchAndBound,
+    CoinSelectionError,
+    CoinSelectionStrategy,
+    LargestFirst,
+    Spendable,
+};
+
+use signer::{
+    verify,
+    AnySigner,
+};
+pub use signer::{
+    AsyncSigner,
+    Signer,
+    SignerError,
+};
+
 pub trait BuildableAloc
 where
     Self: Default
@@ -124,6 +155,12 @@ pub struct TransactionBuilder<Tx> {
     // zeroize the keys
     // Maps signing keys -> witness indexes
     sign_keys: BTreeMap<SecretKey, u8>,
+
+    // Maps owners of signed inputs -> witness indexes
+    owners: BTreeMap<Address, u8>,
+
+    // Maps owners -> signers
+    signers: BTreeMap<Address, AnySigner>,
 }
 
 impl TransactionBuilder<Script> {
@@ -207,6 +244,8 @@ impl<Tx> TransactionBuilder<Tx> {
         let should_prepare_script = false;
         let should_prepare_predicate = false;
         let sign_keys = BTreeMap::new();
+        let owners = BTreeMap::new();
+        let signers = BTreeMap::new();
 
         Self {
             tx,
@@ -214,6 +253,8 @@ impl<Tx> TransactionBuilder<Tx> {
             should_prepare_predicate,
             params: ConsensusParameters::standard(),
             sign_keys,
+            owners,
+            signers,
         }
     }
 
@@ -398,6 +439,77 @@ impl<Tx: Buildable> TransactionBuilder<Tx> {
         self
     }
 
+    /// Adds a signer for the signed inputs of its owner.
+    pub fn add_signer<S>(&mut self, signer: S) -> &mut Self
+    where
+        S: Signer + Send + Sync + 'static,
+    {
+        self.signers
+            .insert(signer.owner(), AnySigner::Sync(Arc::new(signer)));
+
+        self
+    }
+
+    /// Adds an asynchronous signer for the signed inputs of its owner. The transaction
+    /// has to be finalized with [`Self::finalize_async`].
+    pub fn add_async_signer<S>(&mut self, signer: S) -> &mut Self
+    where
+        S: AsyncSigner + Send + Sync + 'static,
+    {
+        self.signers
+            .insert(signer.owner(), AnySigner::Async(Arc::new(signer)));
+
+        self
+    }
+
+    /// Append a coin input of `owner`, signed by its signer.
+    pub fn add_unsigned_owner_coin_input(
+        &mut self,
+        owner: Address,
+        utxo_id: crate::UtxoId,
+        amount: Word,
+        asset_id: fuel_types::AssetId,
+        tx_pointer: TxPointer,
+        maturity: BlockHeight,
+    ) -> &mut Self {
+        let witness_index = self.upsert_owner(owner);
+
+        self.tx.add_input(Input::coin_signed(
+            utxo_id,
+            owner,
+            amount,
+            asset_id,
+            tx_pointer,
+            witness_index,
+            maturity,
+        ));
+
+        self
+    }
+
+    /// Append a message input to `recipient`, signed by its signer.
+    pub fn add_unsigned_owner_message_input(
+        &mut self,
+        recipient: Address,
+        sender: fuel_types::Address,
+        nonce: Nonce,
+        amount: Word,
+        data: Vec<u8>,
+    ) -> &mut Self {
+        let witness_index = self.upsert_owner(recipient);
+
+        self.tx.add_unsigned_message_input(
+            sender,
+            recipient,
+            nonce,
+            amount,
+            data,
+            witness_index,
+        );
+
+        self
+    }
+
     pub fn inputs(&self) -> &[Input] {
         self.tx.inputs()
     }
@@ -422,12 +534,224 @@ impl<Tx: Buildable> TransactionBuilder<Tx> {
         self
     }
 
+    /// Select inputs among the spendable coins and messages to cover the coin outputs
+    /// and the max fee, and add a change output to `change_owner` for every asset
+    /// without one.
+    ///
+    /// The selection is repeated until the fee, growing with the inputs, is covered.
+    /// The selected inputs are signed on finalization by the secret keys or the signers
+    /// of their owners. The builder is left untouched if the funds are insufficient, or
+    /// if covering them takes more inputs than the transaction can take.
+    pub fn select_coins<S>(
+        &mut self,
+        spendables: &[Spendable],
+        change_owner: Address,
+        strategy: &S,
+    ) -> Result<&mut Self, CoinSelectionError>
+    where
+        S: CoinSelectionStrategy + ?Sized,
+    {
+        let base_asset_id = self.params.base_asset_id;
+        let max_inputs = self.get_tx_params().max_inputs;
+
+        let mut required = BTreeMap::<AssetId, Word>::from([(base_asset_id, 0)]);
+        for output in self.outputs() {
+            if let Output::Coin {
+                asset_id, amount, ..
+            } = output
+            {
+                let entry = required.entry(*asset_id).or_default();
+                *entry = entry
+                    .checked_add(*amount)
+                    .ok_or(CoinSelectionError::ArithmeticOverflow)?;
+            }
+        }
+
+        // Data messages aren't spendable, so only coins and message coins are counted
+        let mut provided = BTreeMap::<AssetId, Word>::new();
+        for input in self.inputs() {
+            if input.is_message_data_signed() || input.is_message_data_predicate() {
+                continue
+            }
+            if let (Some(asset_id), Some(amount)) =
+                (input.asset_id(&base_asset_id), input.amount())
+            {
+                let entry = provided.entry(*asset_id).or_default();
+                *entry = entry
+                    .checked_add(amount)
+                    .ok_or(CoinSelectionError::ArithmeticOverflow)?;
+            }
+        }
+
+        let mut balanced = self.clone();
+
+        let assets: BTreeSet<_> = required.keys().chain(provided.keys()).collect();
+        for asset_id in assets {
+            let has_change = balanced.outputs().iter().any(|output| {
+                matches!(output, Output::Change { asset_id: change, .. } if change == asset_id)
+            });
+            if !has_change {
+                balanced.add_output(Output::change(change_owner, 0, *asset_id));
+            }
+        }
+
+        let candidates: Vec<_> = spendables
+            .iter()
+            .filter(|spendable| !spendable.is_spent_by(self.inputs()))
+            .collect();
+
+        let mut fee = 0;
+        loop {
+            let mut selected = balanced.clone();
+
+            for (asset_id, amount) in &required {
+                let mut target = *amount;
+                if *asset_id == base_asset_id {
+                    target = target
+                        .checked_add(fee)
+                        .ok_or(CoinSelectionError::ArithmeticOverflow)?;
+                }
+                let target = target
+                    .saturating_sub(provided.get(asset_id).copied().unwrap_or_default());
+                if target == 0 {
+                    continue
+                }
+
+                let group: Vec<_> = candidates
+                    .iter()
+                    .filter(|spendable| spendable.asset_id(&base_asset_id) == asset_id)
+                    .collect();
+                let amounts: Vec<_> = group.iter().map(|s| s.amount()).collect();
+
+                let insufficient_funds = || CoinSelectionError::InsufficientFunds {
+                    asset_id: *asset_id,
+                    required: target,
+                    available: amounts
+                        .iter()
+                        .fold(0, |sum: Word, amount| sum.saturating_add(*amount)),
+                };
+                let available_inputs = usize::try_from(max_inputs)
+                    .unwrap_or(usize::MAX)
+                    .saturating_sub(selected.inputs().len());
+                let too_many_inputs = || CoinSelectionError::TooManyInputs {
+                    asset_id: *asset_id,
+                    available_inputs,
+                    max_inputs,
+                };
+
+                // The largest amounts tell whether the funds can fit in the inputs left
+                let mut largest = amounts.clone();
+                largest.sort_unstable_by(|a, b| b.cmp(a));
+                let total: u128 = largest.iter().map(|amount| *amount as u128).sum();
+                let reachable: u128 = largest
+                    .iter()
+                    .take(available_inputs)
+                    .map(|amount| *amount as u128)
+                    .sum();
+                if total < target as u128 {
+                    return Err(insufficient_funds())
+                }
+                if reachable < target as u128 {
+                    return Err(too_many_inputs())
+                }
+
+                // The selection of the strategy is checked before it's used
+                let indexes: BTreeSet<_> = strategy
+                    .select(&amounts, target, available_inputs)
+                    .ok_or_else(insufficient_funds)?
+                    .into_iter()
+                    .collect();
+                if let Some(index) = indexes.iter().find(|index| **index >= group.len()) {
+                    return Err(CoinSelectionError::InvalidSelection(*index))
+                }
+                if indexes.len() > available_inputs {
+                    return Err(too_many_inputs())
+                }
+                let sum: u128 = indexes.iter().map(|index| amounts[*index] as u128).sum();
+                if sum < target as u128 {
+                    return Err(insufficient_funds())
+                }
+
+                for index in indexes {
+                    selected.add_spendable(group[index]);
+                }
+            }
+
+            let selected_fee = selected
+                .estimate_max_fee()
+                .ok_or(CoinSelectionError::ArithmeticOverflow)?;
+            if selected_fee <= fee {
+                *self = selected;
+                return Ok(self)
+            }
+
+            fee = selected_fee;
+        }
+    }
+
+    fn add_spendable(&mut self, spendable: &Spendable) {
+        match spendable {
+            Spendable::Coin {
+                utxo_id,
+                owner,
+                amount,
+                asset_id,
+                tx_pointer,
+                maturity,
+            } => self.add_unsigned_owner_coin_input(
+                *owner,
+                *utxo_id,
+                *amount,
+                *asset_id,
+                *tx_pointer,
+                *maturity,
+            ),
+            Spendable::MessageCoin {
+                sender,
+                recipient,
+                nonce,
+                amount,
+            } => self.add_unsigned_owner_message_input(
+                *recipient,
+                *sender,
+                *nonce,
+                *amount,
+                Vec::new(),
+            ),
+        };
+    }
+
+    /// The max fee of the transaction once signed, the size of the signatures included.
+    fn estimate_max_fee(&self) -> Option<Word> {
+        let mut tx = self.tx.clone();
+
+        for witness_index in self.owners.values() {
+            if let Some(witness) = tx.witnesses_mut().get_mut(*witness_index as usize) {
+                if witness.as_ref().is_empty() {
+                    *witness = Signature::default().as_ref().into();
+                }
+            }
+        }
+
+        TransactionFee::checked_from_tx(self.get_fee_params(), &tx)
+            .map(|fee| fee.max_fee())
+    }
+
     /// Adds a secret to the builder, and adds a corresponding witness if it's a new entry
     fn upsert_secret(&mut self, secret_key: SecretKey) -> u8 {
+        let owner = Input::owner(&secret_key.public_key());
+        let witness_index = self.upsert_owner(owner);
+
+        self.sign_keys.insert(secret_key, witness_index);
+        witness_index
+    }
+
+    /// Adds a witness for the owner if it's a new entry
+    fn upsert_owner(&mut self, owner: Address) -> u8 {
         let witness_len = self.witnesses().len() as u8;
 
-        let witness_index = self.sign_keys.entry(secret_key).or_insert_with(|| {
-            // if this private key hasn't been used before,
+        let witness_index = self.owners.entry(owner).or_insert_with(|| {
+            // if this owner hasn't been used before,
             // add a new witness entry and return its index
             self.tx.witnesses_mut().push(Witness::default());
             witness_len
@@ -445,15 +769,82 @@ impl<Tx: Buildable> TransactionBuilder<Tx> {
         }
     }
 
+    /// Backs [`Finalizable::finalize`], panicking where [`Self::try_finalize`] fails.
     fn finalize_inner(&mut self) -> Tx {
+        self.try_finalize()
+            .expect("The signers should be able to sign the transaction")
+    }
+
+    /// Finalize the transaction, signing it with the secret keys and the signers.
+    ///
+    /// The builder is left untouched if a signer fails.
+    pub fn try_finalize(&mut self) -> Result<Tx, SignerError> {
+        let (mut tx, message) = self.prepare_signing()?;
+
+        for (owner, witness_index, signer) in self.owner_signers() {
+            let signature = signer.sign(owner, &message)?;
+            set_signature(&mut tx, owner, witness_index, signature, &message)?;
+        }
+
+        Ok(self.finish_signing(tx))
+    }
+
+    /// Finalize the transaction, signing it with the secret keys and the signers,
+    /// including the asynchronous ones.
+    ///
+    /// The builder is left untouched if a signer fails.
+    pub async fn finalize_async(&mut self) -> Result<Tx, SignerError> {
+        let (mut tx, message) = self.prepare_signing()?;
+
+        for (owner, witness_index, signer) in self.owner_signers() {
+            let signature = signer.sign_async(&message).await?;
+            set_signature(&mut tx, owner, witness_index, signature, &message)?;
+        }
+
+        Ok(self.finish_signing(tx))
+    }
+
+    /// Returns a copy of the prepared transaction signed with the secret keys, and the
+    /// message to sign.
+    fn prepare_signing(&mut self) -> Result<(Tx, Message), SignerError> {
         self.prepare_finalize();
 
-        let mut tx = core::mem::take(&mut self.tx);
+        let key_owners: Vec<_> = self
+            .sign_keys
+            .keys()
+            .map(|k| Input::owner(&k.public_key()))
+            .collect();
+        if let Some(owner) = self.owners.keys().find(|&owner| {
+            !key_owners.contains(owner) && !self.signers.contains_key(owner)
+        }) {
+            return Err(SignerError::MissingSigner(*owner))
+        }
+
+        let mut tx = self.tx.clone();
 
         self.sign_keys
             .iter()
             .for_each(|(k, _)| tx.sign_inputs(k, &self.get_chain_id()));
 
+        let message = Message::from_bytes(*tx.id(&self.get_chain_id()));
+
+        Ok((tx, message))
+    }
+
+    /// The signers of the owners of the signed inputs, with their witness indexes.
+    fn owner_signers(&self) -> Vec<(Address, u8, AnySigner)> {
+        self.owners
+            .iter()
+            .filter_map(|(owner, witness_index)| {
+                let signer = self.signers.get(owner)?;
+                Some((*owner, *witness_index, signer.clone()))
+            })
+            .collect()
+    }
+
+    fn finish_signing(&mut self, mut tx: Tx) -> Tx {
+        self.tx = Default::default();
+
         tx.precompute(&self.get_chain_id())
             .expect("Should be able to calculate cache");
 
@@ -472,6 +863,22 @@ impl<Tx: Buildable> TransactionBuilder<Tx> {
     }
 }
 
+fn set_signature<Tx: Buildable>(
+    tx: &mut Tx,
+    owner: Address,
+    witness_index: u8,
+    signature: Signature,
+    message: &Message,
+) -> Result<(), SignerError> {
+    let signature = verify(owner, signature, message)?;
+
+    if let Some(witness) = tx.witnesses_mut().get_mut(witness_index as usize) {
+        *witness = signature.as_ref().into();
+    }
+
+    Ok(())
+}
+
 impl<Tx: field::Outputs> TransactionBuilder<Tx> {
     pub fn add_output(&mut self, output: Output) -> &mut Self {
         self.tx.outputs_mut().push(output);
@@ -480,6 +887,13 @@ impl<Tx: field::Outputs> TransactionBuilder<Tx> {
 }
 
 pub trait Finalizable<Tx> {
+    /// Finalize the transaction, signing it with the secret keys and the signers.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if a signer fails, if an owner of signed inputs has no signer,
+    /// or if a signer is asynchronous. Use [`TransactionBuilder::try_finalize`] or
+    /// [`TransactionBuilder::finalize_async`] to handle these cases.
     fn finalize(&mut self) -> Tx;
 
     fn finalize_without_signature(&mut self) -> Tx;
@@ -523,6 +937,13 @@ where
     Self: Finalizable<Tx>,
     Transaction: From<Tx>,
 {
+    /// Finalize the transaction with [`Finalizable::finalize`], as a [`Transaction`].
+    ///
+    /// # Panics
+    ///
+    /// This will panic if a signer fails, if an owner of signed inputs has no signer,
+    /// or if a signer is asynchronous. Use [`TransactionBuilder::try_finalize`] or
+    /// [`TransactionBuilder::finalize_async`] to handle these cases.
     pub fn finalize_as_transaction(&mut self) -> Transaction {
         self.finalize().into()
     }
@@ -0,0 +1,307 @@
+use crate::{
+    Input,
+    TxPointer,
+    UtxoId,
+};
+
+use fuel_types::{
+    Address,
+    AssetId,
+    BlockHeight,
+    Nonce,
+    Word,
+};
+
+use alloc::{
+    vec,
+    vec::Vec,
+};
+
+/// Coin selection errors.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, derive_more::Display)]
+pub enum CoinSelectionError {
+    /// The spendable coins and messages don't cover the required amount of the asset.
+    #[display(
+        fmt = "Insufficient funds of {}: {} required, {} available",
+        asset_id,
+        required,
+        available
+    )]
+    InsufficientFunds {
+        /// The asset missing funds.
+        asset_id: AssetId,
+        /// The amount to select to cover the outputs and the fee.
+        required: Word,
+        /// The total amount of the spendable coins and messages of the asset.
+        available: Word,
+    },
+    /// The strategy selected an index out of the spendable amounts.
+    #[display(fmt = "The selected index {} is out of the spendable amounts", _0)]
+    InvalidSelection(usize),
+    /// The spendable coins and messages of the asset can't cover the required amount
+    /// within the inputs left in the transaction.
+    #[display(
+        fmt = "Covering {} takes more than the {} inputs left of {}",
+        asset_id,
+        available_inputs,
+        max_inputs
+    )]
+    TooManyInputs {
+        /// The asset missing inputs.
+        asset_id: AssetId,
+        /// The number of inputs the transaction can still take.
+        available_inputs: usize,
+        /// The maximum number of inputs of a transaction.
+        max_inputs: u64,
+    },
+    /// The required amounts or the fee overflow.
+    #[display(fmt = "Arithmetic overflow")]
+    ArithmeticOverflow,
+}
+
+/// A coin or a message the builder may spend to balance the transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Spendable {
+    /// An unspent coin.
+    Coin {
+        utxo_id: UtxoId,
+        owner: Address,
+        amount: Word,
+        asset_id: AssetId,
+        tx_pointer: TxPointer,
+        maturity: BlockHeight,
+    },
+    /// A message without data, spendable as a coin of the base asset.
+    MessageCoin {
+        sender: Address,
+        recipient: Address,
+        nonce: Nonce,
+        amount: Word,
+    },
+}
+
+impl Spendable {
+    /// The owner signing the input.
+    pub const fn owner(&self) -> &Address {
+        match self {
+            Self::Coin { owner, .. } => owner,
+            Self::MessageCoin { recipient, .. } => recipient,
+        }
+    }
+
+    /// The spendable amount.
+    pub const fn amount(&self) -> Word {
+        match self {
+            Self::Coin { amount, .. } | Self::MessageCoin { amount, .. } => *amount,
+        }
+    }
+
+    /// The asset of the spendable amount.
+    pub const fn asset_id<'a>(&'a self, base_asset_id: &'a AssetId) -> &'a AssetId {
+        match self {
+            Self::Coin { asset_id, .. } => asset_id,
+            Self::MessageCoin { .. } => base_asset_id,
+        }
+    }
+
+    /// Returns `true` if one of the inputs already spends it.
+    pub(super) fn is_spent_by(&self, inputs: &[Input]) -> bool {
+        inputs.iter().any(|input| match self {
+            Self::Coin { utxo_id, .. } => input.utxo_id() == Some(utxo_id),
+            Self::MessageCoin { nonce, .. } => input.nonce() == Some(nonce),
+        })
+    }
+}
+
+/// Chooses the amounts to spend to cover a target.
+pub trait CoinSelectionStrategy {
+    /// Returns the indexes of at most `max_count` amounts adding up to at least
+    /// `target`, or `None` if they can't cover it. Selections with indexes out of
+    /// `amounts` or with more than `max_count` indexes are rejected.
+    fn select(
+        &self,
+        amounts: &[Word],
+        target: Word,
+        max_count: usize,
+    ) -> Option<Vec<usize>>;
+}
+
+/// Spends the largest amounts first, minimizing the number of inputs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LargestFirst;
+
+impl CoinSelectionStrategy for LargestFirst {
+    fn select(
+        &self,
+        amounts: &[Word],
+        target: Word,
+        max_count: usize,
+    ) -> Option<Vec<usize>> {
+        let mut selected = Vec::new();
+        let mut sum = 0u128;
+
+        for index in sorted_by_amount(amounts) {
+            if sum >= target as u128 || selected.len() == max_count {
+                break
+            }
+
+            sum += amounts[index] as u128;
+            selected.push(index);
+        }
+
+        (sum >= target as u128).then_some(selected)
+    }
+}
+
+/// Searches for amounts covering the target with at most `cost_of_change` in excess,
+/// avoiding the change. Falls back to [`LargestFirst`] if no such amounts are found
+/// within `max_tries` steps.
+///
+/// The search keeps its pending branches on the heap, so the number of amounts
+/// doesn't bound its depth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BranchAndBound {
+    /// The excess over the target worth spending to avoid the change.
+    pub cost_of_change: Word,
+    /// The maximum number of steps of the search.
+    pub max_tries: usize,
+}
+
+impl Default for BranchAndBound {
+    fn default() -> Self {
+        Self {
+            cost_of_change: 0,
+            max_tries: 100_000,
+        }
+    }
+}
+
+impl CoinSelectionStrategy for BranchAndBound {
+    fn select(
+        &self,
+        amounts: &[Word],
+        target: Word,
+        max_count: usize,
+    ) -> Option<Vec<usize>> {
+        let sorted = sorted_by_amount(amounts);
+
+        let mut search = Search {
+            amounts: sorted.iter().map(|index| amounts[*index] as u128).collect(),
+            remaining: Vec::new(),
+            target: target as u128,
+            max_waste: self.cost_of_change as u128,
+            max_count,
+            tries: self.max_tries,
+            selected: Vec::new(),
+            best: None,
+        };
+        // The sums of the amounts from every position, bounding the branches
+        search.remaining = search
+            .amounts
+            .iter()
+            .rev()
+            .scan(0u128, |sum, amount| {
+                *sum += amount;
+                Some(*sum)
+            })
+            .collect();
+        search.remaining.reverse();
+        search.explore();
+
+        match search.best {
+            Some((_, selected)) => {
+                Some(selected.into_iter().map(|index| sorted[index]).collect())
+            }
+            None => LargestFirst.select(amounts, target, max_count),
+        }
+    }
+}
+
+/// The depth-first search of [`BranchAndBound`], over the amounts in descending order.
+struct Search {
+    amounts: Vec<u128>,
+    remaining: Vec<u128>,
+    target: u128,
+    max_waste: u128,
+    max_count: usize,
+    tries: usize,
+    selected: Vec<usize>,
+    best: Option<(u128, Vec<usize>)>,
+}
+
+/// A branch of the [`Search`], starting from the first `len` selected positions.
+struct Branch {
+    position: usize,
+    sum: u128,
+    len: usize,
+    pick: Option<usize>,
+}
+
+impl Search {
+    fn explore(&mut self) {
+        let mut branches = vec![Branch {
+            position: 0,
+            sum: 0,
+            len: 0,
+            pick: None,
+        }];
+
+        while let Some(branch) = branches.pop() {
+            if self.tries == 0 || matches!(self.best, Some((0, _))) {
+                return
+            }
+            self.tries -= 1;
+
+            let Branch {
+                position,
+                sum,
+                len,
+                pick,
+            } = branch;
+            self.selected.truncate(len);
+            self.selected.extend(pick);
+
+            if sum >= self.target {
+                let waste = sum - self.target;
+                let worse = self
+                    .best
+                    .as_ref()
+                    .is_some_and(|(best_waste, _)| *best_waste <= waste);
+                if waste <= self.max_waste && !worse {
+                    self.best = Some((waste, self.selected.clone()));
+                }
+                // Adding amounts only increases the waste
+                continue
+            }
+
+            if self.selected.len() == self.max_count {
+                continue
+            }
+            match self.remaining.get(position) {
+                Some(remaining) if sum + remaining >= self.target => {}
+                _ => continue,
+            }
+
+            // The branch including the amount is explored first
+            let len = self.selected.len();
+            branches.push(Branch {
+                position: position + 1,
+                sum,
+                len,
+                pick: None,
+            });
+            branches.push(Branch {
+                position: position + 1,
+                sum: sum + self.amounts[position],
+                len,
+                pick: Some(position),
+            });
+        }
+    }
+}
+
+fn sorted_by_amount(amounts: &[Word]) -> Vec<usize> {
+    let mut indexes: Vec<_> = (0..amounts.len()).collect();
+    indexes.sort_by(|a, b| amounts[*b].cmp(&amounts[*a]));
+    indexes
+}
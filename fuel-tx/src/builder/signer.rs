@@ -0,0 +1,114 @@
+use crate::Input;
+
+use fuel_crypto::{
+    Message,
+    SecretKey,
+    Signature,
+};
+use fuel_types::Address;
+
+use alloc::{
+    boxed::Box,
+    string::String,
+    sync::Arc,
+};
+use core::fmt;
+
+/// Signer errors.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, derive_more::Display)]
+pub enum SignerError {
+    /// The signer couldn't produce the signature.
+    #[display(fmt = "Signing failed: {}", _0)]
+    Failed(String),
+    /// The signature produced by the signer of the owner doesn't match it.
+    #[display(fmt = "The signature of the signer of {} doesn't match it", _0)]
+    InvalidSignature(Address),
+    /// There is no signer for the owner of signed inputs.
+    #[display(fmt = "No signer for the owner {}", _0)]
+    MissingSigner(Address),
+    /// The asynchronous signer of the owner can't be used to finalize the transaction
+    /// synchronously.
+    #[display(fmt = "The signer of {} is asynchronous", _0)]
+    Asynchronous(Address),
+}
+
+/// Signs transactions on behalf of an owner, without exposing its secret key.
+pub trait Signer {
+    /// The address of the owner of the inputs signed by the signer.
+    fn owner(&self) -> Address;
+
+    /// Sign the message with the key of the owner.
+    fn sign(&self, message: &Message) -> Result<Signature, SignerError>;
+}
+
+/// Signs transactions on behalf of an owner, with a key held by an external service
+/// like a hardware wallet or a remote key management service.
+#[async_trait::async_trait]
+pub trait AsyncSigner {
+    /// The address of the owner of the inputs signed by the signer.
+    fn owner(&self) -> Address;
+
+    /// Sign the message with the key of the owner.
+    async fn sign(&self, message: &Message) -> Result<Signature, SignerError>;
+}
+
+impl Signer for SecretKey {
+    fn owner(&self) -> Address {
+        Input::owner(&self.public_key())
+    }
+
+    fn sign(&self, message: &Message) -> Result<Signature, SignerError> {
+        Ok(Signature::sign(self, message))
+    }
+}
+
+/// A signer added to the builder.
+#[derive(Clone)]
+pub(super) enum AnySigner {
+    Sync(Arc<dyn Signer + Send + Sync>),
+    Async(Arc<dyn AsyncSigner + Send + Sync>),
+}
+
+impl AnySigner {
+    pub(super) fn sign(
+        &self,
+        owner: Address,
+        message: &Message,
+    ) -> Result<Signature, SignerError> {
+        match self {
+            Self::Sync(signer) => signer.sign(message),
+            Self::Async(_) => Err(SignerError::Asynchronous(owner)),
+        }
+    }
+
+    pub(super) async fn sign_async(
+        &self,
+        message: &Message,
+    ) -> Result<Signature, SignerError> {
+        match self {
+            Self::Sync(signer) => signer.sign(message),
+            Self::Async(signer) => signer.sign(message).await,
+        }
+    }
+}
+
+impl fmt::Debug for AnySigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sync(_) => f.write_str("Signer"),
+            Self::Async(_) => f.write_str("AsyncSigner"),
+        }
+    }
+}
+
+/// Check that the signature was produced by the owner.
+pub(super) fn verify(
+    owner: Address,
+    signature: Signature,
+    message: &Message,
+) -> Result<Signature, SignerError> {
+    match signature.recover(message) {
+        Ok(public_key) if Input::owner(&public_key) == owner => Ok(signature),
+        _ => Err(SignerError::InvalidSignature(owner)),
+    }
+}
@@ -52,14 +52,23 @@ mod tests;
 
 #[cfg(feature = "builder")]
 pub use builder::{
+    AsyncSigner,
+    BranchAndBound,
     Buildable,
+    CoinSelectionError,
+    CoinSelectionStrategy,
     Finalizable,
+    LargestFirst,
+    Signer,
+    SignerError,
+    Spendable,
     TransactionBuilder,
 };
 
 #[cfg(feature = "alloc")]
 pub use receipt::{
     Receipt,
+    ReceiptRepr,
     ScriptExecutionResult,
 };
 
@@ -72,6 +81,7 @@ pub use transaction::{
     output,
     output::Output,
     output::OutputRepr,
+    partially_signed,
     Cacheable,
     Chargeable,
     CheckError,
@@ -85,7 +95,10 @@ pub use transaction::{
     GasCosts,
     GasCostsValues,
     GasUnit,
+    Location,
     Mint,
+    PartiallySignedError,
+    PartiallySignedTransaction,
     PredicateParameters,
     Script,
     ScriptParameters,
@@ -96,6 +109,7 @@ pub use transaction::{
     TxId,
     TxParameters,
     UtxoId,
+    Violation,
     Witness,
 };
 
@@ -22,6 +22,7 @@ mod receipt_repr;
 mod script_result;
 
 use crate::input::message::compute_message_id;
+pub use receipt_repr::ReceiptRepr;
 pub use script_result::ScriptExecutionResult;
 
 #[derive(Clone, Derivative)]
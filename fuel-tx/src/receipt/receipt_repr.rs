@@ -37,7 +37,9 @@ macro_rules! enum_from {
 }
 
 enum_from! {
+    /// The kind of a [`Receipt`], without its data.
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum ReceiptRepr {
         Call = 0x00,
         Return = 0x01,
@@ -0,0 +1,325 @@
+use fuel_crypto::SecretKey;
+use fuel_tx::{
+    field::{
+        Inputs,
+        Outputs,
+    },
+    *,
+};
+use fuel_types::Address;
+use rand::{
+    rngs::StdRng,
+    Rng,
+    SeedableRng,
+};
+
+use super::valid_cases::CHAIN_ID;
+
+fn params() -> ConsensusParameters {
+    let mut params = ConsensusParameters::standard_with_id(CHAIN_ID);
+    params.fee_params = params.fee_params.with_gas_price_factor(1);
+    params
+}
+
+fn builder() -> TransactionBuilder<Script> {
+    let mut builder = TransactionBuilder::script(vec![], vec![]);
+    builder.with_params(params()).gas_price(1).gas_limit(1_000);
+    builder
+}
+
+fn coin(rng: &mut StdRng, owner: Address, amount: Word, asset_id: AssetId) -> Spendable {
+    Spendable::Coin {
+        utxo_id: rng.gen(),
+        owner,
+        amount,
+        asset_id,
+        tx_pointer: rng.gen(),
+        maturity: Default::default(),
+    }
+}
+
+fn balance(tx: &Script, asset_id: &AssetId) -> Word {
+    tx.inputs()
+        .iter()
+        .filter(|input| input.asset_id(&AssetId::BASE) == Some(asset_id))
+        .filter_map(Input::amount)
+        .sum()
+}
+
+#[test]
+fn selected_coins_cover_the_outputs_and_the_fee() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let key = SecretKey::random(rng);
+    let owner = Input::owner(&key.public_key());
+    let asset_id: AssetId = rng.gen();
+    let recipient = rng.gen();
+
+    let spendables = vec![
+        coin(rng, owner, 6_000, AssetId::BASE),
+        coin(rng, owner, 300, asset_id),
+        coin(rng, owner, 4_000, AssetId::BASE),
+        coin(rng, owner, 400, asset_id),
+        Spendable::MessageCoin {
+            sender: rng.gen(),
+            recipient: owner,
+            nonce: rng.gen(),
+            amount: 9_000,
+        },
+    ];
+
+    let mut builder = builder();
+    builder
+        .add_output(Output::coin(recipient, 1_000, AssetId::BASE))
+        .add_output(Output::coin(recipient, 500, asset_id))
+        .select_coins(&spendables, owner, &LargestFirst)
+        .expect("Failed to select the coins")
+        .add_signer(key);
+    let tx = builder.finalize();
+
+    // The message, the largest amount of the base asset, and the coins of the other asset
+    assert_eq!(tx.inputs().len(), 3);
+    assert!(tx.inputs()[0].is_message_coin_signed());
+    assert_eq!(balance(&tx, &asset_id), 700);
+
+    let fee = TransactionFee::checked_from_tx(params().fee_params(), &tx)
+        .expect("Failed to compute the fee");
+    assert!(fee.max_fee() > 0);
+    assert!(balance(&tx, &AssetId::BASE) >= 1_000 + fee.max_fee());
+
+    let mut change: Vec<_> = tx
+        .outputs()
+        .iter()
+        .filter_map(|output| match output {
+            Output::Change { to, asset_id, .. } if *to == owner => Some(*asset_id),
+            _ => None,
+        })
+        .collect();
+    change.sort();
+    let mut expected = vec![AssetId::BASE, asset_id];
+    expected.sort();
+    assert_eq!(change, expected);
+
+    tx.check_signatures(&CHAIN_ID)
+        .expect("Failed to validate the signatures");
+}
+
+#[test]
+fn selection_skips_the_spent_coins_and_counts_the_inputs() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let key = SecretKey::random(rng);
+    let owner = Input::owner(&key.public_key());
+    let spent = coin(rng, owner, 10_000, AssetId::BASE);
+    let spendables = vec![spent.clone(), coin(rng, owner, 5_000, AssetId::BASE)];
+
+    let Spendable::Coin {
+        utxo_id,
+        tx_pointer,
+        maturity,
+        ..
+    } = spent
+    else {
+        unreachable!()
+    };
+
+    let mut builder = builder();
+    builder
+        .add_unsigned_coin_input(
+            key,
+            utxo_id,
+            10_000,
+            AssetId::BASE,
+            tx_pointer,
+            maturity,
+        )
+        .add_output(Output::coin(rng.gen(), 9_000, AssetId::BASE))
+        .select_coins(&spendables, owner, &BranchAndBound::default())
+        .expect("Failed to select the coins");
+
+    // The input covers the output, the other coin covers the fee
+    assert_eq!(builder.inputs().len(), 2);
+    assert_eq!(builder.witnesses().len(), 1);
+
+    let tx = builder.finalize();
+    tx.check_signatures(&CHAIN_ID)
+        .expect("Failed to validate the signatures");
+}
+
+#[test]
+fn insufficient_funds_leave_the_builder_untouched() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let owner = rng.gen();
+    let asset_id = rng.gen();
+    let spendables = vec![
+        coin(rng, owner, 1_000_000, AssetId::BASE),
+        coin(rng, owner, 100, asset_id),
+        coin(rng, owner, 200, asset_id),
+    ];
+
+    let mut builder = builder();
+    builder.add_output(Output::coin(rng.gen(), 500, asset_id));
+
+    let err = builder
+        .select_coins(&spendables, owner, &LargestFirst)
+        .expect_err("Expected the funds to be insufficient");
+
+    assert_eq!(
+        err,
+        CoinSelectionError::InsufficientFunds {
+            asset_id,
+            required: 500,
+            available: 300,
+        }
+    );
+    assert!(builder.inputs().is_empty());
+    assert_eq!(builder.outputs().len(), 1);
+}
+
+/// A strategy selecting amounts that don't exist.
+struct OutOfRange;
+
+impl CoinSelectionStrategy for OutOfRange {
+    fn select(&self, amounts: &[Word], _: Word, _: usize) -> Option<Vec<usize>> {
+        Some(vec![amounts.len()])
+    }
+}
+
+#[test]
+fn invalid_selections_are_rejected() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let owner = rng.gen();
+    let spendables = vec![
+        coin(rng, owner, 1_000_000, AssetId::BASE),
+        coin(rng, owner, 2_000_000, AssetId::BASE),
+    ];
+
+    let mut builder = builder();
+    builder.add_output(Output::coin(rng.gen(), 20_000, AssetId::BASE));
+
+    let err = builder
+        .select_coins(&spendables, owner, &OutOfRange)
+        .expect_err("Expected the selection to be rejected");
+
+    assert_eq!(err, CoinSelectionError::InvalidSelection(2));
+    assert!(builder.inputs().is_empty());
+    assert_eq!(builder.outputs().len(), 1);
+}
+
+#[test]
+fn strategies_select_the_amounts() {
+    let amounts = [7, 5, 3, 2];
+
+    assert_eq!(
+        LargestFirst.select(&amounts, 10, usize::MAX),
+        Some(vec![0, 1])
+    );
+    assert_eq!(LargestFirst.select(&amounts, 0, usize::MAX), Some(vec![]));
+    assert_eq!(LargestFirst.select(&amounts, 18, usize::MAX), None);
+
+    // An exact match avoids the change
+    let mut selected = BranchAndBound::default()
+        .select(&amounts, 10, usize::MAX)
+        .expect("Expected a selection");
+    selected.sort();
+    assert_eq!(selected, vec![0, 2]);
+
+    let with_cost_of_change = BranchAndBound {
+        cost_of_change: 1,
+        ..Default::default()
+    };
+    assert_eq!(
+        with_cost_of_change.select(&[4, 6, 9], 8, usize::MAX),
+        Some(vec![2])
+    );
+
+    // Falls back to the largest amounts first without a match
+    assert_eq!(
+        BranchAndBound::default().select(&[4, 6, 9], 8, usize::MAX),
+        LargestFirst.select(&[4, 6, 9], 8, usize::MAX)
+    );
+    assert_eq!(
+        BranchAndBound::default().select(&amounts, 18, usize::MAX),
+        None
+    );
+}
+
+#[test]
+fn strategies_select_at_most_max_count_amounts() {
+    let amounts = [7, 5, 3, 2];
+
+    assert_eq!(LargestFirst.select(&amounts, 12, 2), Some(vec![0, 1]));
+    assert_eq!(LargestFirst.select(&amounts, 13, 2), None);
+    assert_eq!(BranchAndBound::default().select(&amounts, 13, 2), None);
+
+    let mut selected = BranchAndBound::default()
+        .select(&amounts, 10, 3)
+        .expect("Expected a selection");
+    selected.sort();
+    assert_eq!(selected, vec![0, 2]);
+}
+
+#[test]
+fn branch_and_bound_searches_large_coin_sets() {
+    // Deep enough to overflow the stack with a recursive search
+    let amounts = vec![1; 100_000];
+
+    let selected = BranchAndBound {
+        cost_of_change: 0,
+        max_tries: usize::MAX,
+    }
+    .select(&amounts, 99_999, usize::MAX)
+    .expect("Expected a selection");
+    assert_eq!(selected.len(), 99_999);
+}
+
+#[test]
+fn selection_fits_in_the_inputs_left() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let key = SecretKey::random(rng);
+    let owner = Input::owner(&key.public_key());
+    let spendables: Vec<_> = (0..10)
+        .map(|_| coin(rng, owner, 10_000, AssetId::BASE))
+        .collect();
+
+    let mut params = params();
+    params.tx_params = params.tx_params.with_max_inputs(4);
+
+    // The dust covers the output, but not within the inputs left
+    let mut dust = builder();
+    dust.with_params(params.clone()).add_output(Output::coin(
+        rng.gen(),
+        50_000,
+        AssetId::BASE,
+    ));
+
+    let err = dust
+        .select_coins(&spendables, owner, &LargestFirst)
+        .expect_err("Expected the inputs to be insufficient");
+    assert_eq!(
+        err,
+        CoinSelectionError::TooManyInputs {
+            asset_id: AssetId::BASE,
+            available_inputs: 4,
+            max_inputs: 4,
+        }
+    );
+    assert!(dust.inputs().is_empty());
+
+    // A selection fitting in the inputs passes the checks
+    let mut builder = builder();
+    builder
+        .with_params(params.clone())
+        .add_output(Output::coin(rng.gen(), 20_000, AssetId::BASE))
+        .select_coins(&spendables, owner, &BranchAndBound::default())
+        .expect("Failed to select the coins")
+        .add_signer(key);
+    let tx = builder.finalize();
+
+    assert!(tx.inputs().len() <= 4);
+    tx.check(Default::default(), &params)
+        .expect("Failed to validate the transaction");
+}
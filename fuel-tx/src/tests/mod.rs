@@ -1,5 +1,8 @@
+mod coin_selection;
 mod offset;
+mod partially_signed;
 mod prepared_init;
+mod signer;
 mod valid_cases;
 
 #[cfg(feature = "serde")]
@@ -0,0 +1,287 @@
+use fuel_crypto::{
+    SecretKey,
+    Signature,
+};
+use fuel_tx::{
+    field::{
+        Inputs,
+        Witnesses,
+    },
+    partially_signed::{
+        MissingSignature,
+        PredicateEstimation,
+    },
+    *,
+};
+use fuel_types::canonical::{
+    Deserialize,
+    Serialize,
+};
+use rand::{
+    rngs::StdRng,
+    Rng,
+    SeedableRng,
+};
+
+use super::valid_cases::CHAIN_ID;
+
+fn params() -> ConsensusParameters {
+    ConsensusParameters::standard_with_id(CHAIN_ID)
+}
+
+/// A transaction with inputs of two owners, and a predicate input.
+fn transaction(a: &SecretKey, b: &SecretKey, predicate_gas_used: Word) -> Transaction {
+    let predicate = vec![0xde, 0xad, 0xbe, 0xef];
+    let owner = Input::predicate_owner(&predicate);
+
+    let mut builder = TransactionBuilder::script(vec![1, 2, 3], vec![]);
+    builder
+        .with_params(params())
+        .add_unsigned_owner_coin_input(
+            Input::owner(&a.public_key()),
+            UtxoId::new([1; 32].into(), 0),
+            100,
+            AssetId::BASE,
+            Default::default(),
+            Default::default(),
+        )
+        .add_unsigned_owner_coin_input(
+            Input::owner(&b.public_key()),
+            UtxoId::new([2; 32].into(), 0),
+            200,
+            AssetId::BASE,
+            Default::default(),
+            Default::default(),
+        )
+        .add_input(Input::coin_predicate(
+            UtxoId::new([3; 32].into(), 0),
+            owner,
+            300,
+            AssetId::BASE,
+            Default::default(),
+            Default::default(),
+            predicate_gas_used,
+            predicate,
+            vec![],
+        ))
+        .add_unsigned_owner_coin_input(
+            Input::owner(&a.public_key()),
+            UtxoId::new([4; 32].into(), 0),
+            400,
+            AssetId::BASE,
+            Default::default(),
+            Default::default(),
+        );
+
+    builder.finalize_without_signature_as_transaction()
+}
+
+fn sign(partially_signed: &mut PartiallySignedTransaction, key: &SecretKey) {
+    let signature = Signature::sign(key, &partially_signed.message());
+    partially_signed
+        .add_signature(Input::owner(&key.public_key()), signature)
+        .expect("Failed to add the signature");
+}
+
+#[test]
+fn parties_sign_copies_combined_into_the_transaction() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let a = SecretKey::random(rng);
+    let b = SecretKey::random(rng);
+
+    let unsigned = PartiallySignedTransaction::new(transaction(&a, &b, 0), &params())
+        .expect("Failed to wrap the transaction");
+
+    assert_eq!(
+        unsigned.missing_signatures(),
+        &[
+            MissingSignature {
+                owner: Input::owner(&a.public_key()),
+                witness_index: 0,
+            },
+            MissingSignature {
+                owner: Input::owner(&b.public_key()),
+                witness_index: 1,
+            },
+        ]
+    );
+    assert_eq!(
+        unsigned
+            .inputs()
+            .iter()
+            .map(|input| (input.amount, input.predicate))
+            .collect::<Vec<_>>(),
+        vec![
+            (100, PredicateEstimation::None),
+            (200, PredicateEstimation::None),
+            (300, PredicateEstimation::Pending),
+            (400, PredicateEstimation::None),
+        ]
+    );
+
+    // Every party signs its own copy
+    let bytes = unsigned.to_bytes();
+    let mut signed_by_a = PartiallySignedTransaction::from_bytes(&bytes)
+        .expect("Failed to decode the transaction");
+    assert_eq!(signed_by_a, unsigned);
+    sign(&mut signed_by_a, &a);
+
+    let mut signed_by_b = PartiallySignedTransaction::from_bytes(&bytes)
+        .expect("Failed to decode the transaction");
+    sign(&mut signed_by_b, &b);
+
+    let mut combined = signed_by_a.clone();
+    combined
+        .combine(&signed_by_b)
+        .expect("Failed to combine the signatures");
+
+    assert!(combined.missing_signatures().is_empty());
+    assert!(!combined.is_complete());
+    assert_eq!(
+        combined.clone().finalize(&params()),
+        Err(PartiallySignedError::PredicateNotEstimated(2))
+    );
+
+    // The estimation of the predicates doesn't invalidate the signatures
+    let estimated =
+        PartiallySignedTransaction::new(transaction(&a, &b, 1_000), &params())
+            .expect("Failed to wrap the transaction");
+    combined
+        .combine(&estimated)
+        .expect("Failed to combine the predicate estimations");
+
+    assert!(combined.is_complete());
+
+    let tx = combined
+        .finalize(&params())
+        .expect("Failed to finalize the transaction");
+    let tx = tx.as_script().expect("Expected a script");
+
+    assert_eq!(tx.witnesses().len(), 2);
+    assert_eq!(tx.inputs()[2].predicate_gas_used(), Some(1_000));
+    tx.check_signatures(&CHAIN_ID)
+        .expect("Failed to validate the signatures");
+}
+
+#[test]
+fn signatures_are_checked_against_the_owners() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let a = SecretKey::random(rng);
+    let b = SecretKey::random(rng);
+    let other = SecretKey::random(rng);
+
+    let mut partially_signed =
+        PartiallySignedTransaction::new(transaction(&a, &b, 0), &params())
+            .expect("Failed to wrap the transaction");
+    let message = partially_signed.message();
+
+    let owner = Input::owner(&other.public_key());
+    assert_eq!(
+        partially_signed.add_signature(owner, Signature::sign(&other, &message)),
+        Err(PartiallySignedError::UnknownOwner(owner))
+    );
+
+    let owner = Input::owner(&a.public_key());
+    assert_eq!(
+        partially_signed.add_signature(owner, Signature::sign(&other, &message)),
+        Err(PartiallySignedError::InvalidSignature(owner))
+    );
+
+    assert_eq!(
+        partially_signed.clone().finalize(&params()),
+        Err(PartiallySignedError::MissingSignature(owner))
+    );
+}
+
+#[test]
+fn combined_witnesses_are_checked_against_the_owners() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let a = SecretKey::random(rng);
+    let b = SecretKey::random(rng);
+    let owner = Input::owner(&a.public_key());
+
+    let mut partially_signed =
+        PartiallySignedTransaction::new(transaction(&a, &b, 1_000), &params())
+            .expect("Failed to wrap the transaction");
+    sign(&mut partially_signed, &b);
+
+    // A copy carrying garbage in place of the signature of `a`
+    let mut tx = transaction(&a, &b, 1_000);
+    if let Transaction::Script(script) = &mut tx {
+        script.witnesses_mut()[0] = vec![0xff; 64].into();
+    }
+    let bytes = PartiallySignedTransaction::new(tx, &params())
+        .expect("Failed to wrap the transaction")
+        .to_bytes();
+    let forged = PartiallySignedTransaction::from_bytes(&bytes)
+        .expect("Failed to decode the transaction");
+
+    let mut combined = partially_signed.clone();
+    assert_eq!(
+        combined.combine(&forged),
+        Err(PartiallySignedError::InvalidSignature(owner))
+    );
+    assert_eq!(combined, partially_signed);
+
+    // Finalizing the forged copy directly is rejected too
+    let mut forged = forged;
+    forged
+        .combine(&partially_signed)
+        .expect("Failed to combine the signatures");
+    assert!(forged.is_complete());
+    assert_eq!(
+        forged.finalize(&params()),
+        Err(PartiallySignedError::InvalidSignature(owner))
+    );
+}
+
+#[test]
+fn consensus_parameters_must_match() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let a = SecretKey::random(rng);
+    let b = SecretKey::random(rng);
+    let tx = transaction(&a, &b, 1_000);
+
+    let mut other_params = params();
+    other_params.fee_params = other_params.fee_params.with_gas_per_byte(1234);
+
+    let mut partially_signed = PartiallySignedTransaction::new(tx.clone(), &params())
+        .expect("Failed to wrap the transaction");
+    let other = PartiallySignedTransaction::new(tx, &other_params)
+        .expect("Failed to wrap the transaction");
+
+    assert_ne!(
+        partially_signed.consensus_parameters_hash(),
+        other.consensus_parameters_hash()
+    );
+    assert_eq!(
+        partially_signed.combine(&other),
+        Err(PartiallySignedError::ConsensusParametersMismatch)
+    );
+
+    sign(&mut partially_signed, &a);
+    sign(&mut partially_signed, &b);
+
+    assert_eq!(
+        partially_signed.finalize(&other_params),
+        Err(PartiallySignedError::ConsensusParametersMismatch)
+    );
+
+    let mint = TransactionBuilder::mint(
+        Default::default(),
+        rng.gen(),
+        rng.gen(),
+        rng.gen(),
+        rng.gen(),
+        rng.gen(),
+    )
+    .finalize_as_transaction();
+    assert_eq!(
+        PartiallySignedTransaction::new(mint, &params()),
+        Err(PartiallySignedError::UnsupportedTransaction)
+    );
+}
@@ -0,0 +1,180 @@
+use fuel_crypto::{
+    Message,
+    SecretKey,
+    Signature,
+};
+use fuel_tx::{
+    field::{
+        Inputs,
+        Witnesses,
+    },
+    *,
+};
+use fuel_tx_test_helpers::generate_bytes;
+use fuel_types::Address;
+use rand::{
+    rngs::StdRng,
+    Rng,
+    SeedableRng,
+};
+
+use super::valid_cases::CHAIN_ID;
+
+/// A signer holding the key out of the reach of the builder.
+struct Custody {
+    owner: Address,
+    key: SecretKey,
+}
+
+impl Custody {
+    fn new(rng: &mut StdRng) -> Self {
+        let key = SecretKey::random(rng);
+
+        Self {
+            owner: Input::owner(&key.public_key()),
+            key,
+        }
+    }
+}
+
+impl Signer for Custody {
+    fn owner(&self) -> Address {
+        self.owner
+    }
+
+    fn sign(&self, message: &Message) -> Result<Signature, SignerError> {
+        Ok(Signature::sign(&self.key, message))
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncSigner for Custody {
+    fn owner(&self) -> Address {
+        self.owner
+    }
+
+    async fn sign(&self, message: &Message) -> Result<Signature, SignerError> {
+        Ok(Signature::sign(&self.key, message))
+    }
+}
+
+fn builder(rng: &mut StdRng) -> TransactionBuilder<Script> {
+    let mut builder =
+        TransactionBuilder::script(generate_bytes(rng), generate_bytes(rng));
+    builder.with_chain_id(CHAIN_ID);
+    builder
+}
+
+fn add_coin_input(
+    rng: &mut StdRng,
+    builder: &mut TransactionBuilder<Script>,
+    owner: Address,
+) {
+    builder.add_unsigned_owner_coin_input(
+        owner,
+        rng.gen(),
+        rng.gen(),
+        rng.gen(),
+        rng.gen(),
+        Default::default(),
+    );
+}
+
+#[test]
+fn signers_sign_the_inputs_of_their_owners() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let a = Custody::new(rng);
+    let b = Custody::new(rng);
+    let key = SecretKey::random(rng);
+
+    let mut builder = builder(rng);
+    add_coin_input(rng, &mut builder, a.owner);
+    add_coin_input(rng, &mut builder, b.owner);
+    builder.add_unsigned_owner_message_input(
+        a.owner,
+        rng.gen(),
+        rng.gen(),
+        rng.gen(),
+        vec![],
+    );
+    builder.add_unsigned_coin_input(
+        key,
+        rng.gen(),
+        rng.gen(),
+        rng.gen(),
+        rng.gen(),
+        Default::default(),
+    );
+    add_coin_input(rng, &mut builder, Input::owner(&key.public_key()));
+
+    let tx = builder.add_signer(a).add_signer(b).finalize();
+
+    // The witnesses are deduplicated per owner
+    assert_eq!(tx.witnesses().len(), 3);
+    tx.check_signatures(&CHAIN_ID)
+        .expect("Failed to validate the signatures");
+}
+
+#[test]
+fn async_signers_sign_the_inputs_of_their_owners() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let a = Custody::new(rng);
+    let b = Custody::new(rng);
+
+    let mut builder = builder(rng);
+    add_coin_input(rng, &mut builder, a.owner);
+    add_coin_input(rng, &mut builder, b.owner);
+    builder.add_async_signer(a).add_signer(b);
+
+    let err = builder
+        .try_finalize()
+        .expect_err("Expected the asynchronous signer to be rejected");
+
+    assert!(matches!(err, SignerError::Asynchronous(_)));
+
+    // The builder is left untouched by the failure
+    let tx = futures::executor::block_on(builder.finalize_async())
+        .expect("Failed to sign the transaction");
+
+    assert_eq!(tx.inputs().len(), 2);
+    tx.check_signatures(&CHAIN_ID)
+        .expect("Failed to validate the signatures");
+}
+
+#[test]
+fn finalization_fails_without_a_signer() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let owner = rng.gen();
+    let mut builder = builder(rng);
+    add_coin_input(rng, &mut builder, owner);
+
+    let err = builder
+        .try_finalize()
+        .expect_err("Expected the missing signer to be reported");
+
+    assert_eq!(err, SignerError::MissingSigner(owner));
+}
+
+#[test]
+fn finalization_fails_with_a_signature_of_another_owner() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let owner = Custody::new(rng).owner;
+    let signer = Custody {
+        owner,
+        key: SecretKey::random(rng),
+    };
+
+    let mut builder = builder(rng);
+    add_coin_input(rng, &mut builder, owner);
+
+    let err = builder
+        .add_signer(signer)
+        .try_finalize()
+        .expect_err("Expected the signature to be rejected");
+
+    assert_eq!(err, SignerError::InvalidSignature(owner));
+}
@@ -9,9 +9,13 @@ use super::{
 use fuel_crypto::SecretKey;
 use fuel_tx::*;
 use fuel_tx_test_helpers::generate_bytes;
-use fuel_types::canonical::{
-    Deserialize,
-    Serialize,
+use fuel_types::{
+    canonical::{
+        Deserialize,
+        Serialize,
+    },
+    BlockHeight,
+    ChainId,
 };
 use rand::{
     rngs::StdRng,
@@ -409,6 +413,157 @@ fn output_change_asset_id() {
     ));
 }
 
+#[test]
+fn check_all_reports_every_violation() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let maturity = 100.into();
+    let block_height = 1000.into();
+
+    let a: AssetId = rng.gen();
+    let b: AssetId = rng.gen();
+
+    let secret = SecretKey::random(rng);
+    let predicate = vec![];
+    let owner = Input::predicate_owner(&predicate);
+
+    let tx: Transaction =
+        TransactionBuilder::script(generate_bytes(rng), generate_bytes(rng))
+            .gas_limit(TX_PARAMS.max_gas_per_tx + 1)
+            .gas_price(rng.gen())
+            .maturity(maturity)
+            .add_unsigned_coin_input(
+                secret,
+                rng.gen(),
+                rng.gen(),
+                a,
+                rng.gen(),
+                rng.gen(),
+            )
+            .add_input(Input::coin_predicate(
+                rng.gen(),
+                owner,
+                rng.gen(),
+                a,
+                rng.gen(),
+                rng.gen(),
+                rng.gen(),
+                predicate,
+                generate_bytes(rng),
+            ))
+            .add_output(Output::change(rng.gen(), rng.next_u64(), a))
+            .add_output(Output::change(rng.gen(), rng.next_u64(), b))
+            .finalize()
+            .into();
+
+    let violations = tx
+        .check_all(block_height, &test_params())
+        .expect_err("Expected erroneous transaction");
+
+    assert_eq!(
+        violations,
+        vec![
+            Violation::transaction("gas_limit", CheckError::TransactionGasLimit),
+            Violation::input(
+                1,
+                Some("predicate"),
+                CheckError::InputPredicateEmpty { index: 1 }
+            ),
+            Violation::output(
+                1,
+                Some("asset_id"),
+                CheckError::TransactionOutputChangeAssetIdNotFound(b)
+            ),
+        ]
+    );
+    assert_eq!(violations[1].location, Location::Input(1));
+
+    // The fail-fast checks stop at the first violation
+    let err = tx
+        .check_without_signatures(block_height, &test_params())
+        .expect_err("Expected erroneous transaction");
+
+    assert_eq!(err, violations[0].error);
+}
+
+#[test]
+fn check_all_reports_a_missing_bytecode_witness() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    // The metadata can't be computed without the bytecode witness
+    let tx = Transaction::create(
+        rng.gen(),
+        TX_PARAMS.max_gas_per_tx,
+        100.into(),
+        7,
+        rng.gen(),
+        vec![],
+        vec![],
+        vec![],
+        vec![Default::default()],
+    );
+
+    let violations = tx
+        .check_all(1000.into(), &test_params())
+        .expect_err("Expected erroneous transaction");
+
+    assert_eq!(
+        violations,
+        vec![
+            Violation::transaction("inputs", CheckError::NoSpendableInput),
+            Violation::transaction(
+                "bytecode_witness_index",
+                CheckError::TransactionCreateBytecodeWitnessIndex
+            ),
+        ]
+    );
+}
+
+/// An implementor relying on the default `check_all`.
+struct AlwaysInvalid;
+
+impl FormatValidityChecks for AlwaysInvalid {
+    fn check_signatures(&self, _: &ChainId) -> Result<(), CheckError> {
+        Err(CheckError::TransactionWitnessesMax)
+    }
+
+    fn check_without_signatures(
+        &self,
+        _: BlockHeight,
+        _: &ConsensusParameters,
+    ) -> Result<(), CheckError> {
+        Err(CheckError::TransactionGasLimit)
+    }
+}
+
+#[test]
+fn check_all_defaults_to_the_first_violation() {
+    assert_eq!(
+        AlwaysInvalid.check_all(1000.into(), &test_params()),
+        Err(vec![Violation {
+            location: Location::Transaction,
+            field: None,
+            error: CheckError::TransactionGasLimit,
+        }])
+    );
+}
+
+#[test]
+fn check_all_accepts_valid_transaction() {
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let tx: Transaction =
+        TransactionBuilder::script(generate_bytes(rng), generate_bytes(rng))
+            .gas_limit(TX_PARAMS.max_gas_per_tx)
+            .gas_price(rng.gen())
+            .maturity(100.into())
+            .add_random_fee_input()
+            .finalize()
+            .into();
+
+    assert_eq!(tx.check_all(1000.into(), &test_params()), Ok(()));
+}
+
 #[test]
 fn script() {
     let rng = &mut StdRng::seed_from_u64(8586);
@@ -24,6 +24,7 @@ mod validity;
 mod id;
 
 pub mod consensus_parameters;
+pub mod partially_signed;
 
 pub use consensus_parameters::{
     ConsensusParameters,
@@ -48,11 +49,17 @@ use fuel_types::canonical::{
     Serialize,
 };
 pub use metadata::Cacheable;
+pub use partially_signed::{
+    PartiallySignedError,
+    PartiallySignedTransaction,
+};
 pub use repr::TransactionRepr;
 pub use types::*;
 pub use validity::{
     CheckError,
     FormatValidityChecks,
+    Location,
+    Violation,
 };
 
 use crate::{
@@ -1,5 +1,7 @@
+use fuel_crypto::Hasher;
 use fuel_types::{
     bytes::WORD_SIZE,
+    canonical::Serialize,
     AssetId,
     Bytes32,
     ChainId,
@@ -20,6 +22,7 @@ const MAX_GAS: u64 = 100_000_000;
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(default))]
+#[derive(fuel_types::canonical::Serialize)]
 pub struct ConsensusParameters {
     pub tx_params: TxParameters,
     pub predicate_params: PredicateParameters,
@@ -127,12 +130,18 @@ impl ConsensusParameters {
     pub fn gas_costs(&self) -> &GasCosts {
         &self.gas_costs
     }
+
+    /// The hash of the canonical serialization of the parameters.
+    pub fn compute_hash(&self) -> Bytes32 {
+        Hasher::hash(self.to_bytes())
+    }
 }
 
 /// Consensus configurable parameters used for verifying transactions
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(default))]
+#[derive(fuel_types::canonical::Serialize)]
 pub struct FeeParameters {
     /// Factor to convert between gas and transaction assets value.
     pub gas_price_factor: u64,
@@ -169,6 +178,7 @@ impl Default for FeeParameters {
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(default))]
+#[derive(fuel_types::canonical::Serialize)]
 pub struct PredicateParameters {
     /// Maximum length of predicate, in instructions.
     pub max_predicate_length: u64,
@@ -223,6 +233,7 @@ impl Default for PredicateParameters {
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(default))]
+#[derive(fuel_types::canonical::Serialize)]
 pub struct TxParameters {
     /// Maximum number of inputs.
     pub max_inputs: u64,
@@ -285,6 +296,7 @@ impl Default for TxParameters {
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(default))]
+#[derive(fuel_types::canonical::Serialize)]
 pub struct ScriptParameters {
     /// Maximum length of script, in instructions.
     pub max_script_length: u64,
@@ -324,6 +336,7 @@ impl Default for ScriptParameters {
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(default))]
+#[derive(fuel_types::canonical::Serialize)]
 pub struct ContractParameters {
     /// Maximum contract size, in bytes.
     pub contract_max_size: u64,
@@ -175,6 +175,31 @@ impl serde::Serialize for GasCosts {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl fuel_types::canonical::Serialize for GasCosts {
+    fn size_static(&self) -> usize {
+        self.0.size_static()
+    }
+
+    fn size_dynamic(&self) -> usize {
+        self.0.size_dynamic()
+    }
+
+    fn encode_static<O: fuel_types::canonical::Output + ?Sized>(
+        &self,
+        buffer: &mut O,
+    ) -> Result<(), fuel_types::canonical::Error> {
+        self.0.encode_static(buffer)
+    }
+
+    fn encode_dynamic<O: fuel_types::canonical::Output + ?Sized>(
+        &self,
+        buffer: &mut O,
+    ) -> Result<(), fuel_types::canonical::Error> {
+        self.0.encode_dynamic(buffer)
+    }
+}
+
 #[cfg(feature = "alloc")]
 impl GasCosts {
     /// Create new cost values wrapped in an [`Arc`].
@@ -203,6 +228,7 @@ impl Default for GasCostsValues {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(default = "GasCostsValues::unit"))]
+#[derive(fuel_types::canonical::Serialize)]
 pub struct GasCostsValues {
     pub add: Word,
     pub addi: Word,
@@ -326,6 +352,7 @@ pub struct GasCostsValues {
 /// then the cost for 0 units is 10, 1 unit is 12, 2 units is 14, etc.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(fuel_types::canonical::Serialize)]
 pub struct DependentCost {
     /// The minimum that this operation can cost.
     pub base: Word,
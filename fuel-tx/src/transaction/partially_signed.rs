@@ -0,0 +1,439 @@
+//! Exchange format for transactions whose witnesses are produced by several parties,
+//! like in multi-party custody or offline signing flows.
+
+use crate::{
+    field::{
+        Inputs,
+        Witnesses,
+    },
+    input::{
+        coin::{
+            CoinPredicate,
+            CoinSigned,
+        },
+        message::{
+            MessageCoinPredicate,
+            MessageCoinSigned,
+            MessageDataPredicate,
+            MessageDataSigned,
+        },
+    },
+    Cacheable,
+    CheckError,
+    ConsensusParameters,
+    Input,
+    Transaction,
+    UniqueIdentifier,
+    Witness,
+};
+
+use fuel_crypto::{
+    Message,
+    Signature,
+};
+use fuel_types::{
+    Address,
+    Bytes32,
+    ChainId,
+    Word,
+};
+
+use alloc::vec::Vec;
+
+/// Partially signed transaction errors.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, derive_more::Display)]
+pub enum PartiallySignedError {
+    /// `Mint` transactions have no inputs to sign.
+    #[display(fmt = "`Mint` transactions can't be partially signed")]
+    UnsupportedTransaction,
+    /// The transactions to combine are different.
+    #[display(fmt = "The transactions are different")]
+    TransactionMismatch,
+    /// The transaction was built against other consensus parameters.
+    #[display(fmt = "The consensus parameters are different")]
+    ConsensusParametersMismatch,
+    /// The owner doesn't need to sign the transaction.
+    #[display(fmt = "No input of {} needs a signature", _0)]
+    UnknownOwner(Address),
+    /// The signature wasn't produced by the owner.
+    #[display(fmt = "The signature doesn't match the owner {}", _0)]
+    InvalidSignature(Address),
+    /// The owner didn't sign the transaction yet.
+    #[display(fmt = "The owner {} didn't sign the transaction", _0)]
+    MissingSignature(Address),
+    /// The predicate of the input wasn't estimated yet.
+    #[display(fmt = "The predicate of the input {} isn't estimated", _0)]
+    PredicateNotEstimated(usize),
+    /// The finalized transaction couldn't be precomputed.
+    #[display(fmt = "{}", _0)]
+    Check(CheckError),
+}
+
+impl From<CheckError> for PartiallySignedError {
+    fn from(error: CheckError) -> Self {
+        Self::Check(error)
+    }
+}
+
+/// The estimation status of the predicate of an input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(fuel_types::canonical::Deserialize, fuel_types::canonical::Serialize)]
+pub enum PredicateEstimation {
+    /// The input has no predicate.
+    None,
+    /// The gas used by the predicate isn't set yet.
+    Pending,
+    /// The gas used by the predicate is set.
+    Estimated,
+}
+
+/// The metadata of an input of a partially signed transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(fuel_types::canonical::Deserialize, fuel_types::canonical::Serialize)]
+pub struct InputMetadata {
+    /// The amount of the spent coin or message. Zero for contract inputs.
+    pub amount: Word,
+    /// The estimation status of the predicate.
+    pub predicate: PredicateEstimation,
+}
+
+impl InputMetadata {
+    fn from_input(input: &Input) -> Self {
+        let predicate = match input.predicate_gas_used() {
+            None => PredicateEstimation::None,
+            Some(0) => PredicateEstimation::Pending,
+            Some(_) => PredicateEstimation::Estimated,
+        };
+
+        Self {
+            amount: input.amount().unwrap_or_default(),
+            predicate,
+        }
+    }
+}
+
+/// A signature an owner still has to add.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(fuel_types::canonical::Deserialize, fuel_types::canonical::Serialize)]
+pub struct MissingSignature {
+    /// The owner of the signed inputs.
+    pub owner: Address,
+    /// The index of the witness holding the signature of the owner.
+    pub witness_index: u8,
+}
+
+/// A transaction along with what the parties need to complete it.
+///
+/// The signatures are added as the owners sign the [`Self::message`], separately or on
+/// copies combined afterwards. The transaction is then extracted with
+/// [`Self::finalize`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(fuel_types::canonical::Deserialize, fuel_types::canonical::Serialize)]
+pub struct PartiallySignedTransaction {
+    transaction: Transaction,
+    chain_id: ChainId,
+    consensus_parameters_hash: Bytes32,
+    missing_signatures: Vec<MissingSignature>,
+    inputs: Vec<InputMetadata>,
+}
+
+impl PartiallySignedTransaction {
+    /// Wrap a transaction built against the consensus parameters. The empty witnesses
+    /// of the signed inputs are the signatures to add.
+    pub fn new(
+        transaction: Transaction,
+        consensus_parameters: &ConsensusParameters,
+    ) -> Result<Self, PartiallySignedError> {
+        if transaction.is_mint() {
+            return Err(PartiallySignedError::UnsupportedTransaction)
+        }
+
+        let mut partially_signed = Self {
+            transaction,
+            chain_id: consensus_parameters.chain_id(),
+            consensus_parameters_hash: consensus_parameters.compute_hash(),
+            missing_signatures: Vec::new(),
+            inputs: Vec::new(),
+        };
+        partially_signed.refresh();
+
+        Ok(partially_signed)
+    }
+
+    /// The transaction, with the signatures added so far.
+    pub fn transaction(&self) -> &Transaction {
+        &self.transaction
+    }
+
+    /// The chain the transaction was built for.
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+
+    /// The hash of the consensus parameters the transaction was built against.
+    pub fn consensus_parameters_hash(&self) -> &Bytes32 {
+        &self.consensus_parameters_hash
+    }
+
+    /// The signatures the owners still have to add.
+    pub fn missing_signatures(&self) -> &[MissingSignature] {
+        &self.missing_signatures
+    }
+
+    /// The metadata of the inputs, in the order of the inputs.
+    pub fn inputs(&self) -> &[InputMetadata] {
+        &self.inputs
+    }
+
+    /// Returns `true` if all the signatures are added and all the predicates are
+    /// estimated.
+    pub fn is_complete(&self) -> bool {
+        self.missing_signatures.is_empty()
+            && self
+                .inputs
+                .iter()
+                .all(|input| input.predicate != PredicateEstimation::Pending)
+    }
+
+    /// The message the owners sign.
+    pub fn message(&self) -> Message {
+        Message::from_bytes(*self.transaction.id(&self.chain_id))
+    }
+
+    /// Add the signature of the owner.
+    pub fn add_signature(
+        &mut self,
+        owner: Address,
+        signature: Signature,
+    ) -> Result<(), PartiallySignedError> {
+        let witness_index = self
+            .missing_signatures
+            .iter()
+            .find(|missing| missing.owner == owner)
+            .map(|missing| missing.witness_index)
+            .ok_or(PartiallySignedError::UnknownOwner(owner))?;
+
+        let witness = Witness::from(signature.as_ref());
+        self.verify(owner, &witness)?;
+
+        self.set_witness(witness_index, witness);
+        self.refresh();
+
+        Ok(())
+    }
+
+    /// Add the signatures and predicate estimations of a copy of the same transaction.
+    pub fn combine(&mut self, other: &Self) -> Result<(), PartiallySignedError> {
+        if self.chain_id != other.chain_id
+            || self.consensus_parameters_hash != other.consensus_parameters_hash
+        {
+            return Err(PartiallySignedError::ConsensusParametersMismatch)
+        }
+
+        // The id excludes the witnesses and the gas used by the predicates
+        if self.message() != other.message() {
+            return Err(PartiallySignedError::TransactionMismatch)
+        }
+
+        // The signatures are all checked before any is added
+        let mut signatures = Vec::new();
+        for missing in &self.missing_signatures {
+            if other.missing_signatures.contains(missing) {
+                continue
+            }
+
+            if let Some(witness) = witnesses(&other.transaction)
+                .get(missing.witness_index as usize)
+                .cloned()
+            {
+                self.verify(missing.owner, &witness)?;
+                signatures.push((missing.witness_index, witness));
+            }
+        }
+        for (witness_index, witness) in signatures {
+            self.set_witness(witness_index, witness);
+        }
+
+        let estimated = inputs(&other.transaction)
+            .iter()
+            .zip(&other.inputs)
+            .enumerate()
+            .filter(|(index, (_, metadata))| {
+                metadata.predicate == PredicateEstimation::Estimated
+                    && self.inputs[*index].predicate == PredicateEstimation::Pending
+            })
+            .filter_map(|(index, (input, _))| Some((index, input.predicate_gas_used()?)))
+            .collect::<Vec<_>>();
+        for (index, gas_used) in estimated {
+            self.set_predicate_gas_used(index, gas_used);
+        }
+
+        self.refresh();
+
+        Ok(())
+    }
+
+    /// Extract the complete transaction, checking that it was built against the
+    /// consensus parameters.
+    pub fn finalize(
+        self,
+        consensus_parameters: &ConsensusParameters,
+    ) -> Result<Transaction, PartiallySignedError> {
+        if self.chain_id != consensus_parameters.chain_id()
+            || self.consensus_parameters_hash != consensus_parameters.compute_hash()
+        {
+            return Err(PartiallySignedError::ConsensusParametersMismatch)
+        }
+
+        // The metadata may come from an untrusted party, so it's computed again
+        let mut partially_signed = self;
+        partially_signed.refresh();
+
+        if let Some(missing) = partially_signed.missing_signatures.first() {
+            return Err(PartiallySignedError::MissingSignature(missing.owner))
+        }
+
+        // Combined or deserialized witnesses may be anything but the signatures
+        for (owner, witness_index) in signers(inputs(&partially_signed.transaction)) {
+            let witness = witnesses(&partially_signed.transaction)
+                .get(witness_index as usize)
+                .cloned()
+                .unwrap_or_default();
+            partially_signed.verify(owner, &witness)?;
+        }
+
+        if let Some(index) = partially_signed
+            .inputs
+            .iter()
+            .position(|input| input.predicate == PredicateEstimation::Pending)
+        {
+            return Err(PartiallySignedError::PredicateNotEstimated(index))
+        }
+
+        let mut transaction = partially_signed.transaction;
+        transaction.precompute(&partially_signed.chain_id)?;
+
+        Ok(transaction)
+    }
+
+    /// Check that the witness is a signature of the message by the owner.
+    fn verify(
+        &self,
+        owner: Address,
+        witness: &Witness,
+    ) -> Result<(), PartiallySignedError> {
+        let id = self.transaction.id(&self.chain_id);
+
+        match witness.recover_witness(&id, 0) {
+            Ok(recovered) if recovered == owner => Ok(()),
+            _ => Err(PartiallySignedError::InvalidSignature(owner)),
+        }
+    }
+
+    /// Compute the metadata from the transaction.
+    fn refresh(&mut self) {
+        let inputs = inputs(&self.transaction);
+        let witnesses = witnesses(&self.transaction);
+
+        let signed = |witness_index: u8| {
+            witnesses
+                .get(witness_index as usize)
+                .is_some_and(|witness| !witness.as_ref().is_empty())
+        };
+
+        self.missing_signatures = signers(inputs)
+            .into_iter()
+            .filter(|(_, witness_index)| !signed(*witness_index))
+            .map(|(owner, witness_index)| MissingSignature {
+                owner,
+                witness_index,
+            })
+            .collect();
+        self.inputs = inputs.iter().map(InputMetadata::from_input).collect();
+    }
+
+    fn set_witness(&mut self, index: u8, witness: Witness) {
+        let witnesses = match &mut self.transaction {
+            Transaction::Script(script) => script.witnesses_mut(),
+            Transaction::Create(create) => create.witnesses_mut(),
+            Transaction::Mint(_) => return,
+        };
+
+        if let Some(slot) = witnesses.get_mut(index as usize) {
+            *slot = witness;
+        }
+    }
+
+    fn set_predicate_gas_used(&mut self, index: usize, gas_used: Word) {
+        let inputs = match &mut self.transaction {
+            Transaction::Script(script) => script.inputs_mut(),
+            Transaction::Create(create) => create.inputs_mut(),
+            Transaction::Mint(_) => return,
+        };
+
+        match inputs.get_mut(index) {
+            Some(Input::CoinPredicate(CoinPredicate {
+                predicate_gas_used, ..
+            }))
+            | Some(Input::MessageCoinPredicate(MessageCoinPredicate {
+                predicate_gas_used,
+                ..
+            }))
+            | Some(Input::MessageDataPredicate(MessageDataPredicate {
+                predicate_gas_used,
+                ..
+            })) => *predicate_gas_used = gas_used,
+            _ => {}
+        }
+    }
+}
+
+/// The owners of the signed inputs, with the indexes of their witnesses.
+fn signers(inputs: &[Input]) -> Vec<(Address, u8)> {
+    let mut signers = Vec::<(Address, u8)>::new();
+    for input in inputs {
+        let signer = match input {
+            Input::CoinSigned(CoinSigned {
+                owner,
+                witness_index,
+                ..
+            })
+            | Input::MessageCoinSigned(MessageCoinSigned {
+                recipient: owner,
+                witness_index,
+                ..
+            })
+            | Input::MessageDataSigned(MessageDataSigned {
+                recipient: owner,
+                witness_index,
+                ..
+            }) => (*owner, *witness_index),
+            _ => continue,
+        };
+
+        if !signers.contains(&signer) {
+            signers.push(signer);
+        }
+    }
+    signers
+}
+
+fn inputs(transaction: &Transaction) -> &[Input] {
+    match transaction {
+        Transaction::Script(script) => script.inputs(),
+        Transaction::Create(create) => create.inputs(),
+        Transaction::Mint(_) => &[],
+    }
+}
+
+fn witnesses(transaction: &Transaction) -> &[Witness] {
+    match transaction {
+        Transaction::Script(script) => script.witnesses(),
+        Transaction::Create(create) => create.witnesses(),
+        Transaction::Mint(_) => &[],
+    }
+}
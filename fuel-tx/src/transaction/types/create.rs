@@ -1,7 +1,12 @@
 use crate::{
     transaction::validity::{
         check_common_part,
+        check_input_signatures,
+        collect_violations,
+        FirstViolation,
         FormatValidityChecks,
+        Violation,
+        Violations,
     },
     ConsensusParameters,
 };
@@ -186,6 +191,41 @@ impl Chargeable for Create {
 
 impl FormatValidityChecks for Create {
     fn check_signatures(&self, chain_id: &ChainId) -> Result<(), CheckError> {
+        self.report_signatures(chain_id, &mut FirstViolation)
+    }
+
+    fn check_without_signatures(
+        &self,
+        block_height: BlockHeight,
+        consensus_params: &ConsensusParameters,
+    ) -> Result<(), CheckError> {
+        self.report_without_signatures(
+            block_height,
+            consensus_params,
+            &mut FirstViolation,
+        )
+    }
+
+    fn check_all(
+        &self,
+        block_height: BlockHeight,
+        consensus_params: &ConsensusParameters,
+    ) -> Result<(), Vec<Violation>> {
+        collect_violations(&[
+            &|violations| {
+                self.report_without_signatures(block_height, consensus_params, violations)
+            },
+            &|violations| self.report_signatures(&consensus_params.chain_id, violations),
+        ])
+    }
+}
+
+impl Create {
+    fn report_signatures(
+        &self,
+        chain_id: &ChainId,
+        violations: &mut dyn Violations,
+    ) -> Result<(), CheckError> {
         use crate::UniqueIdentifier;
 
         let id = self.id(chain_id);
@@ -193,24 +233,18 @@ impl FormatValidityChecks for Create {
         // There will be at most len(witnesses) - 1 signatures to cache, as one of the
         // witnesses will be bytecode
         let mut recovery_cache = Some(HashMap::with_capacity(core::cmp::max(
-            self.witnesses().len() - 1,
+            self.witnesses().len().saturating_sub(1),
             1,
         )));
 
-        self.inputs()
-            .iter()
-            .enumerate()
-            .try_for_each(|(index, input)| {
-                input.check_signature(index, &id, &self.witnesses, &mut recovery_cache)
-            })?;
-
-        Ok(())
+        check_input_signatures(self, &id, &mut recovery_cache, violations)
     }
 
-    fn check_without_signatures(
+    fn report_without_signatures(
         &self,
         block_height: BlockHeight,
         consensus_params: &ConsensusParameters,
+        violations: &mut dyn Violations,
     ) -> Result<(), CheckError> {
         let ConsensusParameters {
             tx_params,
@@ -227,24 +261,39 @@ impl FormatValidityChecks for Create {
             tx_params,
             predicate_params,
             base_asset_id,
+            violations,
         )?;
 
-        let bytecode_witness_len = self
-            .witnesses
-            .get(self.bytecode_witness_index as usize)
-            .map(|w| w.as_ref().len() as Word)
-            .ok_or(CheckError::TransactionCreateBytecodeWitnessIndex)?;
-
-        if bytecode_witness_len > contract_params.contract_max_size
-            || bytecode_witness_len / 4 != self.bytecode_length
-        {
-            return Err(CheckError::TransactionCreateBytecodeLen)
+        let bytecode_witness_index = self.bytecode_witness_index as usize;
+        match self.witnesses.get(bytecode_witness_index) {
+            Some(witness) => {
+                let bytecode_witness_len = witness.as_ref().len() as Word;
+                if bytecode_witness_len > contract_params.contract_max_size {
+                    violations.report(Violation::witness(
+                        bytecode_witness_index,
+                        Some("data"),
+                        CheckError::TransactionCreateBytecodeLen,
+                    ))?;
+                } else if bytecode_witness_len / 4 != self.bytecode_length {
+                    violations.report(Violation::transaction(
+                        "bytecode_length",
+                        CheckError::TransactionCreateBytecodeLen,
+                    ))?;
+                }
+            }
+            None => violations.report(Violation::transaction(
+                "bytecode_witness_index",
+                CheckError::TransactionCreateBytecodeWitnessIndex,
+            ))?,
         }
 
         // Restrict to subset of u16::MAX, allowing this to be increased in the future
         // in a non-breaking way.
         if self.storage_slots.len() > contract_params.max_storage_slots as usize {
-            return Err(CheckError::TransactionCreateStorageSlotMax)
+            violations.report(Violation::transaction(
+                "storage_slots",
+                CheckError::TransactionCreateStorageSlotMax,
+            ))?;
         }
 
         // Verify storage slots are sorted
@@ -254,74 +303,108 @@ impl FormatValidityChecks for Create {
             .windows(2)
             .all(|s| s[0] < s[1])
         {
-            return Err(CheckError::TransactionCreateStorageSlotOrder)
+            violations.report(Violation::transaction(
+                "storage_slots",
+                CheckError::TransactionCreateStorageSlotOrder,
+            ))?;
         }
 
         self.inputs
             .iter()
             .enumerate()
             .try_for_each(|(index, input)| match input {
-                Input::Contract(_) => {
-                    Err(CheckError::TransactionCreateInputContract { index })
-                }
+                Input::Contract(_) => violations.report(Violation::input(
+                    index,
+                    None,
+                    CheckError::TransactionCreateInputContract { index },
+                )),
                 Input::MessageDataSigned(_) | Input::MessageDataPredicate(_) => {
-                    Err(CheckError::TransactionCreateMessageData { index })
+                    violations.report(Violation::input(
+                        index,
+                        None,
+                        CheckError::TransactionCreateMessageData { index },
+                    ))
                 }
                 _ => Ok(()),
             })?;
 
-        debug_assert!(
-            self.metadata.is_some(),
-            "`check_without_signatures` is called without cached metadata"
-        );
-        let (state_root_calculated, contract_id_calculated) =
-            if let Some(metadata) = &self.metadata {
-                (metadata.state_root, metadata.contract_id)
-            } else {
-                let metadata = CreateMetadata::compute(self, chain_id)?;
-                (metadata.state_root, metadata.contract_id)
-            };
+        let calculated = match &self.metadata {
+            Some(metadata) => Some((metadata.state_root, metadata.contract_id)),
+            None => {
+                // The metadata can't be computed without the bytecode witness, which
+                // is already reported. Otherwise, it should have been cached.
+                let metadata = CreateMetadata::compute(self, chain_id).ok();
+                debug_assert!(
+                    metadata.is_none(),
+                    "`check_without_signatures` is called without cached metadata"
+                );
+                metadata.map(|metadata| (metadata.state_root, metadata.contract_id))
+            }
+        };
 
         let mut contract_created = false;
         self.outputs
             .iter()
             .enumerate()
             .try_for_each(|(index, output)| match output {
-                Output::Contract(_) => {
-                    Err(CheckError::TransactionCreateOutputContract { index })
-                }
-
-                Output::Variable { .. } => {
-                    Err(CheckError::TransactionCreateOutputVariable { index })
-                }
-
-                Output::Change { asset_id, .. } if asset_id != base_asset_id => {
-                    Err(CheckError::TransactionCreateOutputChangeNotBaseAsset { index })
-                }
+                Output::Contract(_) => violations.report(Violation::output(
+                    index,
+                    None,
+                    CheckError::TransactionCreateOutputContract { index },
+                )),
+
+                Output::Variable { .. } => violations.report(Violation::output(
+                    index,
+                    None,
+                    CheckError::TransactionCreateOutputVariable { index },
+                )),
+
+                Output::Change { asset_id, .. } if asset_id != base_asset_id => violations
+                    .report(Violation::output(
+                        index,
+                        Some("asset_id"),
+                        CheckError::TransactionCreateOutputChangeNotBaseAsset { index },
+                    )),
 
                 Output::ContractCreated {
                     contract_id,
                     state_root,
-                } if contract_id != &contract_id_calculated
-                    || state_root != &state_root_calculated =>
-                {
-                    Err(
-                        CheckError::TransactionCreateOutputContractCreatedDoesntMatch {
+                } => {
+                    if let Some((state_root_calculated, contract_id_calculated)) =
+                        calculated
+                    {
+                        let field = if contract_id != &contract_id_calculated {
+                            Some("contract_id")
+                        } else if state_root != &state_root_calculated {
+                            Some("state_root")
+                        } else {
+                            None
+                        };
+
+                        if let Some(field) = field {
+                            return violations.report(Violation::output(
+                                index,
+                                Some(field),
+                                CheckError::TransactionCreateOutputContractCreatedDoesntMatch {
+                                    index,
+                                },
+                            ))
+                        }
+                    }
+
+                    // TODO: Output::ContractCreated { contract_id, state_root } if
+                    // contract_id == &id && state_root == &storage_root
+                    //  maybe move from `fuel-vm` to here
+                    if contract_created {
+                        return violations.report(Violation::output(
                             index,
-                        },
-                    )
-                }
-
-                // TODO: Output::ContractCreated { contract_id, state_root } if
-                // contract_id == &id && state_root == &storage_root
-                //  maybe move from `fuel-vm` to here
-                Output::ContractCreated { .. } if contract_created => {
-                    Err(CheckError::TransactionCreateOutputContractCreatedMultiple {
-                        index,
-                    })
-                }
+                            None,
+                            CheckError::TransactionCreateOutputContractCreatedMultiple {
+                                index,
+                            },
+                        ))
+                    }
 
-                Output::ContractCreated { .. } => {
                     contract_created = true;
 
                     Ok(())
@@ -3,7 +3,13 @@ use crate::{
     output,
     transaction::{
         field::TxPointer as TxPointerField,
-        validity::FormatValidityChecks,
+        validity::{
+            collect_violations,
+            FirstViolation,
+            FormatValidityChecks,
+            Violation,
+            Violations,
+        },
     },
     CheckError,
     ConsensusParameters,
@@ -23,6 +29,8 @@ use fuel_types::ChainId;
 
 use fuel_types::canonical::Serialize;
 
+use alloc::vec::Vec;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub(crate) struct MintMetadata {
     pub id: Bytes32,
@@ -93,18 +101,48 @@ impl FormatValidityChecks for Mint {
         &self,
         block_height: BlockHeight,
         consensus_params: &ConsensusParameters,
+    ) -> Result<(), CheckError> {
+        self.report_violations(block_height, consensus_params, &mut FirstViolation)
+    }
+
+    fn check_all(
+        &self,
+        block_height: BlockHeight,
+        consensus_params: &ConsensusParameters,
+    ) -> Result<(), Vec<Violation>> {
+        collect_violations(&[&|violations| {
+            self.report_violations(block_height, consensus_params, violations)
+        }])
+    }
+}
+
+impl Mint {
+    fn report_violations(
+        &self,
+        block_height: BlockHeight,
+        consensus_params: &ConsensusParameters,
+        violations: &mut dyn Violations,
     ) -> Result<(), CheckError> {
         if self.tx_pointer().block_height() != block_height {
-            return Err(CheckError::TransactionMintIncorrectBlockHeight)
+            violations.report(Violation::transaction(
+                "tx_pointer",
+                CheckError::TransactionMintIncorrectBlockHeight,
+            ))?;
         }
 
         if self.output_contract.input_index != 0 {
-            return Err(CheckError::TransactionMintIncorrectOutputIndex)
+            violations.report(Violation::transaction(
+                "output_contract",
+                CheckError::TransactionMintIncorrectOutputIndex,
+            ))?;
         }
 
         // It is temporary check until https://github.com/FuelLabs/fuel-core/issues/1205
         if self.mint_asset_id != consensus_params.base_asset_id {
-            return Err(CheckError::TransactionMintNonBaseAsset)
+            violations.report(Violation::transaction(
+                "mint_asset_id",
+                CheckError::TransactionMintNonBaseAsset,
+            ))?;
         }
 
         Ok(())
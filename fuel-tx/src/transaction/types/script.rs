@@ -15,7 +15,12 @@ use crate::{
         metadata::CommonMetadata,
         validity::{
             check_common_part,
+            check_input_signatures,
+            collect_violations,
+            FirstViolation,
             FormatValidityChecks,
+            Violation,
+            Violations,
         },
         Chargeable,
     },
@@ -149,6 +154,41 @@ impl Chargeable for Script {
 
 impl FormatValidityChecks for Script {
     fn check_signatures(&self, chain_id: &ChainId) -> Result<(), CheckError> {
+        self.report_signatures(chain_id, &mut FirstViolation)
+    }
+
+    fn check_without_signatures(
+        &self,
+        block_height: BlockHeight,
+        consensus_params: &ConsensusParameters,
+    ) -> Result<(), CheckError> {
+        self.report_without_signatures(
+            block_height,
+            consensus_params,
+            &mut FirstViolation,
+        )
+    }
+
+    fn check_all(
+        &self,
+        block_height: BlockHeight,
+        consensus_params: &ConsensusParameters,
+    ) -> Result<(), Vec<Violation>> {
+        collect_violations(&[
+            &|violations| {
+                self.report_without_signatures(block_height, consensus_params, violations)
+            },
+            &|violations| self.report_signatures(&consensus_params.chain_id, violations),
+        ])
+    }
+}
+
+impl Script {
+    fn report_signatures(
+        &self,
+        chain_id: &ChainId,
+        violations: &mut dyn Violations,
+    ) -> Result<(), CheckError> {
         use crate::UniqueIdentifier;
 
         let id = self.id(chain_id);
@@ -156,20 +196,14 @@ impl FormatValidityChecks for Script {
         // There will be at most len(witnesses) signatures to cache
         let mut recovery_cache = Some(HashMap::with_capacity(self.witnesses().len()));
 
-        self.inputs()
-            .iter()
-            .enumerate()
-            .try_for_each(|(index, input)| {
-                input.check_signature(index, &id, &self.witnesses, &mut recovery_cache)
-            })?;
-
-        Ok(())
+        check_input_signatures(self, &id, &mut recovery_cache, violations)
     }
 
-    fn check_without_signatures(
+    fn report_without_signatures(
         &self,
         block_height: BlockHeight,
         consensus_params: &ConsensusParameters,
+        violations: &mut dyn Violations,
     ) -> Result<(), CheckError> {
         check_common_part(
             self,
@@ -177,23 +211,32 @@ impl FormatValidityChecks for Script {
             consensus_params.tx_params(),
             consensus_params.predicate_params(),
             consensus_params.base_asset_id(),
+            violations,
         )?;
         let script_params = consensus_params.script_params();
         if self.script.len() > script_params.max_script_length as usize {
-            Err(CheckError::TransactionScriptLength)?;
+            violations.report(Violation::transaction(
+                "script",
+                CheckError::TransactionScriptLength,
+            ))?;
         }
 
         if self.script_data.len() > script_params.max_script_data_length as usize {
-            Err(CheckError::TransactionScriptDataLength)?;
+            violations.report(Violation::transaction(
+                "script_data",
+                CheckError::TransactionScriptDataLength,
+            ))?;
         }
 
         self.outputs
             .iter()
             .enumerate()
             .try_for_each(|(index, output)| match output {
-                Output::ContractCreated { .. } => {
-                    Err(CheckError::TransactionScriptOutputContractCreated { index })
-                }
+                Output::ContractCreated { .. } => violations.report(Violation::output(
+                    index,
+                    None,
+                    CheckError::TransactionScriptOutputContractCreated { index },
+                )),
                 _ => Ok(()),
             })?;
 
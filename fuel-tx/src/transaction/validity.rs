@@ -5,7 +5,11 @@ use crate::{
     Output,
     Witness,
 };
-use core::hash::Hash;
+use alloc::{
+    collections::BTreeSet,
+    vec,
+    vec::Vec,
+};
 
 use fuel_types::{
     AssetId,
@@ -21,9 +25,9 @@ use fuel_types::{
 };
 
 use hashbrown::HashMap;
-use itertools::Itertools;
 
 mod error;
+mod violation;
 
 use crate::{
     input::{
@@ -48,6 +52,15 @@ use crate::{
     },
 };
 pub use error::CheckError;
+pub(crate) use violation::{
+    collect_violations,
+    FirstViolation,
+    Violations,
+};
+pub use violation::{
+    Location,
+    Violation,
+};
 
 impl Input {
     pub fn check(
@@ -71,6 +84,39 @@ impl Input {
         txhash: &Bytes32,
         witnesses: &[Witness],
         recovery_cache: &mut Option<HashMap<u8, Address>>,
+    ) -> Result<(), CheckError> {
+        self.report_signature(
+            index,
+            txhash,
+            witnesses,
+            recovery_cache,
+            &mut FirstViolation,
+        )
+    }
+
+    pub fn check_without_signature(
+        &self,
+        index: usize,
+        outputs: &[Output],
+        witnesses: &[Witness],
+        predicate_params: &PredicateParameters,
+    ) -> Result<(), CheckError> {
+        self.report_without_signature(
+            index,
+            outputs,
+            witnesses,
+            predicate_params,
+            &mut FirstViolation,
+        )
+    }
+
+    pub(crate) fn report_signature(
+        &self,
+        index: usize,
+        txhash: &Bytes32,
+        witnesses: &[Witness],
+        recovery_cache: &mut Option<HashMap<u8, Address>>,
+        violations: &mut dyn Violations,
     ) -> Result<(), CheckError> {
         match self {
             Self::CoinSigned(CoinSigned {
@@ -101,23 +147,35 @@ impl Input {
                 // available
                 let recovered_address = if let Some(cache) = recovery_cache {
                     if let Some(recovered_address) = cache.get(witness_index) {
-                        *recovered_address
+                        Ok(*recovered_address)
                     } else {
                         // if this witness hasn't been recovered before,
                         // cache ecrecover by witness index
-                        let recovered_address = recover_address()?;
-                        cache.insert(*witness_index, recovered_address);
-                        recovered_address
+                        match recover_address() {
+                            Ok(recovered_address) => {
+                                cache.insert(*witness_index, recovered_address);
+                                Ok(recovered_address)
+                            }
+                            error => error,
+                        }
                     }
                 } else {
-                    recover_address()?
+                    recover_address()
                 };
 
-                if owner != &recovered_address {
-                    return Err(CheckError::InputInvalidSignature { index })
+                match recovered_address {
+                    Ok(recovered_address) if owner == &recovered_address => Ok(()),
+                    Ok(_) => violations.report(Violation::input(
+                        index,
+                        Some("owner"),
+                        CheckError::InputInvalidSignature { index },
+                    )),
+                    Err(error) => violations.report(Violation::input(
+                        index,
+                        Some("witness_index"),
+                        error,
+                    )),
                 }
-
-                Ok(())
             }
 
             Self::CoinPredicate(CoinPredicate {
@@ -132,48 +190,63 @@ impl Input {
                 recipient: owner,
                 predicate,
                 ..
-            }) if !Input::is_predicate_owner_valid(owner, predicate) => {
-                Err(CheckError::InputPredicateOwner { index })
-            }
+            }) if !Input::is_predicate_owner_valid(owner, predicate) => violations
+                .report(Violation::input(
+                    index,
+                    Some("owner"),
+                    CheckError::InputPredicateOwner { index },
+                )),
 
             _ => Ok(()),
         }
     }
 
-    pub fn check_without_signature(
+    pub(crate) fn report_without_signature(
         &self,
         index: usize,
         outputs: &[Output],
         witnesses: &[Witness],
         predicate_params: &PredicateParameters,
+        violations: &mut dyn Violations,
     ) -> Result<(), CheckError> {
-        match self {
-            Self::CoinPredicate(CoinPredicate { predicate, .. })
-            | Self::MessageCoinPredicate(MessageCoinPredicate { predicate, .. })
-            | Self::MessageDataPredicate(MessageDataPredicate { predicate, .. })
-                if predicate.is_empty() =>
-            {
-                Err(CheckError::InputPredicateEmpty { index })
-            }
-
-            Self::CoinPredicate(CoinPredicate { predicate, .. })
-            | Self::MessageCoinPredicate(MessageCoinPredicate { predicate, .. })
-            | Self::MessageDataPredicate(MessageDataPredicate { predicate, .. })
-                if predicate.len() > predicate_params.max_predicate_length as usize =>
-            {
-                Err(CheckError::InputPredicateLength { index })
-            }
+        let mut report =
+            |field, error| violations.report(Violation::input(index, field, error));
 
-            Self::CoinPredicate(CoinPredicate { predicate_data, .. })
+        match self {
+            Self::CoinPredicate(CoinPredicate {
+                predicate,
+                predicate_data,
+                ..
+            })
             | Self::MessageCoinPredicate(MessageCoinPredicate {
-                predicate_data, ..
+                predicate,
+                predicate_data,
+                ..
             })
             | Self::MessageDataPredicate(MessageDataPredicate {
-                predicate_data, ..
-            }) if predicate_data.len()
-                > predicate_params.max_predicate_data_length as usize =>
-            {
-                Err(CheckError::InputPredicateDataLength { index })
+                predicate,
+                predicate_data,
+                ..
+            }) => {
+                if predicate.is_empty() {
+                    report(Some("predicate"), CheckError::InputPredicateEmpty { index })?;
+                }
+
+                if predicate.len() > predicate_params.max_predicate_length as usize {
+                    report(
+                        Some("predicate"),
+                        CheckError::InputPredicateLength { index },
+                    )?;
+                }
+
+                if predicate_data.len()
+                    > predicate_params.max_predicate_data_length as usize
+                {
+                    report(
+                        Some("predicate_data"),
+                        CheckError::InputPredicateDataLength { index },
+                    )?;
+                }
             }
 
             Self::CoinSigned(CoinSigned { witness_index, .. })
@@ -181,7 +254,10 @@ impl Input {
             | Self::MessageDataSigned(MessageDataSigned { witness_index, .. })
                 if *witness_index as usize >= witnesses.len() =>
             {
-                Err(CheckError::InputWitnessIndexBounds { index })
+                report(
+                    Some("witness_index"),
+                    CheckError::InputWitnessIndexBounds { index },
+                )?;
             }
 
             // ∀ inputContract ∃! outputContract : outputContract.inputIndex =
@@ -198,15 +274,22 @@ impl Input {
                     })
                     .count() =>
             {
-                Err(CheckError::InputContractAssociatedOutputContract { index })
+                report(
+                    None,
+                    CheckError::InputContractAssociatedOutputContract { index },
+                )?;
             }
 
+            _ => (),
+        }
+
+        match self {
             Self::MessageDataSigned(MessageDataSigned { data, .. })
             | Self::MessageDataPredicate(MessageDataPredicate { data, .. })
                 if data.is_empty()
                     || data.len() > predicate_params.max_message_data_length as usize =>
             {
-                Err(CheckError::InputMessageDataLength { index })
+                report(Some("data"), CheckError::InputMessageDataLength { index })
             }
 
             // TODO: If h is the block height the UTXO being spent was created,
@@ -224,11 +307,24 @@ impl Output {
     /// because the VM might mutate the message outputs, producing invalid
     /// transactions.
     pub fn check(&self, index: usize, inputs: &[Input]) -> Result<(), CheckError> {
+        self.report_violations(index, inputs, &mut FirstViolation)
+    }
+
+    pub(crate) fn report_violations(
+        &self,
+        index: usize,
+        inputs: &[Input],
+        violations: &mut dyn Violations,
+    ) -> Result<(), CheckError> {
         match self {
             Self::Contract(output::contract::Contract { input_index, .. }) => {
                 match inputs.get(*input_index as usize) {
                     Some(Input::Contract { .. }) => Ok(()),
-                    _ => Err(CheckError::OutputContractInputIndex { index }),
+                    _ => violations.report(Violation::output(
+                        index,
+                        Some("input_index"),
+                        CheckError::OutputContractInputIndex { index },
+                    )),
                 }
             }
 
@@ -265,6 +361,29 @@ pub trait FormatValidityChecks {
         block_height: BlockHeight,
         consensus_params: &ConsensusParameters,
     ) -> Result<(), CheckError>;
+
+    /// Performs the same checks as [`Self::check`], but instead of stopping at the first
+    /// failure, returns every violation found, in the order they were checked.
+    ///
+    /// Checks that depend on a part of the transaction that is already invalid are
+    /// skipped, e.g. the length of the bytecode isn't checked if the bytecode witness
+    /// doesn't exist.
+    ///
+    /// The default implementation stops at the first failure of [`Self::check`], and
+    /// reports it as a violation of the transaction.
+    fn check_all(
+        &self,
+        block_height: BlockHeight,
+        consensus_params: &ConsensusParameters,
+    ) -> Result<(), Vec<Violation>> {
+        self.check(block_height, consensus_params).map_err(|error| {
+            vec![Violation {
+                location: Location::Transaction,
+                field: None,
+                error,
+            }]
+        })
+    }
 }
 
 impl FormatValidityChecks for Transaction {
@@ -293,6 +412,22 @@ impl FormatValidityChecks for Transaction {
             }
         }
     }
+
+    fn check_all(
+        &self,
+        block_height: BlockHeight,
+        consensus_params: &ConsensusParameters,
+    ) -> Result<(), Vec<Violation>> {
+        match self {
+            Transaction::Script(script) => {
+                script.check_all(block_height, consensus_params)
+            }
+            Transaction::Create(create) => {
+                create.check_all(block_height, consensus_params)
+            }
+            Transaction::Mint(mint) => mint.check_all(block_height, consensus_params),
+        }
+    }
 }
 
 pub(crate) fn check_common_part<T>(
@@ -301,6 +436,7 @@ pub(crate) fn check_common_part<T>(
     tx_params: &TxParameters,
     predicate_params: &PredicateParameters,
     base_asset_id: &AssetId,
+    violations: &mut dyn Violations,
 ) -> Result<(), CheckError>
 where
     T: field::GasPrice
@@ -311,23 +447,38 @@ where
         + field::Witnesses,
 {
     if tx.gas_limit() > &tx_params.max_gas_per_tx {
-        Err(CheckError::TransactionGasLimit)?
+        violations.report(Violation::transaction(
+            "gas_limit",
+            CheckError::TransactionGasLimit,
+        ))?;
     }
 
     if tx.maturity() > &block_height {
-        Err(CheckError::TransactionMaturity)?;
+        violations.report(Violation::transaction(
+            "maturity",
+            CheckError::TransactionMaturity,
+        ))?;
     }
 
     if tx.inputs().len() > tx_params.max_inputs as usize {
-        Err(CheckError::TransactionInputsMax)?
+        violations.report(Violation::transaction(
+            "inputs",
+            CheckError::TransactionInputsMax,
+        ))?;
     }
 
     if tx.outputs().len() > tx_params.max_outputs as usize {
-        Err(CheckError::TransactionOutputsMax)?
+        violations.report(Violation::transaction(
+            "outputs",
+            CheckError::TransactionOutputsMax,
+        ))?;
     }
 
     if tx.witnesses().len() > tx_params.max_witnesses as usize {
-        Err(CheckError::TransactionWitnessesMax)?
+        violations.report(Violation::transaction(
+            "witnesses",
+            CheckError::TransactionWitnessesMax,
+        ))?;
     }
 
     let any_spendable_input = tx.inputs().iter().find(|input| match input {
@@ -341,58 +492,76 @@ where
     });
 
     if any_spendable_input.is_none() {
-        Err(CheckError::NoSpendableInput)?
+        violations.report(Violation::transaction(
+            "inputs",
+            CheckError::NoSpendableInput,
+        ))?;
     }
 
     tx.input_asset_ids_unique(base_asset_id)
         .try_for_each(|input_asset_id| {
             // check for duplicate change outputs
-            if tx
+            let duplicate = tx
                 .outputs()
                 .iter()
-                .filter_map(|output| match output {
-                    Output::Change { asset_id, .. } if input_asset_id == asset_id => {
-                        Some(())
-                    }
+                .enumerate()
+                .filter(|(_, output)| match output {
+                    Output::Change { asset_id, .. } if input_asset_id == asset_id => true,
                     Output::Change { asset_id, .. }
                         if asset_id != base_asset_id && input_asset_id == asset_id =>
                     {
-                        Some(())
+                        true
                     }
-                    _ => None,
+                    _ => false,
                 })
-                .count()
-                > 1
-            {
-                return Err(CheckError::TransactionOutputChangeAssetIdDuplicated(
-                    *input_asset_id,
-                ))
+                .nth(1);
+
+            match duplicate {
+                Some((index, _)) => violations.report(Violation::output(
+                    index,
+                    Some("asset_id"),
+                    CheckError::TransactionOutputChangeAssetIdDuplicated(*input_asset_id),
+                )),
+                None => Ok(()),
             }
-
-            Ok(())
         })?;
 
     // Check for duplicated input utxo id
-    let duplicated_utxo_id = tx
+    let utxo_ids = tx
         .inputs()
         .iter()
-        .filter_map(|i| i.is_coin().then(|| i.utxo_id()).flatten());
-
-    if let Some(utxo_id) = next_duplicate(duplicated_utxo_id).copied() {
-        return Err(CheckError::DuplicateInputUtxoId { utxo_id })
+        .map(|i| i.is_coin().then(|| i.utxo_id()).flatten());
+
+    for (index, utxo_id) in duplicates(utxo_ids) {
+        violations.report(Violation::input(
+            index,
+            Some("utxo_id"),
+            CheckError::DuplicateInputUtxoId { utxo_id: *utxo_id },
+        ))?;
     }
 
     // Check for duplicated input contract id
-    let duplicated_contract_id = tx.inputs().iter().filter_map(Input::contract_id);
-
-    if let Some(contract_id) = next_duplicate(duplicated_contract_id).copied() {
-        return Err(CheckError::DuplicateInputContractId { contract_id })
+    let contract_ids = tx.inputs().iter().map(Input::contract_id);
+
+    for (index, contract_id) in duplicates(contract_ids) {
+        violations.report(Violation::input(
+            index,
+            Some("contract_id"),
+            CheckError::DuplicateInputContractId {
+                contract_id: *contract_id,
+            },
+        ))?;
     }
 
     // Check for duplicated input message id
-    let duplicated_message_id = tx.inputs().iter().filter_map(Input::message_id);
-    if let Some(message_id) = next_duplicate(duplicated_message_id) {
-        return Err(CheckError::DuplicateMessageInputId { message_id })
+    let message_ids = tx.inputs().iter().map(Input::message_id);
+
+    for (index, message_id) in duplicates(message_ids) {
+        violations.report(Violation::input(
+            index,
+            Some("nonce"),
+            CheckError::DuplicateMessageInputId { message_id },
+        ))?;
     }
 
     // Validate the inputs without checking signature
@@ -400,11 +569,12 @@ where
         .iter()
         .enumerate()
         .try_for_each(|(index, input)| {
-            input.check_without_signature(
+            input.report_without_signature(
                 index,
                 tx.outputs(),
                 tx.witnesses(),
                 predicate_params,
+                violations,
             )
         })?;
 
@@ -412,16 +582,18 @@ where
         .iter()
         .enumerate()
         .try_for_each(|(index, output)| {
-            output.check(index, tx.inputs())?;
+            output.report_violations(index, tx.inputs(), violations)?;
 
             if let Output::Change { asset_id, .. } = output {
                 if !tx
                     .input_asset_ids(base_asset_id)
                     .any(|input_asset_id| input_asset_id == asset_id)
                 {
-                    return Err(CheckError::TransactionOutputChangeAssetIdNotFound(
-                        *asset_id,
-                    ))
+                    violations.report(Violation::output(
+                        index,
+                        Some("asset_id"),
+                        CheckError::TransactionOutputChangeAssetIdNotFound(*asset_id),
+                    ))?;
                 }
             }
 
@@ -430,9 +602,11 @@ where
                     .input_asset_ids(base_asset_id)
                     .any(|input_asset_id| input_asset_id == asset_id)
                 {
-                    return Err(CheckError::TransactionOutputCoinAssetIdNotFound(
-                        *asset_id,
-                    ))
+                    violations.report(Violation::output(
+                        index,
+                        Some("asset_id"),
+                        CheckError::TransactionOutputCoinAssetIdNotFound(*asset_id),
+                    ))?;
                 }
             }
 
@@ -442,22 +616,36 @@ where
     Ok(())
 }
 
-// TODO https://github.com/FuelLabs/fuel-tx/issues/148
-pub(crate) fn next_duplicate<U>(iter: impl Iterator<Item = U>) -> Option<U>
+/// Validate the signatures of the inputs.
+pub(crate) fn check_input_signatures<T>(
+    tx: &T,
+    id: &Bytes32,
+    recovery_cache: &mut Option<HashMap<u8, Address>>,
+    violations: &mut dyn Violations,
+) -> Result<(), CheckError>
 where
-    U: PartialEq + Ord + Copy + Hash,
+    T: field::Inputs + field::Witnesses,
 {
-    #[cfg(not(feature = "std"))]
-    {
-        iter.sorted()
-            .as_slice()
-            .windows(2)
-            .filter_map(|u| (u[0] == u[1]).then(|| u[0]))
-            .next()
-    }
+    tx.inputs()
+        .iter()
+        .enumerate()
+        .try_for_each(|(index, input)| {
+            input.report_signature(index, id, tx.witnesses(), recovery_cache, violations)
+        })
+}
 
-    #[cfg(feature = "std")]
-    {
-        iter.duplicates().next()
-    }
+/// The position and the value of the items equal to a previous one. `None` items are
+/// skipped.
+// TODO https://github.com/FuelLabs/fuel-tx/issues/148
+fn duplicates<U>(
+    iter: impl Iterator<Item = Option<U>>,
+) -> impl Iterator<Item = (usize, U)>
+where
+    U: Ord + Copy,
+{
+    let mut seen = BTreeSet::new();
+    iter.enumerate().filter_map(move |(index, item)| {
+        let item = item?;
+        (!seen.insert(item)).then_some((index, item))
+    })
 }
@@ -0,0 +1,146 @@
+use super::CheckError;
+
+use alloc::vec::Vec;
+use core::fmt;
+
+/// The part of a transaction a [`Violation`] refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Location {
+    /// A field of the transaction itself.
+    Transaction,
+    /// The input at the index.
+    Input(usize),
+    /// The output at the index.
+    Output(usize),
+    /// The witness at the index.
+    Witness(usize),
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transaction => write!(f, "transaction"),
+            Self::Input(index) => write!(f, "input {index}"),
+            Self::Output(index) => write!(f, "output {index}"),
+            Self::Witness(index) => write!(f, "witness {index}"),
+        }
+    }
+}
+
+/// A validity rule broken by a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Violation {
+    /// The offending input, output, witness or the transaction itself.
+    pub location: Location,
+    /// The field of the offending part, if the violation involves a single one.
+    pub field: Option<&'static str>,
+    /// The broken rule.
+    pub error: CheckError,
+}
+
+impl Violation {
+    /// A violation involving `field` of the transaction.
+    pub const fn transaction(field: &'static str, error: CheckError) -> Self {
+        Self {
+            location: Location::Transaction,
+            field: Some(field),
+            error,
+        }
+    }
+
+    /// A violation involving the input at `index`.
+    pub const fn input(
+        index: usize,
+        field: Option<&'static str>,
+        error: CheckError,
+    ) -> Self {
+        Self {
+            location: Location::Input(index),
+            field,
+            error,
+        }
+    }
+
+    /// A violation involving the output at `index`.
+    pub const fn output(
+        index: usize,
+        field: Option<&'static str>,
+        error: CheckError,
+    ) -> Self {
+        Self {
+            location: Location::Output(index),
+            field,
+            error,
+        }
+    }
+
+    /// A violation involving the witness at `index`.
+    pub const fn witness(
+        index: usize,
+        field: Option<&'static str>,
+        error: CheckError,
+    ) -> Self {
+        Self {
+            location: Location::Witness(index),
+            field,
+            error,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.field {
+            Some(field) => write!(f, "{} `{field}`: {}", self.location, self.error),
+            None => write!(f, "{}: {}", self.location, self.error),
+        }
+    }
+}
+
+/// Receives the violations found by the checks.
+pub(crate) trait Violations {
+    /// Report a violation. Returns its error if the checks should stop.
+    fn report(&mut self, violation: Violation) -> Result<(), CheckError>;
+}
+
+/// Stops the checks at the first violation.
+pub(crate) struct FirstViolation;
+
+impl Violations for FirstViolation {
+    fn report(&mut self, violation: Violation) -> Result<(), CheckError> {
+        Err(violation.error)
+    }
+}
+
+/// Collects all the distinct violations.
+impl Violations for Vec<Violation> {
+    fn report(&mut self, violation: Violation) -> Result<(), CheckError> {
+        if !self.contains(&violation) {
+            self.push(violation);
+        }
+
+        Ok(())
+    }
+}
+
+/// A check reporting its violations, see [`collect_violations`].
+pub(crate) type Check<'a> = &'a dyn Fn(&mut dyn Violations) -> Result<(), CheckError>;
+
+/// Run all the `checks`, collecting all the violations.
+///
+/// The collector never stops the checks, so their results are ignored: an error only
+/// skips the remaining checks of a part that is already reported as invalid.
+pub(crate) fn collect_violations(checks: &[Check<'_>]) -> Result<(), Vec<Violation>> {
+    let mut violations = Vec::new();
+    for check in checks {
+        let _ = check(&mut violations);
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
@@ -0,0 +1,106 @@
+//! Replay the transaction of a snapshot captured with
+//! [`fuel_vm::replay::Snapshot::capture`] and print its result as JSON.
+
+use std::{
+    env,
+    fs::{
+        self,
+        File,
+    },
+    io::{
+        self,
+        BufWriter,
+        Write,
+    },
+    process::ExitCode,
+};
+
+use fuel_vm::{
+    interpreter::trace::JsonLinesTracer,
+    replay::Snapshot,
+};
+
+const USAGE: &str = "\
+Usage: fuel-replay <SNAPSHOT> [--trace <FILE>]
+
+Execute the transaction of the JSON snapshot again and print the resulting state and
+receipts as JSON.
+
+Options:
+    --trace <FILE>  Write the executed instructions to FILE as JSON lines";
+
+struct Args {
+    snapshot: String,
+    trace: Option<String>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Option<Self> {
+        let mut snapshot = None;
+        let mut trace = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--trace" => trace = Some(args.next()?),
+                _ if snapshot.is_none() && !arg.starts_with('-') => snapshot = Some(arg),
+                _ => return None,
+            }
+        }
+
+        Some(Self {
+            snapshot: snapshot?,
+            trace,
+        })
+    }
+}
+
+fn main() -> ExitCode {
+    let Some(args) = Args::parse(env::args().skip(1)) else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2)
+    };
+
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), String> {
+    // The hex encoded types are deserialized from borrowed strings, so the snapshot
+    // can't be deserialized from a reader
+    let json = fs::read_to_string(&args.snapshot)
+        .map_err(|e| format!("failed to read {}: {e}", args.snapshot))?;
+    let snapshot: Snapshot = serde_json::from_str(&json)
+        .map_err(|e| format!("invalid snapshot {}: {e}", args.snapshot))?;
+
+    let transition = match &args.trace {
+        Some(path) => {
+            let file = File::create(path)
+                .map_err(|e| format!("failed to create {path}: {e}"))?;
+            let tracer = JsonLinesTracer::new(BufWriter::new(file));
+            let transition = snapshot.replay_traced(tracer.clone());
+
+            tracer
+                .take_error()
+                .map_or_else(|| tracer.with_writer(|writer| writer.flush()), Err)
+                .map_err(|e| format!("failed to write the trace to {path}: {e}"))?;
+            transition
+        }
+        None => snapshot.replay(),
+    }
+    .map_err(|e| format!("replay failed: {e}"))?;
+
+    let output = serde_json::json!({
+        "state": transition.state(),
+        "receipts": transition.receipts(),
+    });
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &output)
+        .map_err(io::Error::from)
+        .and_then(|_| writeln!(stdout))
+        .map_err(|e| format!("failed to write the result: {e}"))
+}
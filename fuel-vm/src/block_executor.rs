@@ -0,0 +1,406 @@
+//! Execution of a sequence of transactions as a block.
+
+use crate::{
+    checked_transaction::{
+        Checked,
+        CheckedTransaction,
+    },
+    crypto,
+    error::InterpreterError,
+    interpreter::{
+        Interpreter,
+        InterpreterParams,
+    },
+    state::ProgramState,
+    storage::{
+        ContractsAssets,
+        ContractsAssetsStorage,
+        ContractsState,
+        InterpreterStorage,
+        RecordingStorage,
+        StorageDiff,
+    },
+};
+
+use fuel_storage::MerkleRootStorage;
+use fuel_tx::{
+    field::ReceiptsRoot,
+    input,
+    output,
+    Chargeable,
+    Create,
+    Mint,
+    Receipt,
+    Script,
+    Transaction,
+    TransactionFee,
+    TxPointer,
+};
+use fuel_types::{
+    Bytes32,
+    ContractId,
+    Word,
+};
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+mod parallel;
+
+/// Block execution error variants.
+#[derive(Debug, derive_more::Display)]
+pub enum BlockExecutorError<StorageError> {
+    /// The transaction at `index` couldn't be executed.
+    #[display(fmt = "Transaction {} failed: {}", index, error)]
+    Transaction {
+        /// The position of the transaction in the block.
+        index: usize,
+        /// The reason of the failure.
+        error: InterpreterError<StorageError>,
+    },
+    /// A `Mint` transaction was provided; the executor produces it itself.
+    #[display(fmt = "Transaction {_0} is a `Mint` transaction")]
+    UnexpectedMint(usize),
+    /// The fees of the block don't fit into a word, or the coinbase balance overflows.
+    #[display(fmt = "The block fees overflow")]
+    FeeOverflow,
+    /// The fees can't be minted because the coinbase contract isn't deployed.
+    #[display(fmt = "Coinbase contract {_0} doesn't exist")]
+    CoinbaseNotFound(ContractId),
+    /// Storage I/O error
+    #[display(fmt = "Storage error: {}", _0)]
+    Storage(StorageError),
+}
+
+/// A transaction of the block after its execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutedTransaction {
+    /// The transaction with the outputs updated by the execution.
+    pub transaction: Transaction,
+    /// The resulting program state.
+    pub state: ProgramState,
+    /// The receipts produced by the execution.
+    pub receipts: Vec<Receipt>,
+    /// The Merkle root of the receipts.
+    pub receipts_root: Bytes32,
+    /// The fee charged for the transaction.
+    pub fee: Word,
+}
+
+impl ExecutedTransaction {
+    /// Return `true` if the storage changes of the transaction were discarded.
+    pub fn reverted(&self) -> bool {
+        matches!(self.state, ProgramState::Revert(_))
+    }
+}
+
+/// The result of the execution of a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockResult {
+    /// The executed transactions, in the block order.
+    pub transactions: Vec<ExecutedTransaction>,
+    /// The trailing coinbase transaction minting the fees of the block.
+    pub mint: Mint,
+    /// The sum of the fees of all transactions.
+    pub total_fees: Word,
+    /// The storage changes of the block, including the minted fees.
+    pub storage_diff: StorageDiff,
+}
+
+/// Executor running a sequence of transactions as a block.
+///
+/// The transactions are executed in order on top of each other. The storage changes of
+/// reverted scripts are discarded, and if any transaction can't be executed at all, the
+/// storage is left as it was before the block.
+///
+/// The fees are minted as the base asset to the [`InterpreterStorage::coinbase`]
+/// contract. To keep ownership of the storage, the executor can be created with a
+/// `&mut` reference to it.
+#[derive(Debug)]
+pub struct BlockExecutor<S> {
+    interpreter: Interpreter<RecordingStorage<S>, Script>,
+}
+
+impl<S> BlockExecutor<S>
+where
+    S: InterpreterStorage,
+{
+    /// Create a new executor on top of the provided storage.
+    pub fn new(storage: S, interpreter_params: InterpreterParams) -> Self {
+        let interpreter =
+            Interpreter::with_storage(RecordingStorage::new(storage), interpreter_params);
+
+        Self { interpreter }
+    }
+
+    /// The underlying storage.
+    pub fn storage(&self) -> &S {
+        self.interpreter.as_ref().storage()
+    }
+
+    /// Mutable access to the underlying storage.
+    pub fn storage_mut(&mut self) -> &mut S {
+        self.interpreter.as_mut().storage_mut()
+    }
+
+    /// Execute the transactions in order and produce the block.
+    pub fn execute(
+        &mut self,
+        transactions: Vec<CheckedTransaction>,
+    ) -> Result<BlockResult, BlockExecutorError<S::DataError>> {
+        self.execute_block(|executor, storage_diff| {
+            executor.execute_sequential(transactions, storage_diff)
+        })
+    }
+
+    /// Run `execute` to apply the transactions of the block, then mint the fees. The
+    /// storage is restored if the block fails.
+    fn execute_block<F>(
+        &mut self,
+        execute: F,
+    ) -> Result<BlockResult, BlockExecutorError<S::DataError>>
+    where
+        F: FnOnce(
+            &mut Self,
+            &mut StorageDiff,
+        ) -> Result<
+            (Vec<ExecutedTransaction>, Word),
+            BlockExecutorError<S::DataError>,
+        >,
+    {
+        // Discard anything recorded outside of a block
+        self.interpreter.as_mut().take_diff();
+
+        let mut storage_diff = StorageDiff::default();
+        let result =
+            execute(self, &mut storage_diff).and_then(|(transactions, total_fees)| {
+                let mint = self.mint(total_fees, transactions.len())?;
+                Ok((transactions, mint, total_fees))
+            });
+        storage_diff.merge(self.interpreter.as_mut().take_diff());
+
+        match result {
+            Ok((transactions, mint, total_fees)) => Ok(BlockResult {
+                transactions,
+                mint,
+                total_fees,
+                storage_diff,
+            }),
+            Err(e) => {
+                storage_diff
+                    .revert(self.storage_mut())
+                    .map_err(BlockExecutorError::Storage)?;
+                Err(e)
+            }
+        }
+    }
+
+    fn execute_sequential(
+        &mut self,
+        transactions: Vec<CheckedTransaction>,
+        storage_diff: &mut StorageDiff,
+    ) -> Result<(Vec<ExecutedTransaction>, Word), BlockExecutorError<S::DataError>> {
+        let mut executed = Vec::with_capacity(transactions.len());
+        let mut total_fees: Word = 0;
+
+        for (index, transaction) in transactions.into_iter().enumerate() {
+            let transaction = self.execute_transaction(index, transaction)?;
+            storage_diff.merge(self.commit(&transaction)?);
+
+            total_fees = total_fees
+                .checked_add(transaction.fee)
+                .ok_or(BlockExecutorError::FeeOverflow)?;
+            executed.push(transaction);
+        }
+
+        Ok((executed, total_fees))
+    }
+
+    /// Execute the transaction in a fresh VM, so it doesn't depend on the memory left
+    /// by the previous transactions.
+    fn execute_transaction(
+        &mut self,
+        index: usize,
+        transaction: CheckedTransaction,
+    ) -> Result<ExecutedTransaction, BlockExecutorError<S::DataError>> {
+        let params = self.interpreter.interpreter_params().clone();
+        let mut interpreter =
+            Interpreter::with_storage(self.interpreter.as_mut(), params);
+        execute_transaction(&mut interpreter, index, transaction)
+    }
+
+    /// Return the changes recorded for the executed transaction, discarding them if
+    /// it reverted.
+    fn commit(
+        &mut self,
+        transaction: &ExecutedTransaction,
+    ) -> Result<StorageDiff, BlockExecutorError<S::DataError>> {
+        let storage = self.interpreter.as_mut();
+        if transaction.reverted() {
+            storage.revert().map_err(BlockExecutorError::Storage)?;
+        }
+
+        Ok(storage.take_diff())
+    }
+
+    /// Credit the fees to the coinbase contract and build the `Mint` transaction.
+    fn mint(
+        &mut self,
+        total_fees: Word,
+        tx_count: usize,
+    ) -> Result<Mint, BlockExecutorError<S::DataError>> {
+        let base_asset_id = *self.interpreter.base_asset_id();
+        let storage = self.interpreter.as_mut();
+        let height = storage
+            .block_height()
+            .map_err(BlockExecutorError::Storage)?;
+        let coinbase = storage.coinbase().map_err(BlockExecutorError::Storage)?;
+        let tx_index =
+            u16::try_from(tx_count).map_err(|_| BlockExecutorError::FeeOverflow)?;
+
+        let roots = |storage: &RecordingStorage<S>| -> Result<_, S::DataError> {
+            let balance_root = MerkleRootStorage::<ContractId, ContractsAssets>::root(
+                storage, &coinbase,
+            )?;
+            let state_root = MerkleRootStorage::<ContractId, ContractsState>::root(
+                storage, &coinbase,
+            )?;
+            Ok((balance_root.into(), state_root.into()))
+        };
+
+        let (balance_root, state_root) =
+            roots(storage).map_err(BlockExecutorError::Storage)?;
+
+        if total_fees > 0 {
+            if !storage
+                .storage_contract_exists(&coinbase)
+                .map_err(BlockExecutorError::Storage)?
+            {
+                return Err(BlockExecutorError::CoinbaseNotFound(coinbase))
+            }
+
+            let balance = storage
+                .merkle_contract_asset_id_balance(&coinbase, &base_asset_id)
+                .map_err(BlockExecutorError::Storage)?
+                .unwrap_or_default()
+                .checked_add(total_fees)
+                .ok_or(BlockExecutorError::FeeOverflow)?;
+            storage
+                .merkle_contract_asset_id_balance_insert(
+                    &coinbase,
+                    &base_asset_id,
+                    balance,
+                )
+                .map_err(BlockExecutorError::Storage)?;
+        }
+
+        let (balance_root_after, state_root_after) =
+            roots(storage).map_err(BlockExecutorError::Storage)?;
+
+        Ok(Transaction::mint(
+            TxPointer::new(height, tx_index),
+            input::contract::Contract {
+                utxo_id: Default::default(),
+                balance_root,
+                state_root,
+                tx_pointer: Default::default(),
+                contract_id: coinbase,
+            },
+            output::contract::Contract {
+                input_index: 0,
+                balance_root: balance_root_after,
+                state_root: state_root_after,
+            },
+            total_fees,
+            base_asset_id,
+        ))
+    }
+}
+
+fn execute_transaction<S>(
+    interpreter: &mut Interpreter<S, Script>,
+    index: usize,
+    transaction: CheckedTransaction,
+) -> Result<ExecutedTransaction, BlockExecutorError<S::DataError>>
+where
+    S: InterpreterStorage,
+{
+    match transaction {
+        CheckedTransaction::Script(script) => execute_script(interpreter, script),
+        CheckedTransaction::Create(create) => execute_create(interpreter, create),
+        CheckedTransaction::Mint(_) => {
+            return Err(BlockExecutorError::UnexpectedMint(index))
+        }
+    }
+    .map_err(|error| BlockExecutorError::Transaction { index, error })
+}
+
+fn execute_script<S>(
+    interpreter: &mut Interpreter<S, Script>,
+    script: Checked<Script>,
+) -> Result<ExecutedTransaction, InterpreterError<S::DataError>>
+where
+    S: InterpreterStorage,
+{
+    let max_fee = script.metadata().fee.max_fee();
+    let state = *interpreter.transact(script)?.state();
+
+    let receipts = interpreter.receipts().to_vec();
+    let transaction = interpreter.transaction().clone();
+    let gas_used = receipts
+        .iter()
+        .rev()
+        .find_map(Receipt::gas_used)
+        .unwrap_or_default();
+    let remaining_gas = transaction.limit().saturating_sub(gas_used);
+    let fee = charged_fee(interpreter, max_fee, remaining_gas, transaction.price())?;
+
+    Ok(ExecutedTransaction {
+        receipts_root: *transaction.receipts_root(),
+        transaction: transaction.into(),
+        state,
+        receipts,
+        fee,
+    })
+}
+
+fn execute_create<S>(
+    interpreter: &mut Interpreter<S, Script>,
+    create: Checked<Create>,
+) -> Result<ExecutedTransaction, InterpreterError<S::DataError>>
+where
+    S: InterpreterStorage,
+{
+    let max_fee = create.metadata().fee.max_fee();
+    let transaction = interpreter.deploy(create)?;
+
+    let remaining_gas = transaction
+        .limit()
+        .saturating_sub(transaction.gas_used_by_predicates());
+    let fee = charged_fee(interpreter, max_fee, remaining_gas, transaction.price())?;
+
+    Ok(ExecutedTransaction {
+        transaction: transaction.into(),
+        state: ProgramState::Return(1),
+        receipts: Vec::new(),
+        receipts_root: crypto::ephemeral_merkle_root(core::iter::empty::<Vec<u8>>()),
+        fee,
+    })
+}
+
+/// The fee paid upfront minus the refund of the unused gas.
+fn charged_fee<S>(
+    interpreter: &Interpreter<S, Script>,
+    max_fee: Word,
+    remaining_gas: Word,
+    price: Word,
+) -> Result<Word, InterpreterError<S::DataError>>
+where
+    S: InterpreterStorage,
+{
+    TransactionFee::gas_refund_value(interpreter.fee_params(), remaining_gas, price)
+        .and_then(|refund| max_fee.checked_sub(refund))
+        .ok_or(InterpreterError::CheckError(
+            fuel_tx::CheckError::ArithmeticOverflow,
+        ))
+}
@@ -0,0 +1,243 @@
+//! Optimistic parallel execution of the transactions of a block.
+
+use super::{
+    execute_transaction,
+    BlockExecutor,
+    BlockExecutorError,
+    BlockResult,
+    ExecutedTransaction,
+};
+use crate::{
+    checked_transaction::CheckedTransaction,
+    interpreter::{
+        storage_access::{
+            StorageAccess,
+            StorageAccessTracer,
+            Traced,
+        },
+        Interpreter,
+        InterpreterParams,
+    },
+    storage::{
+        ContractsAssetKey,
+        ContractsStateKey,
+        InterpreterStorage,
+        OverlayStorage,
+        StorageDiff,
+    },
+};
+
+use fuel_tx::{
+    Contract,
+    Executable,
+};
+use fuel_types::{
+    Bytes32,
+    ContractId,
+    Word,
+};
+
+use alloc::{
+    collections::BTreeSet,
+    vec::Vec,
+};
+use core::{
+    mem,
+    num::NonZeroUsize,
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+};
+
+/// Storage entries accessed by a transaction, or written by the committed ones.
+#[derive(Debug, Default)]
+struct StorageKeys {
+    /// Contracts whose code or info was accessed.
+    contracts: BTreeSet<ContractId>,
+    balances: BTreeSet<ContractsAssetKey>,
+    state: BTreeSet<ContractsStateKey>,
+}
+
+impl StorageKeys {
+    fn extend(&mut self, diff: &StorageDiff) {
+        self.contracts.extend(diff.contracts.keys());
+        self.contracts.extend(diff.contracts_info.keys());
+        self.balances.extend(diff.balances.keys());
+        self.state.extend(diff.state.keys());
+    }
+
+    fn intersects(&self, other: &StorageKeys) -> bool {
+        !self.contracts.is_disjoint(&other.contracts)
+            || !self.balances.is_disjoint(&other.balances)
+            || !self.state.is_disjoint(&other.state)
+    }
+}
+
+impl StorageAccessTracer for StorageKeys {
+    fn contract_code(&mut self, access: StorageAccess<ContractId, Contract>) {
+        self.contracts.insert(access.key);
+    }
+
+    fn contract_code_read(
+        &mut self,
+        key: ContractId,
+        _code: &mut dyn FnMut() -> Option<Contract>,
+    ) {
+        self.contracts.insert(key);
+    }
+
+    fn contract_state(&mut self, access: StorageAccess<ContractsStateKey, Bytes32>) {
+        self.state.insert(access.key);
+    }
+
+    fn contract_balance(&mut self, access: StorageAccess<ContractsAssetKey, Word>) {
+        self.balances.insert(access.key);
+    }
+}
+
+/// Execution of a transaction on top of the storage as it was before the block.
+struct Speculation<E> {
+    result: Result<ExecutedTransaction, BlockExecutorError<E>>,
+    /// The entries read or written; writes are included since their result may
+    /// depend on the previous value.
+    accessed: StorageKeys,
+    changes: StorageDiff,
+}
+
+impl<S> BlockExecutor<S>
+where
+    S: InterpreterStorage + Sync,
+    S::DataError: Send,
+{
+    /// Execute the transactions optimistically in parallel and produce the block.
+    ///
+    /// Every transaction is first executed on its own thread, on top of the storage as
+    /// it was before the block, with its writes kept in an [`OverlayStorage`]. The
+    /// transactions are then committed in order: the ones that accessed an entry
+    /// written by a previous transaction of the block are executed again on top of the
+    /// committed changes. The result is the same as the one of [`Self::execute`].
+    pub fn execute_parallel(
+        &mut self,
+        transactions: Vec<CheckedTransaction>,
+    ) -> Result<BlockResult, BlockExecutorError<S::DataError>> {
+        self.execute_block(|executor, storage_diff| {
+            let speculations = executor.speculate(&transactions);
+            executor.commit_speculations(transactions, speculations, storage_diff)
+        })
+    }
+
+    fn speculate(
+        &self,
+        transactions: &[CheckedTransaction],
+    ) -> Vec<Speculation<S::DataError>> {
+        let storage = self.storage();
+        let params = self.interpreter.interpreter_params();
+        let next = AtomicUsize::new(0);
+        let workers = std::thread::available_parallelism()
+            .map_or(1, NonZeroUsize::get)
+            .min(transactions.len());
+
+        let mut speculations: Vec<_> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut speculations = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(transaction) = transactions.get(index) else {
+                                break speculations
+                            };
+                            let speculation =
+                                speculate(storage, params, index, transaction.clone());
+                            speculations.push((index, speculation));
+                        }
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .flat_map(|worker| {
+                    worker
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        });
+        speculations.sort_by_key(|(index, _)| *index);
+
+        speculations
+            .into_iter()
+            .map(|(_, speculation)| speculation)
+            .collect()
+    }
+
+    fn commit_speculations(
+        &mut self,
+        transactions: Vec<CheckedTransaction>,
+        speculations: Vec<Speculation<S::DataError>>,
+        storage_diff: &mut StorageDiff,
+    ) -> Result<(Vec<ExecutedTransaction>, Word), BlockExecutorError<S::DataError>> {
+        let mut executed = Vec::with_capacity(transactions.len());
+        let mut total_fees: Word = 0;
+        let mut written = StorageKeys::default();
+
+        for (index, (transaction, speculation)) in
+            transactions.into_iter().zip(speculations).enumerate()
+        {
+            let transaction = if speculation.accessed.intersects(&written) {
+                self.execute_transaction(index, transaction)?
+            } else {
+                let transaction = speculation.result?;
+                if !transaction.reverted() {
+                    speculation
+                        .changes
+                        .apply(self.interpreter.as_mut())
+                        .map_err(BlockExecutorError::Storage)?;
+                }
+                transaction
+            };
+
+            let changes = self.commit(&transaction)?;
+            written.extend(&changes);
+            storage_diff.merge(changes);
+
+            total_fees = total_fees
+                .checked_add(transaction.fee)
+                .ok_or(BlockExecutorError::FeeOverflow)?;
+            executed.push(transaction);
+        }
+
+        Ok((executed, total_fees))
+    }
+}
+
+fn speculate<S>(
+    storage: &S,
+    params: &InterpreterParams,
+    index: usize,
+    transaction: CheckedTransaction,
+) -> Speculation<S::DataError>
+where
+    S: InterpreterStorage,
+{
+    // The contract inputs give access to the contract info, which isn't traced
+    let mut accessed = StorageKeys::default();
+    accessed.contracts.extend(match &transaction {
+        CheckedTransaction::Script(script) => script.transaction().input_contracts(),
+        CheckedTransaction::Create(create) => create.transaction().input_contracts(),
+        CheckedTransaction::Mint(_) => Vec::new().into_iter(),
+    });
+
+    let storage = Traced::new(OverlayStorage::new(storage), accessed);
+    let mut interpreter = Interpreter::with_storage(storage, params.clone());
+    let result = execute_transaction(&mut interpreter, index, transaction);
+
+    let storage = interpreter.as_mut();
+    Speculation {
+        result,
+        accessed: mem::take(storage.tracer_mut()),
+        changes: storage.storage().diff().clone(),
+    }
+}
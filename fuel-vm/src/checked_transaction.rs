@@ -32,8 +32,14 @@ use fuel_tx::ConsensusParameters;
 
 mod balances;
 pub mod builder;
+mod fee;
 pub mod types;
 
+pub use fee::{
+    ExecutionFee,
+    FeeBreakdown,
+    SignatureRecovery,
+};
 pub use types::*;
 
 use crate::{
@@ -0,0 +1,189 @@
+//! Breakdown of the fee of a checked transaction, to explain where it comes from.
+
+use super::{
+    Checked,
+    Checks,
+    IntoChecked,
+};
+use crate::interpreter::CheckedMetadata;
+
+use fuel_tx::{
+    field,
+    Chargeable,
+    ConsensusParameters,
+    FeeParameters,
+    TransactionFee,
+};
+use fuel_types::Word;
+
+use alloc::{
+    collections::BTreeSet,
+    vec::Vec,
+};
+
+/// The recovery of the signature of a signed input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignatureRecovery {
+    /// The index of the signed input.
+    pub input_index: usize,
+    /// The index of the witness holding the signature.
+    pub witness_index: u8,
+    /// The gas cost of the recovery. Zero if the signature was already recovered for a
+    /// previous input.
+    pub gas: Word,
+}
+
+/// The components of the min and max fees of a checked transaction.
+///
+/// The gas is converted into a fee as `ceil(gas * gas_price / gas_price_factor)`, and
+/// the unused gas is refunded as `floor(gas * gas_price / gas_price_factor)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeeBreakdown {
+    /// The gas price of the transaction.
+    pub gas_price: Word,
+    /// The fee parameters: the `gas_price_factor` divisor converting gas multiplied by
+    /// the price into a fee, and the gas charged for each metered byte.
+    pub fee_params: FeeParameters,
+    /// The size of the transaction without the witnesses.
+    pub metered_bytes: Word,
+    /// The gas charged for the metered bytes.
+    pub bytes_gas: Word,
+    /// The predicate gas declared by the inputs, charged by the min fee.
+    pub predicate_gas: Word,
+    /// The gas used by the predicates when they were checked, or `None` if they
+    /// weren't.
+    pub predicate_gas_used: Option<Word>,
+    /// The gas limit of the transaction, charged by the max fee.
+    pub gas_limit: Word,
+    /// The signatures recovered to check the signed inputs.
+    ///
+    /// The recoveries aren't charged by the fee; they are reported as the cost of the
+    /// checks the transaction imposes on the validators.
+    pub signatures: Vec<SignatureRecovery>,
+    /// The bytes and predicate gas.
+    pub min_gas: Word,
+    /// The bytes gas and the gas limit.
+    pub max_gas: Word,
+    /// The fee for the `min_gas`.
+    pub min_fee: Word,
+    /// The fee for the `max_gas`, paid upfront.
+    pub max_fee: Word,
+    /// The part of the `min_fee` added by rounding up the division by the
+    /// `gas_price_factor`.
+    pub min_fee_rounding: Word,
+    /// The part of the `max_fee` added by rounding up the division by the
+    /// `gas_price_factor`.
+    pub max_fee_rounding: Word,
+}
+
+/// The fee charged after the execution of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExecutionFee {
+    /// The gas of the limit used by the execution.
+    pub gas_used: Word,
+    /// The value of the unused gas, refunded from the `max_fee`.
+    pub refund: Word,
+    /// The `max_fee` minus the `refund`.
+    pub fee: Word,
+}
+
+impl FeeBreakdown {
+    /// The gas of all the signature recoveries.
+    pub fn signatures_gas(&self) -> Word {
+        self.signatures
+            .iter()
+            .fold(0, |gas, recovery| gas.saturating_add(recovery.gas))
+    }
+
+    /// The fee charged after an execution that used `gas_used` of the gas limit: the
+    /// `gas_used` of the `ScriptResult` receipt of a script, or the predicate gas of a
+    /// create.
+    ///
+    /// Will return `None` if arithmetic overflow occurs.
+    pub fn execution(&self, gas_used: Word) -> Option<ExecutionFee> {
+        let remaining_gas = self.gas_limit.saturating_sub(gas_used);
+        let refund = TransactionFee::gas_refund_value(
+            &self.fee_params,
+            remaining_gas,
+            self.gas_price,
+        )?;
+        let fee = self.max_fee.checked_sub(refund)?;
+
+        Some(ExecutionFee {
+            gas_used,
+            refund,
+            fee,
+        })
+    }
+}
+
+impl<Tx> Checked<Tx>
+where
+    Tx: IntoChecked + Chargeable + field::Inputs,
+    Tx::Metadata: CheckedMetadata,
+{
+    /// The breakdown of the fee of the transaction.
+    ///
+    /// Will return `None` if arithmetic overflow occurs.
+    pub fn fee_breakdown(&self, params: &ConsensusParameters) -> Option<FeeBreakdown> {
+        let fee_params = params.fee_params();
+        let tx = self.transaction();
+
+        let fee = TransactionFee::checked_from_tx(fee_params, tx)?;
+        let gas_price = tx.price();
+        // The fees round up the same division the refund rounds down
+        let rounding = |fee: Word, gas: Word| -> Option<Word> {
+            fee.checked_sub(TransactionFee::gas_refund_value(
+                fee_params, gas, gas_price,
+            )?)
+        };
+
+        let metered_bytes = tx.metered_bytes_size() as Word;
+        let predicate_gas_used = self
+            .checks()
+            .contains(Checks::Predicates)
+            .then(|| self.metadata().gas_used_by_predicates());
+
+        // The signatures are recovered once per witness
+        let mut recovered = BTreeSet::new();
+        let signatures = tx
+            .inputs()
+            .iter()
+            .enumerate()
+            .filter_map(|(input_index, input)| {
+                let witness_index = input.witness_index()?;
+                let gas = if recovered.insert(witness_index) {
+                    params.gas_costs().ecr1
+                } else {
+                    0
+                };
+
+                Some(SignatureRecovery {
+                    input_index,
+                    witness_index,
+                    gas,
+                })
+            })
+            .collect();
+
+        Some(FeeBreakdown {
+            gas_price,
+            fee_params: *fee_params,
+            metered_bytes,
+            bytes_gas: fee_params.gas_per_byte.checked_mul(metered_bytes)?,
+            predicate_gas: tx.gas_used_by_predicates(),
+            predicate_gas_used,
+            gas_limit: tx.limit(),
+            signatures,
+            min_gas: fee.min_gas(),
+            max_gas: fee.max_gas(),
+            min_fee: fee.min_fee(),
+            max_fee: fee.max_fee(),
+            min_fee_rounding: rounding(fee.min_fee(), fee.min_gas())?,
+            max_fee_rounding: rounding(fee.max_fee(), fee.max_gas())?,
+        })
+    }
+}
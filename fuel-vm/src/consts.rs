@@ -21,6 +21,10 @@ pub const VM_REGISTER_PROGRAM_COUNT: usize = VM_REGISTER_COUNT - VM_REGISTER_SYS
 /// instruction is currently implemented using recursion.
 pub const VM_MAX_NESTED_CALLS: usize = 64;
 
+/// Margin added to the gas used by a script to estimate its gas limit, in percent
+/// of the gas used.
+pub const SCRIPT_GAS_ESTIMATION_MARGIN: Word = 10;
+
 // MEMORY TYPES
 
 /// Length of a word, in bytes
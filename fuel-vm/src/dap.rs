@@ -0,0 +1,684 @@
+//! [Debug adapter protocol](https://microsoft.github.io/debug-adapter-protocol/)
+//! server driving the debugger of the interpreter.
+//!
+//! The server executes a single script transaction and exposes it to the client
+//! as a single thread. Sources are the disassembly of the script and of the
+//! called contracts, one instruction per line, so line `n` is the instruction at
+//! offset `4 * (n - 1)` from `$is`.
+//!
+//! When paused, the client can inspect the registers, the call frames, the
+//! stack and the heap, and the receipts produced so far.
+
+mod protocol;
+mod variables;
+
+pub use protocol::{
+    read_message,
+    write_message,
+    DapError,
+    Request,
+};
+pub use variables::{
+    Reference,
+    MEMORY_ROW_SIZE,
+};
+
+use crate::{
+    checked_transaction::Checked,
+    consts::MEM_SIZE,
+    interpreter::Interpreter,
+    state::{
+        Breakpoint,
+        Comparison,
+        DebugEval,
+        ProgramState,
+        RegisterCondition,
+        Trigger,
+    },
+    storage::InterpreterStorage,
+};
+
+use fuel_asm::{
+    assembler::disassemble,
+    RegId,
+};
+use fuel_tx::{
+    field::Script as _,
+    Script,
+};
+use fuel_types::{
+    ContractId,
+    Word,
+};
+use hashbrown::HashMap;
+use serde_json::{
+    json,
+    Value,
+};
+
+use std::io::{
+    self,
+    BufRead,
+    BufReader,
+    Stdin,
+    Stdout,
+    Write,
+};
+
+/// The only thread reported to the client.
+const THREAD_ID: i64 = 1;
+
+/// How the program is resumed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    /// Until a breakpoint or a trigger.
+    Continue,
+    /// A single instruction, entering calls.
+    In,
+    /// A single instruction of the current call frame.
+    Over,
+    /// Until the current call frame returns.
+    Out,
+}
+
+/// Debug adapter protocol server for a script transaction.
+pub struct DapServer<S, R, W> {
+    vm: Interpreter<S, Script>,
+    tx: Option<Checked<Script>>,
+    script: Vec<u8>,
+    reader: R,
+    writer: W,
+    seq: i64,
+    /// Breakpoints set by the client, by source.
+    breakpoints: HashMap<ContractId, Vec<Breakpoint>>,
+    /// Contracts by `sourceReference - 1`; the zero id is the script.
+    sources: Vec<ContractId>,
+    stop_on_entry: bool,
+    launched: bool,
+    configured: bool,
+    /// The program is started and suspended.
+    suspended: bool,
+}
+
+impl<S> DapServer<S, BufReader<Stdin>, Stdout> {
+    /// Create a server communicating over the standard input and output.
+    pub fn stdio(vm: Interpreter<S, Script>, tx: Checked<Script>) -> Self {
+        Self::new(vm, tx, BufReader::new(io::stdin()), io::stdout())
+    }
+}
+
+impl<S, R, W> DapServer<S, R, W> {
+    /// Create a server debugging `tx` with `vm`, reading requests from `reader`
+    /// and writing responses and events to `writer`.
+    pub fn new(
+        vm: Interpreter<S, Script>,
+        tx: Checked<Script>,
+        reader: R,
+        writer: W,
+    ) -> Self {
+        let script = tx.transaction().script().clone();
+
+        Self {
+            vm,
+            tx: Some(tx),
+            script,
+            reader,
+            writer,
+            seq: 0,
+            breakpoints: HashMap::new(),
+            sources: vec![ContractId::zeroed()],
+            stop_on_entry: false,
+            launched: false,
+            configured: false,
+            suspended: false,
+        }
+    }
+
+    /// The interpreter driven by the server.
+    pub const fn interpreter(&self) -> &Interpreter<S, Script> {
+        &self.vm
+    }
+
+    /// Consume the server, returning the interpreter.
+    pub fn into_interpreter(self) -> Interpreter<S, Script> {
+        self.vm
+    }
+}
+
+impl<S, R, W> DapServer<S, R, W>
+where
+    S: InterpreterStorage,
+    R: BufRead,
+    W: Write,
+{
+    /// Serve requests until the client disconnects or closes the stream.
+    pub fn run(&mut self) -> Result<(), DapError> {
+        while let Some(message) = read_message(&mut self.reader)? {
+            let request: Request = serde_json::from_value(message)?;
+            if !self.handle(request)? {
+                break
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle a single request; return `false` once the client disconnected.
+    pub fn handle(&mut self, request: Request) -> Result<bool, DapError> {
+        let args = &request.arguments;
+
+        let result = match request.command.as_str() {
+            "initialize" => Ok(json!({
+                "supportsConfigurationDoneRequest": true,
+                "supportsConditionalBreakpoints": true,
+                "supportsTerminateRequest": true,
+            })),
+            "launch" | "attach" => {
+                self.stop_on_entry = args
+                    .get("stopOnEntry")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                self.launched = true;
+                Ok(Value::Null)
+            }
+            "configurationDone" => {
+                self.configured = true;
+                Ok(Value::Null)
+            }
+            "setBreakpoints" => self.set_breakpoints(args),
+            "threads" => Ok(json!({
+                "threads": [{ "id": THREAD_ID, "name": "main" }],
+            })),
+            "stackTrace" => Ok(self.stack_trace()),
+            "scopes" => Ok(self.scopes(args)),
+            "variables" => self.variables(args),
+            "source" => self.source(args),
+            "continue" => self
+                .suspended()
+                .map(|_| json!({ "allThreadsContinued": true })),
+            "next" | "stepIn" | "stepOut" => self.suspended().map(|_| Value::Null),
+            "disconnect" | "terminate" => Ok(Value::Null),
+            command => Err(format!("Unsupported command `{command}`")),
+        };
+
+        let success = match result {
+            Ok(body) => {
+                self.respond(&request, true, None, body)?;
+                true
+            }
+            Err(message) => {
+                self.respond(&request, false, Some(message), Value::Null)?;
+                false
+            }
+        };
+
+        match request.command.as_str() {
+            "initialize" => self.event("initialized", Value::Null)?,
+            "launch" | "attach" | "configurationDone"
+                if self.launched && self.configured && self.tx.is_some() =>
+            {
+                self.start()?
+            }
+            "continue" if success => self.resume(Step::Continue)?,
+            "next" if success => self.resume(Step::Over)?,
+            "stepIn" if success => self.resume(Step::In)?,
+            "stepOut" if success => self.resume(Step::Out)?,
+            "disconnect" => return Ok(false),
+            "terminate" => {
+                self.suspended = false;
+                self.event("terminated", Value::Null)?
+            }
+            _ => {}
+        }
+
+        Ok(true)
+    }
+
+    fn respond(
+        &mut self,
+        request: &Request,
+        success: bool,
+        message: Option<String>,
+        body: Value,
+    ) -> Result<(), DapError> {
+        let mut response = json!({
+            "type": "response",
+            "request_seq": request.seq,
+            "success": success,
+            "command": request.command,
+        });
+        if let Some(message) = message {
+            response["message"] = message.into();
+        }
+        if !body.is_null() {
+            response["body"] = body;
+        }
+
+        self.send(response)
+    }
+
+    fn event(&mut self, event: &str, body: Value) -> Result<(), DapError> {
+        let mut message = json!({
+            "type": "event",
+            "event": event,
+        });
+        if !body.is_null() {
+            message["body"] = body;
+        }
+
+        self.send(message)
+    }
+
+    fn send(&mut self, mut message: Value) -> Result<(), DapError> {
+        self.seq += 1;
+        message["seq"] = self.seq.into();
+
+        write_message(&mut self.writer, &message)
+    }
+
+    fn suspended(&self) -> Result<(), String> {
+        self.suspended
+            .then_some(())
+            .ok_or_else(|| "The program isn't suspended".into())
+    }
+
+    /// Execute the transaction until the first breakpoint.
+    fn start(&mut self) -> Result<(), DapError> {
+        let Some(tx) = self.tx.take() else {
+            return Ok(())
+        };
+
+        self.vm.set_single_stepping(self.stop_on_entry);
+        let state = self
+            .vm
+            .transact(tx)
+            .map(|transition| *transition.state())
+            .map_err(|e| e.erase_generics().to_string());
+
+        let step = if self.stop_on_entry {
+            Step::In
+        } else {
+            Step::Continue
+        };
+        self.report(state, step, self.stop_on_entry)
+    }
+
+    /// Resume the program and report where it stopped.
+    fn resume(&mut self, step: Step) -> Result<(), DapError> {
+        let depth = self.vm.call_stack().len();
+        self.vm.set_single_stepping(step != Step::Continue);
+
+        let state = loop {
+            let state = match self.vm.resume() {
+                Ok(state) => state,
+                Err(e) => break Err(e.erase_generics().to_string()),
+            };
+
+            let stepped = match state {
+                ProgramState::RunProgram(DebugEval::Breakpoint(b)) => {
+                    let depth_now = self.vm.call_stack().len();
+                    match step {
+                        Step::Continue | Step::In => true,
+                        Step::Over => depth_now <= depth || self.user_breakpoint(&b),
+                        Step::Out => depth_now < depth || self.user_breakpoint(&b),
+                    }
+                }
+                _ => true,
+            };
+
+            if stepped {
+                break Ok(state)
+            }
+        };
+
+        self.vm.set_single_stepping(false);
+        self.report(state, step, false)
+    }
+
+    /// Return `true` if the client set a breakpoint at `b` whose condition holds.
+    fn user_breakpoint(&self, b: &Breakpoint) -> bool {
+        let set = self
+            .breakpoints
+            .get(b.contract())
+            .is_some_and(|breakpoints| breakpoints.contains(b));
+
+        set && self
+            .vm
+            .debugger()
+            .breakpoint_condition(b)
+            .map_or(true, |condition| condition.holds(self.vm.registers()))
+    }
+
+    fn report(
+        &mut self,
+        state: Result<ProgramState, String>,
+        step: Step,
+        entry: bool,
+    ) -> Result<(), DapError> {
+        let (reason, description) = match state {
+            Ok(ProgramState::RunProgram(DebugEval::Breakpoint(b))) => {
+                let reason = if entry {
+                    "entry"
+                } else if step == Step::Continue || self.user_breakpoint(&b) {
+                    "breakpoint"
+                } else {
+                    "step"
+                };
+                (reason, None)
+            }
+            Ok(ProgramState::RunProgram(DebugEval::Trigger(_, trigger))) => {
+                let reason = match trigger {
+                    Trigger::Panic(_) => "exception",
+                    Trigger::Memory { .. } | Trigger::Storage { .. } => "data breakpoint",
+                    Trigger::Opcode(_) | Trigger::Receipt(_) => "function breakpoint",
+                };
+                (reason, Some(format!("{trigger:?}")))
+            }
+            Ok(state) => {
+                self.suspended = false;
+                let exit_code = match state {
+                    ProgramState::Revert(_) => 1,
+                    _ => 0,
+                };
+                self.event(
+                    "output",
+                    json!({
+                        "category": "console",
+                        "output": format!("Program finished: {state:?}\n"),
+                    }),
+                )?;
+                self.event("exited", json!({ "exitCode": exit_code }))?;
+                return self.event("terminated", Value::Null)
+            }
+            Err(error) => {
+                self.suspended = false;
+                self.event(
+                    "output",
+                    json!({
+                        "category": "stderr",
+                        "output": format!("{error}\n"),
+                    }),
+                )?;
+                self.event("exited", json!({ "exitCode": 1 }))?;
+                return self.event("terminated", Value::Null)
+            }
+        };
+
+        self.suspended = true;
+        let mut body = json!({
+            "reason": reason,
+            "threadId": THREAD_ID,
+            "allThreadsStopped": true,
+        });
+        if let Some(description) = description {
+            body["description"] = description.clone().into();
+            body["text"] = description.into();
+        }
+
+        self.event("stopped", body)
+    }
+
+    /// Identify the contract of a `Source`, either by its `sourceReference` or
+    /// by its name: `script` or the contract id.
+    fn source_contract(&self, source: &Value) -> Option<ContractId> {
+        if let Some(reference) = source.get("sourceReference").and_then(Value::as_i64) {
+            if reference > 0 {
+                return self.sources.get(reference as usize - 1).copied()
+            }
+        }
+
+        let name = source
+            .get("name")
+            .or_else(|| source.get("path"))
+            .and_then(Value::as_str)?;
+        match name {
+            "script" => Some(ContractId::zeroed()),
+            name => name.parse().ok(),
+        }
+    }
+
+    fn source_reference(&mut self, contract: &ContractId) -> i64 {
+        let index = match self.sources.iter().position(|c| c == contract) {
+            Some(index) => index,
+            None => {
+                self.sources.push(*contract);
+                self.sources.len() - 1
+            }
+        };
+
+        index as i64 + 1
+    }
+
+    fn source_json(&mut self, contract: &ContractId) -> Value {
+        let name = if contract == &ContractId::zeroed() {
+            "script".into()
+        } else {
+            format!("{contract:#x}")
+        };
+
+        json!({
+            "name": name,
+            "sourceReference": self.source_reference(contract),
+        })
+    }
+
+    fn set_breakpoints(&mut self, args: &Value) -> Result<Value, String> {
+        let source = args.get("source").ok_or("Missing source")?;
+        let contract = self
+            .source_contract(source)
+            .ok_or_else(|| format!("Unknown source {source}"))?;
+
+        for b in self.breakpoints.remove(&contract).unwrap_or_default() {
+            self.vm.remove_breakpoint(&b);
+        }
+
+        let requested = args
+            .get("breakpoints")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        let mut set = Vec::with_capacity(requested.len());
+        let mut breakpoints = Vec::with_capacity(requested.len());
+
+        for requested in requested {
+            let line = requested.get("line").and_then(Value::as_u64).unwrap_or(0);
+            let condition = requested.get("condition").and_then(Value::as_str);
+
+            let verified = match (line, condition.map(parse_condition)) {
+                (0, _) => Err("Lines start at 1".into()),
+                (_, Some(Err(e))) => Err(e),
+                (line, condition) => {
+                    let b = Breakpoint::new(contract, line - 1);
+                    match condition {
+                        Some(Ok(condition)) => {
+                            self.vm.set_conditional_breakpoint(b, condition)
+                        }
+                        _ => self.vm.set_breakpoint(b),
+                    }
+                    set.push(b);
+                    Ok(())
+                }
+            };
+
+            breakpoints.push(match verified {
+                Ok(()) => json!({ "verified": true, "line": line }),
+                Err(message) => json!({ "verified": false, "message": message }),
+            });
+        }
+
+        self.breakpoints.insert(contract, set);
+
+        Ok(json!({ "breakpoints": breakpoints }))
+    }
+
+    fn stack_trace(&mut self) -> Value {
+        if !self.suspended {
+            return json!({ "stackFrames": [], "totalFrames": 0 })
+        }
+
+        // The current location, followed by the location of each `CALL`
+        let mut locations = vec![self.vm.debugger_location()];
+        let frames = self.vm.call_stack();
+        locations.extend(frames.iter().enumerate().rev().map(|(index, frame)| {
+            let caller = index
+                .checked_sub(1)
+                .map(|caller| *frames[caller].to())
+                .unwrap_or_default();
+            let registers = frame.registers();
+            let pc = registers[RegId::PC].saturating_sub(registers[RegId::IS]);
+
+            Breakpoint::raw(caller, pc)
+        }));
+
+        let frames = locations
+            .iter()
+            .enumerate()
+            .map(|(id, location)| {
+                let line = location.pc() / fuel_asm::Instruction::SIZE as Word + 1;
+                let source = self.source_json(location.contract());
+
+                json!({
+                    "id": id,
+                    "name": source["name"].clone(),
+                    "source": source,
+                    "line": line,
+                    "column": 1,
+                    "instructionPointerReference": format!("{:#x}", location.pc()),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({ "totalFrames": frames.len(), "stackFrames": frames })
+    }
+
+    fn scopes(&self, args: &Value) -> Value {
+        // The registers of the callers are the ones saved in the call frames
+        let depth = self.vm.call_stack().len();
+        let frame = args.get("frameId").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let registers = match frame {
+            0 => Reference::Registers,
+            frame => Reference::CallFrameRegisters(depth.saturating_sub(frame)),
+        };
+
+        let scope = |name: &str, reference: Reference, expensive: bool| {
+            json!({
+                "name": name,
+                "variablesReference": reference.to_id(),
+                "expensive": expensive,
+            })
+        };
+
+        json!({
+            "scopes": [
+                scope("Registers", registers, false),
+                scope("Call frames", Reference::CallFrames, false),
+                scope("Memory", Reference::Memory, true),
+                scope("Receipts", Reference::Receipts, false),
+            ],
+        })
+    }
+
+    fn variables(&self, args: &Value) -> Result<Value, String> {
+        let reference = args
+            .get("variablesReference")
+            .and_then(Value::as_i64)
+            .and_then(Reference::from_id)
+            .ok_or("Unknown variables reference")?;
+        let first = args.get("start").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let count = args
+            .get("count")
+            .and_then(Value::as_u64)
+            .filter(|count| *count > 0)
+            .map(|count| count as usize);
+
+        let registers = self.vm.registers();
+        let frames = self.vm.call_stack();
+        let frame = |index: usize| frames.get(index).ok_or("Unknown call frame");
+        let sp = registers[RegId::SP] as usize;
+        let hp = registers[RegId::HP] as usize;
+
+        let variables = match reference {
+            Reference::Registers => variables::registers(registers),
+            Reference::CallFrames => variables::call_frames(frames),
+            Reference::Memory => vec![
+                variables::memory_container("stack", Reference::Stack, 0, sp),
+                variables::memory_container("heap", Reference::Heap, hp, MEM_SIZE),
+            ],
+            Reference::Receipts => variables::receipts(self.vm.receipts()),
+            Reference::Stack => {
+                variables::memory_rows(self.vm.memory(), 0, sp, first, count)
+            }
+            Reference::Heap => {
+                variables::memory_rows(self.vm.memory(), hp, MEM_SIZE, first, count)
+            }
+            Reference::CallFrame(index) => variables::call_frame(frame(index)?, index),
+            Reference::CallFrameRegisters(index) => {
+                variables::registers(frame(index)?.registers())
+            }
+            Reference::Receipt(index) => variables::receipt(
+                self.vm.receipts().get(index).ok_or("Unknown receipt")?,
+            ),
+        };
+
+        Ok(json!({ "variables": variables }))
+    }
+
+    fn source(&self, args: &Value) -> Result<Value, String> {
+        let contract = args
+            .get("source")
+            .and_then(|source| self.source_contract(source))
+            .or_else(|| {
+                let reference = args.get("sourceReference").and_then(Value::as_i64)?;
+                self.source_contract(&json!({ "sourceReference": reference }))
+            })
+            .ok_or("Unknown source")?;
+
+        let code = if contract == ContractId::zeroed() {
+            self.script.clone()
+        } else {
+            AsRef::<S>::as_ref(&self.vm)
+                .storage_contract(&contract)
+                .map_err(|e| format!("{e:?}"))?
+                .ok_or_else(|| format!("Contract {contract:#x} not found"))?
+                .as_ref()
+                .as_ref()
+                .to_vec()
+        };
+
+        Ok(json!({
+            "content": disassemble(&code),
+            "mimeType": "text/x-fuel-asm",
+        }))
+    }
+}
+
+/// Parse a breakpoint condition such as `$r16 >= 0x10`.
+fn parse_condition(condition: &str) -> Result<RegisterCondition, String> {
+    let invalid =
+        || format!("Invalid condition `{condition}`, expected `$rX <op> value`");
+
+    let mut parts = condition.split_whitespace();
+    let (Some(register), Some(comparison), Some(value), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid())
+    };
+
+    let register = register.parse::<RegId>().map_err(|_| invalid())?;
+    let comparison = match comparison {
+        "==" => Comparison::Eq,
+        "!=" => Comparison::Ne,
+        "<" => Comparison::Lt,
+        "<=" => Comparison::Le,
+        ">" => Comparison::Gt,
+        ">=" => Comparison::Ge,
+        _ => return Err(invalid()),
+    };
+    let value = match value.strip_prefix("0x") {
+        Some(hex) => Word::from_str_radix(hex, 16),
+        None => value.parse(),
+    }
+    .map_err(|_| invalid())?;
+
+    Ok(RegisterCondition::new(register, comparison, value))
+}
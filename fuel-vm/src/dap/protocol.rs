@@ -0,0 +1,104 @@
+//! Wire format of the debug adapter protocol: JSON messages preceded by a
+//! `Content-Length` header.
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use std::io::{
+    self,
+    BufRead,
+    Write,
+};
+
+/// Protocol error variants.
+#[derive(Debug, derive_more::Display)]
+pub enum DapError {
+    /// The underlying reader or writer failed.
+    #[display(fmt = "I/O error: {_0}")]
+    Io(io::Error),
+    /// A message isn't valid JSON, or isn't a valid request.
+    #[display(fmt = "Malformed message: {_0}")]
+    Json(serde_json::Error),
+    /// A message header is malformed or misses the `Content-Length` field.
+    #[display(fmt = "Malformed header: {_0:?}")]
+    Header(String),
+}
+
+impl std::error::Error for DapError {}
+
+impl From<io::Error> for DapError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for DapError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+/// A request sent by the client.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Request {
+    /// Sequence number of the message.
+    pub seq: i64,
+    /// The command to execute.
+    pub command: String,
+    /// Arguments of the command, if any.
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+/// Read the next message from `reader`; return `None` once the stream is
+/// closed.
+pub fn read_message<R: BufRead>(reader: &mut R) -> Result<Option<Value>, DapError> {
+    let mut content_length = None;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return match content_length {
+                None => Ok(None),
+                Some(_) => Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+            }
+        }
+
+        let header = line.trim_end_matches(['\r', '\n']);
+        if header.is_empty() {
+            break
+        }
+
+        match header.split_once(':') {
+            Some((name, value)) if name.eq_ignore_ascii_case("Content-Length") => {
+                let length = value
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| DapError::Header(header.into()))?;
+                content_length.replace(length);
+            }
+            // Other fields, such as `Content-Type`, are ignored
+            Some(_) => {}
+            None => return Err(DapError::Header(header.into())),
+        }
+    }
+
+    let length =
+        content_length.ok_or_else(|| DapError::Header("Content-Length".into()))?;
+    let mut content = vec![0u8; length];
+    reader.read_exact(&mut content)?;
+
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+/// Write `message` to `writer`, preceded by its header.
+pub fn write_message<W: Write>(writer: &mut W, message: &Value) -> Result<(), DapError> {
+    let content = serde_json::to_vec(message)?;
+
+    write!(writer, "Content-Length: {}\r\n\r\n", content.len())?;
+    writer.write_all(&content)?;
+    writer.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,232 @@
+//! Scopes and variables exposed to the client while the program is suspended.
+
+use crate::{
+    call::CallFrame,
+    consts::{
+        MEM_SIZE,
+        VM_REGISTER_COUNT,
+    },
+};
+
+use fuel_asm::RegId;
+use fuel_tx::Receipt;
+use fuel_types::Word;
+use serde_json::{
+    json,
+    Value,
+};
+
+/// Number of bytes of memory displayed in each variable.
+pub const MEMORY_ROW_SIZE: usize = 32;
+
+/// Container of variables, identified in the protocol by its
+/// `variablesReference`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Reference {
+    /// The registers of the current context.
+    Registers,
+    /// The stack of call frames, latest first.
+    CallFrames,
+    /// The stack and the heap.
+    Memory,
+    /// The receipts produced so far.
+    Receipts,
+    /// The memory in `[0, $sp)`, in rows of [`MEMORY_ROW_SIZE`] bytes.
+    Stack,
+    /// The memory in `[$hp, MEM_SIZE)`, in rows of [`MEMORY_ROW_SIZE`] bytes.
+    Heap,
+    /// The fields of the call frame at the given index of the call stack.
+    CallFrame(usize),
+    /// The registers of the caller saved in the call frame at the given index.
+    CallFrameRegisters(usize),
+    /// The fields of the receipt at the given index.
+    Receipt(usize),
+}
+
+impl Reference {
+    const KIND_SHIFT: u32 = 32;
+
+    /// Encode the reference as a `variablesReference`; `0` is reserved by the
+    /// protocol for values without children.
+    pub fn to_id(self) -> i64 {
+        let (kind, index) = match self {
+            Self::Registers => (1, 0),
+            Self::CallFrames => (2, 0),
+            Self::Memory => (3, 0),
+            Self::Receipts => (4, 0),
+            Self::Stack => (5, 0),
+            Self::Heap => (6, 0),
+            Self::CallFrame(index) => (7, index),
+            Self::CallFrameRegisters(index) => (8, index),
+            Self::Receipt(index) => (9, index),
+        };
+
+        (kind << Self::KIND_SHIFT) | index as i64
+    }
+
+    /// Decode a `variablesReference` produced by [`Self::to_id`].
+    pub fn from_id(id: i64) -> Option<Self> {
+        let index = usize::try_from(id & ((1 << Self::KIND_SHIFT) - 1)).ok()?;
+
+        match id >> Self::KIND_SHIFT {
+            1 => Some(Self::Registers),
+            2 => Some(Self::CallFrames),
+            3 => Some(Self::Memory),
+            4 => Some(Self::Receipts),
+            5 => Some(Self::Stack),
+            6 => Some(Self::Heap),
+            7 => Some(Self::CallFrame(index)),
+            8 => Some(Self::CallFrameRegisters(index)),
+            9 => Some(Self::Receipt(index)),
+            _ => None,
+        }
+    }
+}
+
+/// Variable without children.
+pub fn variable(name: impl Into<String>, value: impl Into<String>) -> Value {
+    json!({
+        "name": name.into(),
+        "value": value.into(),
+        "variablesReference": 0,
+    })
+}
+
+/// Variable whose children are listed under `reference`.
+pub fn container(
+    name: impl Into<String>,
+    value: impl Into<String>,
+    reference: Reference,
+) -> Value {
+    json!({
+        "name": name.into(),
+        "value": value.into(),
+        "variablesReference": reference.to_id(),
+    })
+}
+
+/// Variable whose children are rows of memory, fetched in pages.
+pub fn memory_container(
+    name: &str,
+    reference: Reference,
+    start: usize,
+    end: usize,
+) -> Value {
+    json!({
+        "name": name,
+        "value": format!("{:#x}..{:#x} ({} bytes)", start, end, end - start),
+        "variablesReference": reference.to_id(),
+        "indexedVariables": (end - start + MEMORY_ROW_SIZE - 1) / MEMORY_ROW_SIZE,
+    })
+}
+
+/// Variables of the provided register values.
+pub fn registers(registers: &[Word]) -> Vec<Value> {
+    registers
+        .iter()
+        .take(VM_REGISTER_COUNT)
+        .enumerate()
+        .map(|(id, value)| {
+            let name = RegId::new(id as u8).to_string();
+            variable(name, value.to_string())
+        })
+        .collect()
+}
+
+/// Variables of the call stack, latest frame first.
+pub fn call_frames(frames: &[CallFrame]) -> Vec<Value> {
+    frames
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(index, frame)| {
+            container(
+                format!("[{index}]"),
+                format!("{:#x}", frame.to()),
+                Reference::CallFrame(index),
+            )
+        })
+        .collect()
+}
+
+/// Fields of the call frame at `index`.
+pub fn call_frame(frame: &CallFrame, index: usize) -> Vec<Value> {
+    vec![
+        variable("to", format!("{:#x}", frame.to())),
+        variable("asset_id", format!("{:#x}", frame.asset_id())),
+        variable("code_size", frame.code_size().to_string()),
+        variable("a", frame.a().to_string()),
+        variable("b", frame.b().to_string()),
+        variable("context_gas", frame.context_gas().to_string()),
+        container(
+            "registers",
+            "caller registers",
+            Reference::CallFrameRegisters(index),
+        ),
+    ]
+}
+
+/// Rows of `[start, end)` of the memory, paged by `first` and `count` rows.
+pub fn memory_rows(
+    memory: &[u8],
+    start: usize,
+    end: usize,
+    first: usize,
+    count: Option<usize>,
+) -> Vec<Value> {
+    let end = end.min(MEM_SIZE).min(memory.len());
+    let rows = (start..end).step_by(MEMORY_ROW_SIZE).skip(first);
+
+    rows.take(count.unwrap_or(usize::MAX))
+        .map(|address| {
+            let row = &memory[address..end.min(address + MEMORY_ROW_SIZE)];
+            let value = row
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<String>();
+
+            variable(format!("{address:#010x}"), value)
+        })
+        .collect()
+}
+
+/// Variables of the receipts, by kind.
+pub fn receipts(receipts: &[Receipt]) -> Vec<Value> {
+    receipts
+        .iter()
+        .enumerate()
+        .map(|(index, receipt)| {
+            let (kind, _) = receipt_fields(receipt);
+            container(format!("[{index}]"), kind, Reference::Receipt(index))
+        })
+        .collect()
+}
+
+/// Fields of a receipt.
+pub fn receipt(receipt: &Receipt) -> Vec<Value> {
+    let (_, fields) = receipt_fields(receipt);
+
+    fields
+        .into_iter()
+        .map(|(name, value)| match value {
+            Value::String(value) => variable(name, value),
+            value => variable(name, value.to_string()),
+        })
+        .collect()
+}
+
+/// Kind and fields of the serialized receipt.
+fn receipt_fields(receipt: &Receipt) -> (String, Vec<(String, Value)>) {
+    // Receipts are serialized as `{ "Kind": { "field": value, .. } }`
+    match serde_json::to_value(receipt) {
+        Ok(Value::Object(receipt)) => receipt
+            .into_iter()
+            .next()
+            .map(|(kind, fields)| match fields {
+                Value::Object(fields) => (kind, fields.into_iter().collect()),
+                _ => (kind, Vec::new()),
+            })
+            .unwrap_or_default(),
+        _ => Default::default(),
+    }
+}
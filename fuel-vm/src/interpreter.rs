@@ -67,6 +67,9 @@ mod memory;
 mod metadata;
 mod post_execution;
 mod receipts;
+pub mod storage_access;
+#[cfg(feature = "trace")]
+pub mod trace;
 
 mod debug;
 
@@ -88,6 +91,7 @@ use crate::checked_transaction::{
 };
 
 use self::{
+    diff::history::History,
     memory::Memory,
     receipts::ReceiptsCtx,
 };
@@ -117,6 +121,11 @@ pub struct Interpreter<S, Tx = ()> {
     /// `PanicContext` after the latest execution. It is consumed by
     /// `append_panic_receipt` and is `PanicContext::None` after consumption.
     panic_context: PanicContext,
+    /// Execution history recorded to step backwards, if enabled.
+    history: Option<History<S, Tx>>,
+    /// Tracer of the executed instructions, if enabled.
+    #[cfg(feature = "trace")]
+    tracing: Option<trace::Tracing>,
 }
 
 /// Interpreter parameters
@@ -233,6 +242,11 @@ impl<S, Tx> Interpreter<S, Tx> {
         &self.initial_balances
     }
 
+    /// The parameters of the interpreter
+    pub fn interpreter_params(&self) -> &InterpreterParams {
+        &self.interpreter_params
+    }
+
     /// Get max_inputs value
     pub fn max_inputs(&self) -> u64 {
         self.interpreter_params.max_inputs
@@ -50,6 +50,9 @@ where
             profiler: Profiler::default(),
             interpreter_params,
             panic_context: PanicContext::None,
+            history: None,
+            #[cfg(feature = "trace")]
+            tracing: None,
         }
     }
 
@@ -5,8 +5,20 @@ use alloc::{
 };
 
 use super::Interpreter;
-use crate::prelude::*;
-use fuel_asm::RegId;
+use crate::{
+    prelude::*,
+    state::{
+        RegisterCondition,
+        Trigger,
+    },
+    storage::InterpreterStorage,
+};
+use alloc::borrow::Cow;
+use fuel_asm::{
+    Opcode,
+    RawInstruction,
+    RegId,
+};
 
 impl<S, Tx> Interpreter<S, Tx>
 where
@@ -32,16 +44,104 @@ where
         self.debugger.remove_breakpoint(breakpoint)
     }
 
-    pub(crate) fn eval_debugger_state(&mut self) -> DebugEval {
-        let debugger = &mut self.debugger;
+    /// Set a new breakpoint for the provided location that breaks only if the
+    /// condition holds.
+    pub fn set_conditional_breakpoint(
+        &mut self,
+        breakpoint: Breakpoint,
+        condition: RegisterCondition,
+    ) {
+        self.debugger
+            .set_conditional_breakpoint(breakpoint, condition)
+    }
+
+    /// Break when the content of the memory range `[start, start + len)` changes.
+    pub fn watch_memory(&mut self, start: Word, len: Word) {
+        self.debugger.watch_memory(start, len)
+    }
+
+    /// Stop watching a memory range.
+    pub fn unwatch_memory(&mut self, start: Word, len: Word) {
+        self.debugger.unwatch_memory(start, len)
+    }
+
+    /// Break when the value of the storage slot `key` of `contract` changes.
+    pub fn watch_storage(&mut self, contract: ContractId, key: Bytes32) {
+        self.debugger.watch_storage(contract, key)
+    }
+
+    /// Stop watching a storage slot.
+    pub fn unwatch_storage(&mut self, contract: &ContractId, key: &Bytes32) {
+        self.debugger.unwatch_storage(contract, key)
+    }
+
+    /// Break before the execution of any instruction with the provided opcode.
+    pub fn break_on_opcode(&mut self, opcode: Opcode) {
+        self.debugger.break_on_opcode(opcode)
+    }
+
+    /// Stop breaking on an opcode.
+    pub fn remove_opcode_break(&mut self, opcode: Opcode) {
+        self.debugger.remove_opcode_break(opcode)
+    }
+
+    /// Break after a receipt of the provided kind is produced.
+    pub fn break_on_receipt(&mut self, receipt: ReceiptRepr) {
+        self.debugger.break_on_receipt(receipt)
+    }
+
+    /// Stop breaking on a receipt kind.
+    pub fn remove_receipt_break(&mut self, receipt: ReceiptRepr) {
+        self.debugger.remove_receipt_break(receipt)
+    }
+
+    /// Break when an instruction panics with the provided reason.
+    pub fn break_on_panic(&mut self, reason: PanicReason) {
+        self.debugger.break_on_panic(reason)
+    }
+
+    /// Stop breaking on a panic reason.
+    pub fn remove_panic_break(&mut self, reason: PanicReason) {
+        self.debugger.remove_panic_break(reason)
+    }
 
+    /// Location of the next instruction, as evaluated by the debugger.
+    pub(crate) fn debugger_location(&self) -> Breakpoint {
         let contract = self.frames.last().map(CallFrame::to);
         let pc = self.registers[RegId::PC].saturating_sub(self.registers[RegId::IS]);
 
-        debugger.eval_state(contract, pc)
+        Breakpoint::raw(contract.copied().unwrap_or_default(), pc)
+    }
+
+    /// Return `true` if the VM was suspended in the current location and is
+    /// being resumed.
+    pub(crate) fn debugger_resuming(&self) -> bool {
+        let location = self.debugger_location();
+
+        matches!(self.debugger.last_state(), Some(state) if *state == location)
+    }
+
+    pub(crate) fn eval_debugger_state(&mut self) -> DebugEval {
+        let location = self.debugger_location();
+        let debugger = &mut self.debugger;
+
+        match debugger.eval_state(Some(location.contract()), location.pc()) {
+            DebugEval::Breakpoint(b) if !debugger.single_stepping() => {
+                match debugger.breakpoint_condition(&b) {
+                    Some(condition) if !condition.holds(self.registers.as_ref()) => {
+                        DebugEval::Continue
+                    }
+                    _ => DebugEval::Breakpoint(b),
+                }
+            }
+            eval => eval,
+        }
     }
 
     pub(crate) fn debugger_set_last_state(&mut self, state: ProgramState) {
+        // Changes made while suspending the VM shouldn't trigger watchpoints
+        self.debugger
+            .sync(self.memory.as_ref(), self.receipts.len());
         self.debugger.set_last_state(state)
     }
 
@@ -50,6 +150,51 @@ where
     }
 }
 
+impl<S, Tx> Interpreter<S, Tx>
+where
+    S: InterpreterStorage,
+    Tx: ExecutableTransaction,
+{
+    /// Evaluate the triggers set in the debugger before the execution of
+    /// `instruction`. The opcode isn't checked when `resuming` from the current
+    /// location, since it was already reported.
+    pub(crate) fn eval_debugger_triggers(
+        &mut self,
+        instruction: RawInstruction,
+        resuming: bool,
+    ) -> Result<DebugEval, RuntimeError<S::DataError>> {
+        let location = self.debugger_location();
+        let debugger = &mut self.debugger;
+
+        let trigger =
+            if let Some((start, len)) = debugger.eval_memory(self.memory.as_ref()) {
+                Some(Trigger::Memory { start, len })
+            } else if let Some((contract, key)) = debugger
+                .eval_storage(|contract, key| {
+                    self.storage
+                        .merkle_contract_state(contract, key)
+                        .map(|value| value.map(Cow::into_owned))
+                })
+                .map_err(RuntimeError::Storage)?
+            {
+                Some(Trigger::Storage { contract, key })
+            } else if let Some(receipt) = debugger.eval_receipts(self.receipts.as_ref()) {
+                Some(Trigger::Receipt(receipt))
+            } else {
+                Instruction::try_from(instruction)
+                    .ok()
+                    .filter(|_| !resuming)
+                    .map(|instruction| instruction.opcode())
+                    .filter(|opcode| debugger.breaks_on_opcode(*opcode))
+                    .map(Trigger::Opcode)
+            };
+
+        Ok(trigger
+            .map(|trigger| DebugEval::Trigger(location, trigger))
+            .unwrap_or_default())
+    }
+}
+
 #[test]
 fn breakpoint_script() {
     use fuel_asm::op;
@@ -173,3 +318,326 @@ fn single_stepping() {
 
     assert_eq!(stops, vec![0, 4, 8, 4, 8, 4, 8, 4, 8, 4, 8, 12]);
 }
+
+#[test]
+fn conditional_breakpoint() {
+    use crate::state::Comparison;
+    use fuel_asm::op;
+    use fuel_tx::ConsensusParameters;
+
+    let mut vm = Interpreter::with_memory_storage();
+
+    let gas_limit = 1_000_000;
+    let height = Default::default();
+
+    // Repeats the middle two instructions five times
+    let script = [
+        op::addi(0x10, RegId::ZERO, 5),
+        op::addi(0x11, 0x11, 1),
+        op::jnei(0x10, 0x11, 1),
+        op::ret(0x10),
+    ]
+    .into_iter()
+    .collect();
+
+    let consensus_params = ConsensusParameters::standard();
+
+    let tx = TransactionBuilder::script(script, vec![])
+        .gas_limit(gas_limit)
+        .add_random_fee_input()
+        .finalize()
+        .into_checked(height, &consensus_params)
+        .expect("failed to generate checked tx");
+
+    let condition = RegisterCondition::new(RegId::new(0x11), Comparison::Eq, 3);
+    vm.set_conditional_breakpoint(Breakpoint::script(2), condition);
+
+    let state = vm
+        .transact(tx)
+        .map(ProgramState::from)
+        .expect("Failed to execute script!");
+
+    assert_eq!(state, Breakpoint::script(2));
+    assert_eq!(vm.registers()[0x11], 3);
+
+    let state = vm.resume().expect("Failed to resume");
+    assert_eq!(state, ProgramState::Return(5));
+}
+
+#[test]
+fn watchpoints_and_triggers() {
+    use fuel_asm::op;
+    use fuel_tx::ConsensusParameters;
+
+    let mut vm = Interpreter::with_memory_storage();
+
+    let gas_limit = 1_000_000;
+    let height = Default::default();
+
+    let script = [
+        op::movi(0x10, 8),
+        op::aloc(0x10),
+        op::movi(0x11, 42),
+        op::sw(RegId::HP, 0x11, 0),
+        op::log(0x11, RegId::ZERO, RegId::ZERO, RegId::ZERO),
+        op::noop(),
+        op::ret(RegId::ONE),
+    ]
+    .into_iter()
+    .collect();
+
+    let consensus_params = ConsensusParameters::standard();
+
+    let tx = TransactionBuilder::script(script, vec![])
+        .gas_limit(gas_limit)
+        .add_random_fee_input()
+        .finalize()
+        .into_checked(height, &consensus_params)
+        .expect("failed to generate checked tx");
+
+    vm.set_breakpoint(Breakpoint::script(3));
+    vm.break_on_receipt(ReceiptRepr::Log);
+    vm.break_on_opcode(Opcode::RET);
+
+    let state = vm
+        .transact(tx)
+        .map(ProgramState::from)
+        .expect("Failed to execute script!");
+    assert_eq!(state, Breakpoint::script(3));
+
+    // Watch the allocated word from the suspended state
+    let hp = vm.registers()[RegId::HP];
+    vm.watch_memory(hp, 8);
+
+    let mut triggers = Vec::new();
+    let mut state = vm.resume().expect("Failed to resume");
+    while let Some(debug) = state.debug_ref() {
+        triggers.push((debug.location().expect("Expected a location").pc(), *debug));
+        state = vm.resume().expect("Failed to resume");
+    }
+
+    assert_eq!(
+        triggers,
+        vec![
+            (
+                16,
+                DebugEval::Trigger(
+                    Breakpoint::script(4),
+                    Trigger::Memory { start: hp, len: 8 }
+                )
+            ),
+            (
+                20,
+                DebugEval::Trigger(
+                    Breakpoint::script(5),
+                    Trigger::Receipt(ReceiptRepr::Log)
+                )
+            ),
+            (
+                24,
+                DebugEval::Trigger(Breakpoint::script(6), Trigger::Opcode(Opcode::RET))
+            ),
+        ]
+    );
+    assert_eq!(state, ProgramState::Return(1));
+}
+
+#[test]
+fn simultaneous_watchpoints_are_reported_in_order() {
+    use fuel_asm::op;
+    use fuel_tx::ConsensusParameters;
+
+    let mut vm = Interpreter::with_memory_storage();
+
+    let gas_limit = 1_000_000;
+    let height = Default::default();
+
+    let script = [
+        op::movi(0x10, 8),
+        op::aloc(0x10),
+        op::not(0x11, RegId::ZERO),
+        op::sw(RegId::HP, 0x11, 0),
+        op::ret(RegId::ONE),
+    ]
+    .into_iter()
+    .collect();
+
+    let consensus_params = ConsensusParameters::standard();
+
+    let tx = TransactionBuilder::script(script, vec![])
+        .gas_limit(gas_limit)
+        .add_random_fee_input()
+        .finalize()
+        .into_checked(height, &consensus_params)
+        .expect("failed to generate checked tx");
+
+    vm.set_breakpoint(Breakpoint::script(3));
+
+    let state = vm
+        .transact(tx)
+        .map(ProgramState::from)
+        .expect("Failed to execute script!");
+    assert_eq!(state, Breakpoint::script(3));
+
+    // Both halves of the word change in the same step
+    let hp = vm.registers()[RegId::HP];
+    vm.watch_memory(hp + 4, 4);
+    vm.watch_memory(hp, 4);
+
+    let state = vm.resume().expect("Failed to resume");
+    assert_eq!(
+        state,
+        ProgramState::RunProgram(DebugEval::Trigger(
+            Breakpoint::script(4),
+            Trigger::Memory { start: hp, len: 4 }
+        ))
+    );
+}
+
+#[test]
+fn break_on_panic() {
+    use fuel_asm::op;
+    use fuel_tx::ConsensusParameters;
+
+    let mut vm = Interpreter::with_memory_storage();
+
+    let gas_limit = 1_000_000;
+    let height = Default::default();
+
+    let script = [
+        op::addi(0x10, RegId::ZERO, 8),
+        op::log(0x10, RegId::ZERO, RegId::ZERO, RegId::ZERO),
+        op::div(0x11, 0x10, RegId::ZERO),
+        op::ret(RegId::ONE),
+    ]
+    .into_iter()
+    .collect();
+
+    let consensus_params = ConsensusParameters::standard();
+
+    let tx = TransactionBuilder::script(script, vec![])
+        .gas_limit(gas_limit)
+        .add_random_fee_input()
+        .finalize()
+        .into_checked(height, &consensus_params)
+        .expect("failed to generate checked tx");
+
+    let mut undebugged = Interpreter::with_memory_storage();
+    let receipts = undebugged
+        .transact(tx.clone())
+        .expect("Failed to execute script!")
+        .receipts()
+        .to_vec();
+
+    vm.break_on_panic(PanicReason::ArithmeticError);
+
+    let state = vm
+        .transact(tx)
+        .map(ProgramState::from)
+        .expect("Failed to execute script!");
+    assert_eq!(
+        state,
+        ProgramState::RunProgram(DebugEval::Trigger(
+            Breakpoint::script(2),
+            Trigger::Panic(PanicReason::ArithmeticError)
+        ))
+    );
+    assert_eq!(vm.registers()[0x10], 8);
+
+    // The reported panic is processed when resuming
+    let err = vm.resume().expect_err("Expected a panic");
+    assert_eq!(err.panic_reason(), Some(PanicReason::ArithmeticError));
+
+    // The instruction isn't executed nor charged again. The panic is returned
+    // instead of the receipts `transact` generates for it.
+    let [receipts @ .., Receipt::Panic { reason, .. }, Receipt::ScriptResult { gas_used, .. }] =
+        receipts.as_slice()
+    else {
+        panic!("Expected the script to panic");
+    };
+    let program_receipts: Vec<_> = vm
+        .receipts()
+        .iter()
+        .filter(|receipt| !matches!(receipt, Receipt::ScriptResult { .. }))
+        .cloned()
+        .collect();
+    assert_eq!(program_receipts, receipts);
+    assert_eq!(err.instruction_result(), Some(*reason));
+    assert_eq!(
+        vm.registers()[RegId::GGAS],
+        undebugged.registers()[RegId::GGAS]
+    );
+    assert_eq!(
+        vm.registers()[RegId::CGAS],
+        undebugged.registers()[RegId::CGAS]
+    );
+    assert_eq!(gas_limit - vm.registers()[RegId::GGAS], *gas_used);
+}
+
+#[test]
+fn watch_storage_in_call() {
+    use crate::{
+        interpreter::InterpreterParams,
+        script_with_data_offset,
+        util::test_helpers::TestBuilder,
+    };
+    use fuel_asm::op;
+    use fuel_tx::ConsensusParameters;
+    use fuel_types::canonical::Serialize;
+
+    let mut test_context = TestBuilder::new(2322u64);
+    let gas_limit = 1_000_000;
+
+    let program = vec![
+        // Allocate 32 bytes for the zeroed state key
+        op::movi(0x15, Bytes32::LEN as u32),
+        op::aloc(0x15),
+        op::movi(0x10, 7),
+        op::sww(RegId::HP, 0x29, 0x10),
+        op::noop(),
+        op::ret(RegId::ONE),
+    ];
+    let contract_id = test_context.setup_contract(program, None, None).contract_id;
+
+    let (script, _) = script_with_data_offset!(
+        data_offset,
+        vec![
+            op::movi(0x10, data_offset as Immediate18),
+            op::call(0x10, RegId::ZERO, RegId::ZERO, RegId::CGAS),
+            op::ret(RegId::ONE),
+        ],
+        test_context.get_tx_params().tx_offset()
+    );
+    let script_data = Call::new(contract_id, 0, 0).to_bytes();
+    let tx = test_context
+        .start_script(script, script_data)
+        .gas_limit(gas_limit)
+        .contract_input(contract_id)
+        .fee_input()
+        .contract_output(&contract_id)
+        .build();
+
+    let mut vm = Interpreter::<_, Script>::with_storage(
+        test_context.get_storage().clone(),
+        InterpreterParams::from(&ConsensusParameters::standard()),
+    );
+    vm.watch_storage(contract_id, Bytes32::zeroed());
+
+    let state = vm
+        .transact(tx)
+        .map(ProgramState::from)
+        .expect("Failed to execute script!");
+    assert_eq!(
+        state,
+        ProgramState::RunProgram(DebugEval::Trigger(
+            Breakpoint::new(contract_id, 4),
+            Trigger::Storage {
+                contract: contract_id,
+                key: Bytes32::zeroed(),
+            }
+        ))
+    );
+
+    let state = vm.resume().expect("Failed to resume");
+    assert_eq!(state, ProgramState::Return(1));
+}
@@ -52,6 +52,7 @@ use super::{
 };
 use storage::*;
 
+pub(crate) mod history;
 mod storage;
 
 #[cfg(test)]
@@ -319,6 +320,29 @@ where
         })
 }
 
+/// Capture the changed regions between two slices of memory starting at `offset`.
+fn capture_memory_state(diff: &mut Diff<Deltas>, offset: usize, a: &[u8], b: &[u8]) {
+    let mut memory = a.iter().enumerate().zip(b.iter());
+
+    while let Some(((start, s_from), s_to)) = memory
+        .by_ref()
+        .find(|((_, a), b)| a != b)
+        .map(|((n, a), b)| ((n + offset, *a), *b))
+    {
+        let (mut from, mut to): (Vec<_>, Vec<_>) = memory
+            .by_ref()
+            .take_while(|((_, a), b)| a != b)
+            .map(|((_, a), b)| (*a, *b))
+            .unzip();
+        from.splice(..0, core::iter::once(s_from)).next();
+        to.splice(..0, core::iter::once(s_to)).next();
+        diff.changes.push(Change::Memory(Delta {
+            from: Memory { start, bytes: from },
+            to: Memory { start, bytes: to },
+        }));
+    }
+}
+
 impl<S, Tx> Interpreter<S, Tx> {
     /// The diff function generates a diff of VM state, represented by the Diff struct,
     /// between two VMs internal states.
@@ -351,25 +375,12 @@ impl<S, Tx> Interpreter<S, Tx> {
         );
         diff.changes.extend(balances);
 
-        let mut memory = self.memory.iter().enumerate().zip(other.memory.iter());
-
-        while let Some(((start, s_from), s_to)) = memory
-            .by_ref()
-            .find(|((_, a), b)| a != b)
-            .map(|((n, a), b)| ((n, *a), *b))
-        {
-            let (mut from, mut to): (Vec<_>, Vec<_>) = memory
-                .by_ref()
-                .take_while(|((_, a), b)| a != b)
-                .map(|((_, a), b)| (*a, *b))
-                .unzip();
-            from.splice(..0, core::iter::once(s_from)).next();
-            to.splice(..0, core::iter::once(s_to)).next();
-            diff.changes.push(Change::Memory(Delta {
-                from: Memory { start, bytes: from },
-                to: Memory { start, bytes: to },
-            }));
-        }
+        capture_memory_state(
+            &mut diff,
+            0,
+            self.memory.as_slice(),
+            other.memory.as_slice(),
+        );
 
         if self.context != other.context {
             diff.changes.push(Change::Context(Delta {
@@ -0,0 +1,434 @@
+//! Recording of the execution history, used to step backwards in the debugger.
+//!
+//! Every executed instruction is stored as the [`Diff`] that restores the VM to the
+//! state before it, along with the length of the storage change log of [`Record`].
+//! The diff is computed against a shadow copy of the VM state that is kept in sync
+//! with the VM. Only the used memory, `[0, $sp)` and `[$hp, VM_MAX_RAM)`, is compared
+//! since no instruction can write to the unallocated memory in between.
+
+use alloc::{
+    collections::VecDeque,
+    sync::Arc,
+    vec::Vec,
+};
+use core::fmt;
+use hashbrown::HashMap;
+
+use fuel_asm::Word;
+use fuel_tx::Receipt;
+use fuel_types::AssetId;
+
+use crate::{
+    call::CallFrame,
+    consts::*,
+    context::Context,
+    error::InterpreterError,
+    interpreter::{
+        balances::Balance,
+        memory::{
+            used_memory,
+            Memory as VmMemory,
+        },
+        ExecutableTransaction,
+        Interpreter,
+        PanicContext,
+    },
+    state::ProgramState,
+    storage::InterpreterStorage,
+};
+
+use super::{
+    capture_buffer_state,
+    capture_map_state,
+    capture_memory_state,
+    capture_vec_state,
+    storage::Record,
+    AnyDebug,
+    Change,
+    Delta,
+    Diff,
+    InitialVmState,
+    Memory,
+    Previous,
+};
+
+/// Point of the execution at which the history is updated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HistoryEvent {
+    /// An instruction is about to be executed.
+    BeforeInstruction,
+    /// An instruction was executed, successfully or not.
+    AfterInstruction,
+}
+
+/// Execution history of an interpreter.
+#[derive(Clone)]
+pub(crate) struct History<S, Tx> {
+    /// Maximum number of steps kept; the oldest ones are dropped first.
+    max_steps: usize,
+    steps: VecDeque<Step>,
+    /// The VM state after the latest step; captured before the first instruction.
+    shadow: Option<Shadow<Tx>>,
+    /// Records the events; specialized for the storage and transaction types.
+    hook: fn(&mut Interpreter<S, Tx>, HistoryEvent),
+}
+
+impl<S, Tx> fmt::Debug for History<S, Tx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("History")
+            .field("max_steps", &self.max_steps)
+            .field("steps", &self.steps.len())
+            .finish()
+    }
+}
+
+impl<S, Tx> History<S, Tx> {
+    /// Number of recorded instructions.
+    fn instructions(&self) -> usize {
+        self.steps.iter().filter(|step| step.instruction).count()
+    }
+
+    fn clear(&mut self) {
+        self.steps.clear();
+        self.shadow = None;
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Step {
+    /// Changes restoring the VM state before the step.
+    diff: Diff<InitialVmState>,
+    /// Length of the storage change log before the step.
+    storage_len: usize,
+    /// `false` if the changes weren't made by an instruction, e.g. by the
+    /// finalization of the transaction.
+    instruction: bool,
+}
+
+#[derive(Clone)]
+struct Shadow<Tx> {
+    registers: [Word; VM_REGISTER_COUNT],
+    memory: VmMemory<MEM_SIZE>,
+    frames: Vec<CallFrame>,
+    receipts: Vec<Receipt>,
+    balances: HashMap<AssetId, Balance>,
+    context: Context,
+    panic_context: PanicContext,
+    tx: Tx,
+    storage_len: usize,
+}
+
+impl<Tx> Shadow<Tx>
+where
+    Tx: PartialEq + Clone + fmt::Debug + 'static,
+{
+    fn capture<S>(vm: &Interpreter<S, Tx>, storage_len: usize) -> Self {
+        Self {
+            registers: vm.registers,
+            memory: vm.memory.clone(),
+            frames: vm.frames.clone(),
+            receipts: vm.receipts.as_ref().clone(),
+            balances: vm.balances.as_ref().clone(),
+            context: vm.context.clone(),
+            panic_context: vm.panic_context.clone(),
+            tx: vm.tx.clone(),
+            storage_len,
+        }
+    }
+
+    /// Compute the changes from the shadow to the VM state, and update the shadow.
+    fn update<S>(&mut self, vm: &Interpreter<S, Tx>, storage_len: usize) -> Step {
+        let mut diff = Diff {
+            changes: Vec::new(),
+        };
+
+        diff.changes.extend(capture_buffer_state(
+            self.registers.iter(),
+            vm.registers.iter(),
+            Change::Register,
+        ));
+        diff.changes.extend(capture_vec_state(
+            self.frames.iter(),
+            vm.frames.iter(),
+            Change::Frame,
+        ));
+        diff.changes.extend(capture_vec_state(
+            self.receipts.iter(),
+            vm.receipts.as_ref().iter(),
+            Change::Receipt,
+        ));
+        diff.changes.extend(capture_map_state(
+            &self.balances,
+            vm.balances.as_ref(),
+            Change::Balance,
+        ));
+
+        for range in used_memory(&self.registers, &vm.registers) {
+            capture_memory_state(
+                &mut diff,
+                range.start,
+                &self.memory[range.clone()],
+                &vm.memory[range],
+            );
+        }
+
+        if self.context != vm.context {
+            diff.changes.push(Change::Context(Delta {
+                from: self.context.clone(),
+                to: vm.context.clone(),
+            }))
+        }
+
+        if self.panic_context != vm.panic_context {
+            diff.changes.push(Change::PanicContext(Delta {
+                from: self.panic_context.clone(),
+                to: vm.panic_context.clone(),
+            }))
+        }
+
+        if self.tx != vm.tx {
+            let from: Arc<dyn AnyDebug> = Arc::new(self.tx.clone());
+            let to: Arc<dyn AnyDebug> = Arc::new(vm.tx.clone());
+            diff.changes.push(Change::Txn(Delta { from, to }))
+        }
+
+        let step = Step {
+            diff: diff.into(),
+            storage_len: self.storage_len,
+            instruction: false,
+        };
+        self.sync(vm, &step.diff);
+        self.storage_len = storage_len;
+
+        step
+    }
+
+    /// Copy the VM state that was changed by `diff` to the shadow.
+    fn sync<S>(&mut self, vm: &Interpreter<S, Tx>, diff: &Diff<InitialVmState>) {
+        self.registers = vm.registers;
+
+        for change in &diff.changes {
+            match change {
+                Change::Memory(Previous(Memory { start, bytes })) => {
+                    let range = *start..(*start + bytes.len());
+                    self.memory[range.clone()].copy_from_slice(&vm.memory[range]);
+                }
+                Change::Frame(_) => self.frames.clone_from(&vm.frames),
+                Change::Receipt(_) => self.receipts.clone_from(vm.receipts.as_ref()),
+                Change::Balance(_) => self.balances.clone_from(vm.balances.as_ref()),
+                Change::Context(_) => self.context = vm.context.clone(),
+                Change::PanicContext(_) => self.panic_context = vm.panic_context.clone(),
+                Change::Txn(_) => self.tx = vm.tx.clone(),
+                Change::Register(_) | Change::Storage(_) => (),
+            }
+        }
+    }
+}
+
+impl<S, Tx> Interpreter<S, Tx> {
+    /// Number of executed instructions that can be stepped back.
+    pub fn history_len(&self) -> usize {
+        self.history.as_ref().map_or(0, History::instructions)
+    }
+
+    pub(crate) fn record_history_event(&mut self, event: HistoryEvent) {
+        if let Some(hook) = self.history.as_ref().map(|history| history.hook) {
+            hook(self, event)
+        }
+    }
+
+    /// Drop the recorded history, e.g. when a new transaction is initialized.
+    pub(crate) fn clear_history(&mut self) {
+        if let Some(history) = self.history.as_mut() {
+            history.clear();
+        }
+    }
+}
+
+impl<S, Tx> Interpreter<Record<S>, Tx>
+where
+    S: InterpreterStorage,
+    Tx: ExecutableTransaction + PartialEq + fmt::Debug + 'static,
+{
+    /// Start recording the execution history, allowing to step backwards with
+    /// [`Self::step_back`] and [`Self::reverse_continue`].
+    ///
+    /// At most `max_steps` executed instructions are kept. Each of them stores only
+    /// the changes it made, but the recording keeps a copy of the VM memory.
+    pub fn record_history(&mut self, max_steps: usize) {
+        self.history = Some(History {
+            max_steps,
+            steps: VecDeque::new(),
+            shadow: None,
+            hook: Self::history_hook,
+        });
+    }
+
+    /// Stop recording the execution history and drop it.
+    pub fn stop_recording_history(&mut self) {
+        self.history = None;
+    }
+
+    fn history_hook(&mut self, event: HistoryEvent) {
+        let Some(mut history) = self.history.take() else {
+            return
+        };
+        let storage_len = self.storage.len();
+
+        match (event, history.shadow.as_mut()) {
+            (HistoryEvent::BeforeInstruction, None) => {
+                history.shadow = Some(Shadow::capture(self, storage_len));
+            }
+            (HistoryEvent::BeforeInstruction, Some(_)) => (),
+            (HistoryEvent::AfterInstruction, None) => (),
+            (HistoryEvent::AfterInstruction, Some(shadow)) => {
+                let mut step = shadow.update(self, storage_len);
+                step.instruction = true;
+                history.steps.push_back(step);
+
+                if history.steps.len() > history.max_steps {
+                    history.steps.pop_front();
+                }
+            }
+        }
+
+        self.history = Some(history);
+    }
+
+    /// Record the changes made outside of instructions since the latest step.
+    fn flush_history(&mut self) {
+        let Some(mut history) = self.history.take() else {
+            return
+        };
+        let storage_len = self.storage.len();
+
+        if let Some(shadow) = history.shadow.as_mut() {
+            let step = shadow.update(self, storage_len);
+            if !step.diff.changes.is_empty() || step.storage_len != storage_len {
+                history.steps.push_back(step);
+            }
+        }
+
+        self.history = Some(history);
+    }
+
+    /// Revert the latest executed instruction, and the changes made after it.
+    /// Return `false` if no instruction was recorded.
+    fn undo_history_step(&mut self) -> Result<bool, S::DataError> {
+        let Some(mut history) = self.history.take() else {
+            return Ok(false)
+        };
+
+        let result = self.undo_steps(&mut history);
+        self.history = Some(history);
+
+        result
+    }
+
+    fn undo_steps(
+        &mut self,
+        history: &mut History<Record<S>, Tx>,
+    ) -> Result<bool, S::DataError> {
+        if history.instructions() == 0 {
+            return Ok(false)
+        }
+
+        while let Some(step) = history.steps.pop_back() {
+            self.reset_vm_state(&step.diff);
+            self.storage.undo(step.storage_len)?;
+
+            if let Some(shadow) = history.shadow.as_mut() {
+                shadow.sync(self, &step.diff);
+                shadow.storage_len = step.storage_len;
+            }
+
+            if step.instruction {
+                break
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Revert the latest executed instruction, suspending the VM before it.
+    ///
+    /// Return `None` if there is no recorded instruction to revert. The execution
+    /// can be continued with [`Self::resume`].
+    pub fn step_back(
+        &mut self,
+    ) -> Result<Option<ProgramState>, InterpreterError<S::DataError>> {
+        self.flush_history();
+
+        if !self
+            .undo_history_step()
+            .map_err(InterpreterError::Storage)?
+        {
+            return Ok(None)
+        }
+
+        let state = ProgramState::RunProgram(self.debugger_location().into());
+        self.suspend_in_history(state)?;
+
+        Ok(Some(state))
+    }
+
+    /// Revert the executed instructions until a breakpoint or a trigger of the
+    /// debugger is reached, or the recorded history is exhausted.
+    ///
+    /// Watchpoints break before the instruction that changed the watched value.
+    /// Receipts and panics don't break when stepping backwards. Return `None` if
+    /// there is no recorded instruction to revert.
+    pub fn reverse_continue(
+        &mut self,
+    ) -> Result<Option<ProgramState>, InterpreterError<S::DataError>> {
+        self.flush_history();
+
+        let mut reverted = false;
+        let state = loop {
+            if !self
+                .undo_history_step()
+                .map_err(InterpreterError::Storage)?
+            {
+                if !reverted {
+                    return Ok(None)
+                }
+
+                break ProgramState::RunProgram(self.debugger_location().into())
+            }
+            reverted = true;
+
+            let debug = self.eval_debugger_state();
+            if !debug.should_continue() {
+                break ProgramState::RunProgram(debug)
+            }
+
+            if let Some(raw) = self.fetch_instruction() {
+                let debug = self
+                    .eval_debugger_triggers(raw, false)
+                    .map_err(InterpreterError::from)?;
+                if !debug.should_continue() {
+                    break ProgramState::RunProgram(debug)
+                }
+            }
+        };
+
+        self.suspend_in_history(state)?;
+
+        Ok(Some(state))
+    }
+
+    /// Suspend the VM after moving in the history.
+    fn suspend_in_history(
+        &mut self,
+        state: ProgramState,
+    ) -> Result<(), InterpreterError<S::DataError>> {
+        // The observed values of the watchpoints are updated without breaking
+        if let Some(raw) = self.fetch_instruction() {
+            self.eval_debugger_triggers(raw, true)
+                .map_err(InterpreterError::from)?;
+        }
+        self.debugger_set_last_state(state);
+
+        Ok(())
+    }
+}
@@ -12,6 +12,7 @@ use fuel_types::{
 };
 
 use crate::storage::{
+    state_keys,
     ContractsAssetKey,
     ContractsAssetsStorage,
     ContractsStateKey,
@@ -97,6 +98,9 @@ where
             panic_context: self.panic_context,
             profiler: self.profiler,
             interpreter_params: self.interpreter_params,
+            history: None,
+            #[cfg(feature = "trace")]
+            tracing: self.tracing,
         }
     }
 
@@ -170,6 +174,9 @@ where
             panic_context: self.panic_context,
             profiler: self.profiler,
             interpreter_params: self.interpreter_params,
+            history: None,
+            #[cfg(feature = "trace")]
+            tracing: self.tracing,
         }
     }
 
@@ -426,8 +433,19 @@ where
         start_key: &Bytes32,
         values: &[Bytes32],
     ) -> Result<Option<()>, Self::DataError> {
-        self.0
-            .merkle_contract_state_insert_range(contract, start_key, values)
+        let existing = self.state_range(contract, start_key, values.len() as Word)?;
+        let result = self
+            .0
+            .merkle_contract_state_insert_range(contract, start_key, values)?;
+
+        for ((key, existing), value) in state_keys(start_key).zip(existing).zip(values) {
+            let key = (contract, &key).into();
+            self.1.push(<ContractsState as StorageType>::record_insert(
+                &key, value, existing,
+            ));
+        }
+
+        Ok(result)
     }
 
     fn merkle_contract_state_remove_range(
@@ -436,8 +454,21 @@ where
         start_key: &Bytes32,
         range: Word,
     ) -> Result<Option<()>, S::DataError> {
-        self.0
-            .merkle_contract_state_remove_range(contract, start_key, range)
+        let existing = self.state_range(contract, start_key, range)?;
+        let result = self
+            .0
+            .merkle_contract_state_remove_range(contract, start_key, range)?;
+
+        for (key, existing) in state_keys(start_key).zip(existing) {
+            if let Some(existing) = existing {
+                let key = (contract, &key).into();
+                self.1.push(<ContractsState as StorageType>::record_remove(
+                    &key, existing,
+                ));
+            }
+        }
+
+        Ok(result)
     }
 }
 
@@ -507,4 +538,86 @@ where
     pub fn new(s: S) -> Self {
         Self(s, Vec::new())
     }
+
+    /// Number of recorded changes.
+    pub(super) fn len(&self) -> usize {
+        self.1.len()
+    }
+
+    /// Revert the recorded changes until only `len` of them remain.
+    pub(super) fn undo(&mut self, len: usize) -> Result<(), S::DataError> {
+        while self.1.len() > len {
+            match self.1.pop().expect("The length is checked above") {
+                StorageDelta::State(delta) => {
+                    undo_delta::<ContractsState, _>(&mut self.0, delta)?
+                }
+                StorageDelta::Assets(delta) => {
+                    undo_delta::<ContractsAssets, _>(&mut self.0, delta)?
+                }
+                StorageDelta::Info(delta) => {
+                    undo_delta::<ContractsInfo, _>(&mut self.0, delta)?
+                }
+                StorageDelta::RawCode(MappableDelta::Insert(key, _, Some(existing))) => {
+                    StorageMutate::<ContractsRawCode>::insert(
+                        &mut self.0,
+                        &key,
+                        existing.as_ref(),
+                    )
+                    .map(|_| ())?
+                }
+                StorageDelta::RawCode(MappableDelta::Insert(key, _, None)) => {
+                    StorageMutate::<ContractsRawCode>::remove(&mut self.0, &key)
+                        .map(|_| ())?
+                }
+                StorageDelta::RawCode(MappableDelta::Remove(key, existing)) => {
+                    StorageMutate::<ContractsRawCode>::insert(
+                        &mut self.0,
+                        &key,
+                        existing.as_ref(),
+                    )
+                    .map(|_| ())?
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn state_range(
+        &self,
+        contract: &ContractId,
+        start_key: &Bytes32,
+        range: Word,
+    ) -> Result<Vec<Option<Bytes32>>, S::DataError> {
+        Ok(self
+            .0
+            .merkle_contract_state_range(contract, start_key, range)?
+            .into_iter()
+            .map(|value| value.map(alloc::borrow::Cow::into_owned))
+            .collect())
+    }
+}
+
+/// Revert a change of a storage type whose key and value are sized.
+fn undo_delta<Type, S>(
+    storage: &mut S,
+    delta: MappableDelta<Type::OwnedKey, Type::OwnedValue>,
+) -> Result<(), S::DataError>
+where
+    Type: Mappable<
+        Key = <Type as Mappable>::OwnedKey,
+        Value = <Type as Mappable>::OwnedValue,
+    >,
+    S: InterpreterStorage
+        + StorageMutate<Type, Error = <S as InterpreterStorage>::DataError>,
+{
+    match delta {
+        MappableDelta::Insert(key, _, Some(existing))
+        | MappableDelta::Remove(key, existing) => {
+            StorageMutate::<Type>::insert(storage, &key, &existing).map(|_| ())
+        }
+        MappableDelta::Insert(key, _, None) => {
+            StorageMutate::<Type>::remove(storage, &key).map(|_| ())
+        }
+    }
 }
@@ -7,6 +7,7 @@ use crate::{
     },
     interpreter::{
         alu,
+        diff::history::HistoryEvent,
         flow::{
             JumpArgs,
             JumpMode,
@@ -14,7 +15,11 @@ use crate::{
         ExecutableTransaction,
         Interpreter,
     },
-    state::ExecuteState,
+    state::{
+        DebugEval,
+        ExecuteState,
+        Trigger,
+    },
     storage::InterpreterStorage,
 };
 
@@ -45,7 +50,7 @@ where
 
     /// Reads the current instruction located in `$m[$pc]`,
     /// returning `None` on any memory access violation.
-    fn fetch_instruction(&self) -> Option<RawInstruction> {
+    pub(crate) fn fetch_instruction(&self) -> Option<RawInstruction> {
         let start: usize = self.registers[RegId::PC].try_into().ok()?;
         let end = start.checked_add(Instruction::SIZE)?;
         let bytes = self.memory.get(start..end)?;
@@ -59,15 +64,61 @@ where
         &mut self,
         raw: R,
     ) -> Result<ExecuteState, InterpreterError<S::DataError>> {
-        if self.debugger.is_active() {
+        let location = self.debugger.is_active().then(|| self.debugger_location());
+        let resuming = location.is_some() && self.debugger_resuming();
+
+        if location.is_some() {
             let debug = self.eval_debugger_state();
             if !debug.should_continue() {
                 return Ok(debug.into())
             }
+
+            let debug = self
+                .eval_debugger_triggers(raw.into(), resuming)
+                .map_err(|e| InterpreterError::from_runtime(e, raw.into()))?;
+            if !debug.should_continue() {
+                return Ok(debug.into())
+            }
+        }
+
+        // A panic already reported in the current location is processed as usual,
+        // without charging the instruction again
+        if let Some(reason) = location
+            .filter(|_| resuming)
+            .and_then(|location| self.debugger.take_reported_panic(&location))
+        {
+            return Err(InterpreterError::from_runtime(
+                RuntimeError::Recoverable(reason),
+                raw.into(),
+            ))
         }
 
-        self.instruction_inner(raw.into())
-            .map_err(|e| InterpreterError::from_runtime(e, raw.into()))
+        self.profiler.set_call_stack(&self.frames);
+        self.record_history_event(HistoryEvent::BeforeInstruction);
+        #[cfg(feature = "trace")]
+        self.trace_before_instruction();
+        let result = self.instruction_inner(raw.into());
+        self.record_history_event(HistoryEvent::AfterInstruction);
+        #[cfg(feature = "trace")]
+        self.trace_after_instruction(
+            raw.into(),
+            match &result {
+                Err(RuntimeError::Recoverable(reason)) => Some(*reason),
+                _ => None,
+            },
+        );
+
+        match (result, location) {
+            (Err(RuntimeError::Recoverable(reason)), Some(location))
+                if self.debugger.breaks_on_panic(reason) =>
+            {
+                self.debugger.report_panic(location, reason);
+                Ok(DebugEval::Trigger(location, Trigger::Panic(reason)).into())
+            }
+            (result, _) => {
+                result.map_err(|e| InterpreterError::from_runtime(e, raw.into()))
+            }
+        }
     }
 
     fn instruction_inner(
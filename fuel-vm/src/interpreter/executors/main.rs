@@ -5,6 +5,7 @@ use alloc::{
     vec,
     vec::Vec,
 };
+use core::ops::Deref;
 
 use crate::{
     checked_transaction::{
@@ -12,6 +13,7 @@ use crate::{
         IntoChecked,
         ParallelExecutor,
     },
+    consts::SCRIPT_GAS_ESTIMATION_MARGIN,
     context::Context,
     error::{
         Bug,
@@ -31,13 +33,16 @@ use crate::{
         RuntimeError,
     },
     state::{
+        DryRun,
         ExecuteState,
         ProgramState,
+        ScriptGasEstimate,
         StateTransition,
         StateTransitionRef,
     },
     storage::{
         InterpreterStorage,
+        OverlayStorage,
         PredicateStorage,
     },
 };
@@ -52,6 +57,8 @@ use fuel_asm::{
 };
 use fuel_tx::{
     field::{
+        GasLimit,
+        GasPrice,
         ReceiptsRoot,
         Salt,
         Script as ScriptField,
@@ -65,12 +72,16 @@ use fuel_tx::{
         },
     },
     Chargeable,
+    CheckError,
+    ConsensusParameters,
     Contract,
     Create,
     FeeParameters,
     Input,
     Receipt,
+    Script,
     ScriptExecutionResult,
+    TransactionFee,
 };
 use fuel_types::{
     AssetId,
@@ -622,9 +633,12 @@ where
             let state = self.execute()?;
 
             if in_call {
-                // Only reverts should terminate execution from a call context
-                if let ExecuteState::Revert(r) = state {
-                    return Ok(ProgramState::Revert(r))
+                // Only reverts and debug events should interrupt execution from a call
+                // context
+                match state {
+                    ExecuteState::Revert(r) => return Ok(ProgramState::Revert(r)),
+                    ExecuteState::DebugEvent(d) => return Ok(ProgramState::RunProgram(d)),
+                    _ => (),
                 }
             } else {
                 match state {
@@ -694,6 +708,95 @@ where
     }
 }
 
+impl<S, Tx> Interpreter<OverlayStorage<S>, Tx>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+    Tx: ExecutableTransaction,
+    <Tx as IntoChecked>::Metadata: CheckedMetadata,
+{
+    /// Allocate internally a new instance of [`Interpreter`] reading through the
+    /// provided storage, execute the transaction and return the result of the
+    /// execution in form of [`DryRun`].
+    ///
+    /// The writes of the execution are kept in memory and returned instead of being
+    /// applied, so the storage can be shared, e.g. as `&S` or `Arc<S>`.
+    pub fn dry_run(
+        storage: S,
+        tx: Checked<Tx>,
+        params: InterpreterParams,
+    ) -> Result<DryRun<Tx>, InterpreterError<<S::Target as InterpreterStorage>::DataError>>
+    {
+        let mut interpreter =
+            Interpreter::with_storage(OverlayStorage::new(storage), params);
+        let state = ProgramState::from(interpreter.transact(tx)?);
+        let changes = interpreter.storage.take_diff();
+
+        Ok(DryRun::new(
+            StateTransition::new(state, interpreter.tx, interpreter.receipts.into()),
+            changes,
+        ))
+    }
+}
+
+impl<S> Interpreter<S, Script>
+where
+    S: InterpreterStorage,
+{
+    /// Estimate the gas limit of the script transaction, and set it as the gas limit
+    /// of `tx`.
+    ///
+    /// The script is executed with the maximum gas per transaction, against a
+    /// read-only view of the storage that keeps the writes of the execution in
+    /// memory. The gas price is zeroed for the dry run, so the inputs don't need to
+    /// cover the fee of the maximum gas.
+    ///
+    /// The gas price of `tx` is kept. The transaction has no other fee field: its max
+    /// fee follows from the gas limit and the gas price, and is returned in the
+    /// estimate. The base asset inputs of `tx` have to cover it, and the signatures
+    /// of `tx` have to be renewed before it's checked again.
+    pub fn estimate_script_gas(
+        &self,
+        tx: &mut Script,
+        consensus_params: &ConsensusParameters,
+    ) -> Result<ScriptGasEstimate, InterpreterError<S::DataError>> {
+        let block_height = self
+            .storage
+            .block_height()
+            .map_err(InterpreterError::Storage)?;
+        let max_gas = consensus_params.tx_params.max_gas_per_tx;
+
+        let mut dry_run = tx.clone();
+        *dry_run.gas_limit_mut() = max_gas;
+        *dry_run.gas_price_mut() = 0;
+        let checked = dry_run.into_checked_basic(block_height, consensus_params)?;
+
+        let mut interpreter = Interpreter::with_storage(
+            OverlayStorage::new(&self.storage),
+            self.interpreter_params.clone(),
+        );
+        let state = *interpreter.transact(checked)?.state();
+
+        let gas_used = max_gas.saturating_sub(interpreter.remaining_gas());
+        let gas_limit = gas_used
+            .saturating_add(gas_used.saturating_mul(SCRIPT_GAS_ESTIMATION_MARGIN) / 100)
+            .min(max_gas);
+
+        *tx.gas_limit_mut() = gas_limit;
+        let max_fee = TransactionFee::checked_from_tx(&consensus_params.fee_params, tx)
+            .ok_or(CheckError::ArithmeticOverflow)?
+            .max_fee();
+
+        Ok(ScriptGasEstimate::new(
+            gas_used,
+            gas_limit,
+            max_fee,
+            state,
+            interpreter.receipts.into(),
+        ))
+    }
+}
+
 impl<S, Tx> Interpreter<S, Tx>
 where
     S: InterpreterStorage,
@@ -40,6 +40,9 @@ where
         runtime_balances: RuntimeBalances,
         gas_limit: Word,
     ) -> Result<(), RuntimeError<S::DataError>> {
+        self.clear_history();
+        #[cfg(feature = "trace")]
+        self.reset_tracing();
         self.tx = tx;
 
         self.initial_balances = initial_balances.clone();
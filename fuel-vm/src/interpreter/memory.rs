@@ -329,6 +329,25 @@ where
     }
 }
 
+/// The memory regions in use with either of two register states: the stack up to the
+/// higher `$sp` and the heap from the lower `$hp`. The rest of the memory is unused
+/// with both.
+pub(crate) fn used_memory(
+    a: &[Word; VM_REGISTER_COUNT],
+    b: &[Word; VM_REGISTER_COUNT],
+) -> [Range<usize>; 2] {
+    let register = |registers: &[Word; VM_REGISTER_COUNT], reg: RegId| {
+        usize::try_from(registers[reg]).map_or(MEM_SIZE, |r| r.min(MEM_SIZE))
+    };
+
+    let stack = register(a, RegId::SP).max(register(b, RegId::SP));
+    let heap = register(a, RegId::HP)
+        .min(register(b, RegId::HP))
+        .max(stack);
+
+    [0..stack, heap..MEM_SIZE]
+}
+
 /// Update stack pointer, checking for validity first.
 pub(crate) fn try_update_stack_pointer(
     mut sp: RegMut<SP>,
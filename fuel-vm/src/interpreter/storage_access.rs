@@ -0,0 +1,666 @@
+//! # Storage access tracing
+//! This module provides the ability to observe every access of the VM to the contract
+//! code, contract state and contract balances in its storage. Each access is reported
+//! to a [`StorageAccessTracer`] as a read or a write, along with the value before and
+//! after the access. The code of a contract is only loaded for a read if the tracer asks
+//! for it. The reads of the block timestamps and hashes are reported too.
+
+use alloc::{
+    borrow::Cow,
+    vec::Vec,
+};
+use core::cell::RefCell;
+
+use fuel_storage::{
+    Mappable,
+    MerkleRoot,
+    MerkleRootStorage,
+    StorageInspect,
+    StorageMutate,
+    StorageRead,
+    StorageSize,
+};
+use fuel_tx::Contract;
+use fuel_types::{
+    BlockHeight,
+    Bytes32,
+    ContractId,
+    Salt,
+    Word,
+};
+
+use crate::storage::{
+    state_keys,
+    ContractsAssetKey,
+    ContractsAssets,
+    ContractsAssetsStorage,
+    ContractsInfo,
+    ContractsRawCode,
+    ContractsState,
+    ContractsStateKey,
+    InterpreterStorage,
+};
+
+use super::{
+    ExecutableTransaction,
+    Interpreter,
+};
+
+/// Whether a storage access observed or modified the entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessKind {
+    /// The entry was read; `before` and `after` are the same.
+    Read,
+    /// The entry was inserted or removed.
+    Write,
+}
+
+/// A single access to a storage entry. `None` means the entry is unset.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StorageAccess<K, V> {
+    /// Read or write.
+    pub kind: AccessKind,
+    /// The key of the entry.
+    pub key: K,
+    /// The value of the entry before the access.
+    pub before: Option<V>,
+    /// The value of the entry after the access.
+    pub after: Option<V>,
+}
+
+impl<K, V> StorageAccess<K, V>
+where
+    V: Clone,
+{
+    fn read(key: K, value: Option<V>) -> Self {
+        Self {
+            kind: AccessKind::Read,
+            key,
+            before: value.clone(),
+            after: value,
+        }
+    }
+
+    fn write(key: K, before: Option<V>, after: Option<V>) -> Self {
+        Self {
+            kind: AccessKind::Write,
+            key,
+            before,
+            after,
+        }
+    }
+}
+
+/// Receives the storage accesses of the VM, in the order they happen.
+///
+/// All methods do nothing by default.
+pub trait StorageAccessTracer {
+    /// A write of the code of a contract.
+    fn contract_code(&mut self, _access: StorageAccess<ContractId, Contract>) {}
+
+    /// A read of the code of the contract `key`. Calling `code` loads the code from the
+    /// storage, `None` meaning the contract doesn't exist.
+    fn contract_code_read(
+        &mut self,
+        _key: ContractId,
+        _code: &mut dyn FnMut() -> Option<Contract>,
+    ) {
+    }
+
+    /// An access to a slot of the state of a contract.
+    fn contract_state(&mut self, _access: StorageAccess<ContractsStateKey, Bytes32>) {}
+
+    /// An access to the balance of an asset of a contract.
+    fn contract_balance(&mut self, _access: StorageAccess<ContractsAssetKey, Word>) {}
+
+    /// A read of the timestamp or the hash of the block at `height`.
+    fn block(&mut self, _height: BlockHeight) {}
+}
+
+impl<T> StorageAccessTracer for &mut T
+where
+    T: StorageAccessTracer,
+{
+    fn contract_code(&mut self, access: StorageAccess<ContractId, Contract>) {
+        (*self).contract_code(access)
+    }
+
+    fn contract_code_read(
+        &mut self,
+        key: ContractId,
+        code: &mut dyn FnMut() -> Option<Contract>,
+    ) {
+        (*self).contract_code_read(key, code)
+    }
+
+    fn contract_state(&mut self, access: StorageAccess<ContractsStateKey, Bytes32>) {
+        (*self).contract_state(access)
+    }
+
+    fn contract_balance(&mut self, access: StorageAccess<ContractsAssetKey, Word>) {
+        (*self).contract_balance(access)
+    }
+
+    fn block(&mut self, height: BlockHeight) {
+        (*self).block(height)
+    }
+}
+
+/// A [`StorageAccessTracer`] keeping all accesses, grouped by table.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StorageAccessLog {
+    /// Accesses to the contracts code.
+    pub contract_code: Vec<StorageAccess<ContractId, Contract>>,
+    /// Accesses to the contracts state.
+    pub contract_state: Vec<StorageAccess<ContractsStateKey, Bytes32>>,
+    /// Accesses to the contracts balances.
+    pub contract_balance: Vec<StorageAccess<ContractsAssetKey, Word>>,
+}
+
+impl StorageAccessTracer for StorageAccessLog {
+    fn contract_code(&mut self, access: StorageAccess<ContractId, Contract>) {
+        self.contract_code.push(access)
+    }
+
+    fn contract_code_read(
+        &mut self,
+        key: ContractId,
+        code: &mut dyn FnMut() -> Option<Contract>,
+    ) {
+        self.contract_code.push(StorageAccess::read(key, code()))
+    }
+
+    fn contract_state(&mut self, access: StorageAccess<ContractsStateKey, Bytes32>) {
+        self.contract_state.push(access)
+    }
+
+    fn contract_balance(&mut self, access: StorageAccess<ContractsAssetKey, Word>) {
+        self.contract_balance.push(access)
+    }
+}
+
+#[derive(Debug)]
+/// Storage wrapper reporting every access to a [`StorageAccessTracer`].
+pub struct Traced<S, T> {
+    storage: S,
+    tracer: RefCell<T>,
+}
+
+impl<S, T> Traced<S, T> {
+    /// Wrap the storage, reporting its accesses to `tracer`.
+    pub fn new(storage: S, tracer: T) -> Self {
+        Self {
+            storage,
+            tracer: RefCell::new(tracer),
+        }
+    }
+
+    /// The underlying storage.
+    pub const fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// The tracer receiving the accesses.
+    pub fn tracer_mut(&mut self) -> &mut T {
+        self.tracer.get_mut()
+    }
+
+    /// Return the underlying storage and the tracer.
+    pub fn into_inner(self) -> (S, T) {
+        (self.storage, self.tracer.into_inner())
+    }
+}
+
+impl<S, Tx> Interpreter<S, Tx>
+where
+    S: InterpreterStorage,
+    Tx: ExecutableTransaction,
+{
+    /// Add a [`Traced`] wrapper around the storage to report every access of this VM
+    /// to its storage to `tracer`.
+    pub fn add_access_tracer<T>(self, tracer: T) -> Interpreter<Traced<S, T>, Tx>
+    where
+        T: StorageAccessTracer,
+    {
+        Interpreter {
+            registers: self.registers,
+            memory: self.memory,
+            frames: self.frames,
+            receipts: self.receipts,
+            tx: self.tx,
+            initial_balances: self.initial_balances,
+            storage: Traced::new(self.storage, tracer),
+            debugger: self.debugger,
+            context: self.context,
+            balances: self.balances,
+            panic_context: self.panic_context,
+            profiler: self.profiler,
+            interpreter_params: self.interpreter_params,
+            history: None,
+            #[cfg(feature = "trace")]
+            tracing: self.tracing,
+        }
+    }
+}
+
+impl<S, T, Tx> Interpreter<Traced<S, T>, Tx>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+    Tx: ExecutableTransaction,
+{
+    /// Remove the [`Traced`] wrapper from the storage, returning the tracer.
+    pub fn remove_access_tracer(self) -> (Interpreter<S, Tx>, T) {
+        let (storage, tracer) = self.storage.into_inner();
+        let interpreter = Interpreter {
+            registers: self.registers,
+            memory: self.memory,
+            frames: self.frames,
+            receipts: self.receipts,
+            tx: self.tx,
+            initial_balances: self.initial_balances,
+            storage,
+            debugger: self.debugger,
+            context: self.context,
+            balances: self.balances,
+            panic_context: self.panic_context,
+            profiler: self.profiler,
+            interpreter_params: self.interpreter_params,
+            history: None,
+            #[cfg(feature = "trace")]
+            tracing: self.tracing,
+        };
+
+        (interpreter, tracer)
+    }
+
+    /// The tracer receiving the storage accesses of this VM.
+    pub fn access_tracer_mut(&mut self) -> &mut T {
+        self.storage.tracer_mut()
+    }
+}
+
+impl<S, T> Traced<S, T>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+{
+    /// Report a read of the code of `key`, loading it only if the tracer asks for it.
+    fn trace_code_read(&self, key: &ContractId) -> Result<(), S::DataError> {
+        let mut error = None;
+        let mut code = || {
+            StorageInspect::<ContractsRawCode>::get(&self.storage, key)
+                .map_err(|e| error = Some(e))
+                .ok()
+                .flatten()
+                .map(Cow::into_owned)
+        };
+        self.tracer.borrow_mut().contract_code_read(*key, &mut code);
+        error.map_or(Ok(()), Err)
+    }
+
+    fn owned_state_range(
+        &self,
+        contract: &ContractId,
+        start_key: &Bytes32,
+        range: Word,
+    ) -> Result<Vec<Option<Bytes32>>, S::DataError> {
+        Ok(self
+            .storage
+            .merkle_contract_state_range(contract, start_key, range)?
+            .into_iter()
+            .map(|value| value.map(Cow::into_owned))
+            .collect())
+    }
+}
+
+impl<S, T> StorageInspect<ContractsRawCode> for Traced<S, T>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+{
+    type Error = S::DataError;
+
+    fn get(&self, key: &ContractId) -> Result<Option<Cow<'_, Contract>>, Self::Error> {
+        let value = StorageInspect::<ContractsRawCode>::get(&self.storage, key)?;
+        self.tracer
+            .borrow_mut()
+            .contract_code_read(*key, &mut || value.as_deref().cloned());
+        Ok(value)
+    }
+
+    fn contains_key(&self, key: &ContractId) -> Result<bool, Self::Error> {
+        self.trace_code_read(key)?;
+        StorageInspect::<ContractsRawCode>::contains_key(&self.storage, key)
+    }
+}
+
+impl<S, T> StorageMutate<ContractsRawCode> for Traced<S, T>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+{
+    fn insert(
+        &mut self,
+        key: &ContractId,
+        value: &[u8],
+    ) -> Result<Option<Contract>, Self::Error> {
+        let before =
+            StorageMutate::<ContractsRawCode>::insert(&mut self.storage, key, value)?;
+        self.tracer.get_mut().contract_code(StorageAccess::write(
+            *key,
+            before.clone(),
+            Some(value.into()),
+        ));
+        Ok(before)
+    }
+
+    fn remove(&mut self, key: &ContractId) -> Result<Option<Contract>, Self::Error> {
+        let before = StorageMutate::<ContractsRawCode>::remove(&mut self.storage, key)?;
+        self.tracer.get_mut().contract_code(StorageAccess::write(
+            *key,
+            before.clone(),
+            None,
+        ));
+        Ok(before)
+    }
+}
+
+impl<S, T> StorageSize<ContractsRawCode> for Traced<S, T>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+{
+    fn size_of_value(&self, key: &ContractId) -> Result<Option<usize>, Self::Error> {
+        self.trace_code_read(key)?;
+        StorageSize::<ContractsRawCode>::size_of_value(&self.storage, key)
+    }
+}
+
+impl<S, T> StorageRead<ContractsRawCode> for Traced<S, T>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+{
+    fn read(
+        &self,
+        key: &ContractId,
+        buf: &mut [u8],
+    ) -> Result<Option<usize>, Self::Error> {
+        self.trace_code_read(key)?;
+        StorageRead::<ContractsRawCode>::read(&self.storage, key, buf)
+    }
+
+    fn read_alloc(&self, key: &ContractId) -> Result<Option<Vec<u8>>, Self::Error> {
+        self.trace_code_read(key)?;
+        StorageRead::<ContractsRawCode>::read_alloc(&self.storage, key)
+    }
+}
+
+impl<S, T> StorageInspect<ContractsInfo> for Traced<S, T>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+{
+    type Error = S::DataError;
+
+    fn get(
+        &self,
+        key: &ContractId,
+    ) -> Result<Option<Cow<'_, (Salt, Bytes32)>>, Self::Error> {
+        StorageInspect::<ContractsInfo>::get(&self.storage, key)
+    }
+
+    fn contains_key(&self, key: &ContractId) -> Result<bool, Self::Error> {
+        StorageInspect::<ContractsInfo>::contains_key(&self.storage, key)
+    }
+}
+
+impl<S, T> StorageMutate<ContractsInfo> for Traced<S, T>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+{
+    fn insert(
+        &mut self,
+        key: &ContractId,
+        value: &(Salt, Bytes32),
+    ) -> Result<Option<(Salt, Bytes32)>, Self::Error> {
+        StorageMutate::<ContractsInfo>::insert(&mut self.storage, key, value)
+    }
+
+    fn remove(
+        &mut self,
+        key: &ContractId,
+    ) -> Result<Option<(Salt, Bytes32)>, Self::Error> {
+        StorageMutate::<ContractsInfo>::remove(&mut self.storage, key)
+    }
+}
+
+impl<S, T> StorageInspect<ContractsAssets> for Traced<S, T>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+{
+    type Error = S::DataError;
+
+    fn get(
+        &self,
+        key: &<ContractsAssets as Mappable>::Key,
+    ) -> Result<Option<Cow<'_, Word>>, Self::Error> {
+        let value = StorageInspect::<ContractsAssets>::get(&self.storage, key)?;
+        self.tracer
+            .borrow_mut()
+            .contract_balance(StorageAccess::read(*key, value.as_deref().copied()));
+        Ok(value)
+    }
+
+    fn contains_key(
+        &self,
+        key: &<ContractsAssets as Mappable>::Key,
+    ) -> Result<bool, Self::Error> {
+        StorageInspect::<ContractsAssets>::get(self, key).map(|value| value.is_some())
+    }
+}
+
+impl<S, T> StorageMutate<ContractsAssets> for Traced<S, T>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+{
+    fn insert(
+        &mut self,
+        key: &<ContractsAssets as Mappable>::Key,
+        value: &Word,
+    ) -> Result<Option<Word>, Self::Error> {
+        let before =
+            StorageMutate::<ContractsAssets>::insert(&mut self.storage, key, value)?;
+        self.tracer.get_mut().contract_balance(StorageAccess::write(
+            *key,
+            before,
+            Some(*value),
+        ));
+        Ok(before)
+    }
+
+    fn remove(
+        &mut self,
+        key: &<ContractsAssets as Mappable>::Key,
+    ) -> Result<Option<Word>, Self::Error> {
+        let before = StorageMutate::<ContractsAssets>::remove(&mut self.storage, key)?;
+        self.tracer
+            .get_mut()
+            .contract_balance(StorageAccess::write(*key, before, None));
+        Ok(before)
+    }
+}
+
+impl<S, T> MerkleRootStorage<ContractId, ContractsAssets> for Traced<S, T>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+{
+    fn root(&self, parent: &ContractId) -> Result<MerkleRoot, Self::Error> {
+        MerkleRootStorage::<ContractId, ContractsAssets>::root(&self.storage, parent)
+    }
+}
+
+impl<S, T> StorageInspect<ContractsState> for Traced<S, T>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+{
+    type Error = S::DataError;
+
+    fn get(
+        &self,
+        key: &<ContractsState as Mappable>::Key,
+    ) -> Result<Option<Cow<'_, Bytes32>>, Self::Error> {
+        let value = StorageInspect::<ContractsState>::get(&self.storage, key)?;
+        self.tracer
+            .borrow_mut()
+            .contract_state(StorageAccess::read(*key, value.as_deref().copied()));
+        Ok(value)
+    }
+
+    fn contains_key(
+        &self,
+        key: &<ContractsState as Mappable>::Key,
+    ) -> Result<bool, Self::Error> {
+        StorageInspect::<ContractsState>::get(self, key).map(|value| value.is_some())
+    }
+}
+
+impl<S, T> StorageMutate<ContractsState> for Traced<S, T>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+{
+    fn insert(
+        &mut self,
+        key: &<ContractsState as Mappable>::Key,
+        value: &Bytes32,
+    ) -> Result<Option<Bytes32>, Self::Error> {
+        let before =
+            StorageMutate::<ContractsState>::insert(&mut self.storage, key, value)?;
+        self.tracer.get_mut().contract_state(StorageAccess::write(
+            *key,
+            before,
+            Some(*value),
+        ));
+        Ok(before)
+    }
+
+    fn remove(
+        &mut self,
+        key: &<ContractsState as Mappable>::Key,
+    ) -> Result<Option<Bytes32>, Self::Error> {
+        let before = StorageMutate::<ContractsState>::remove(&mut self.storage, key)?;
+        self.tracer
+            .get_mut()
+            .contract_state(StorageAccess::write(*key, before, None));
+        Ok(before)
+    }
+}
+
+impl<S, T> MerkleRootStorage<ContractId, ContractsState> for Traced<S, T>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+{
+    fn root(&self, parent: &ContractId) -> Result<MerkleRoot, Self::Error> {
+        MerkleRootStorage::<ContractId, ContractsState>::root(&self.storage, parent)
+    }
+}
+
+impl<S, T> ContractsAssetsStorage for Traced<S, T>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+{
+}
+
+impl<S, T> InterpreterStorage for Traced<S, T>
+where
+    S: InterpreterStorage,
+    T: StorageAccessTracer,
+{
+    type DataError = S::DataError;
+
+    fn block_height(&self) -> Result<BlockHeight, Self::DataError> {
+        self.storage.block_height()
+    }
+
+    fn timestamp(&self, height: BlockHeight) -> Result<Word, Self::DataError> {
+        self.tracer.borrow_mut().block(height);
+        self.storage.timestamp(height)
+    }
+
+    fn block_hash(&self, block_height: BlockHeight) -> Result<Bytes32, Self::DataError> {
+        self.tracer.borrow_mut().block(block_height);
+        self.storage.block_hash(block_height)
+    }
+
+    fn coinbase(&self) -> Result<ContractId, Self::DataError> {
+        self.storage.coinbase()
+    }
+
+    fn merkle_contract_state_range(
+        &self,
+        id: &ContractId,
+        start_key: &Bytes32,
+        range: Word,
+    ) -> Result<Vec<Option<Cow<'_, Bytes32>>>, Self::DataError> {
+        let values = self
+            .storage
+            .merkle_contract_state_range(id, start_key, range)?;
+
+        let mut tracer = self.tracer.borrow_mut();
+        for (key, value) in state_keys(start_key).zip(&values) {
+            let key = (id, &key).into();
+            tracer.contract_state(StorageAccess::read(key, value.as_deref().copied()));
+        }
+
+        Ok(values)
+    }
+
+    fn merkle_contract_state_insert_range(
+        &mut self,
+        contract: &ContractId,
+        start_key: &Bytes32,
+        values: &[Bytes32],
+    ) -> Result<Option<()>, Self::DataError> {
+        let before = self.owned_state_range(contract, start_key, values.len() as Word)?;
+        let result = self
+            .storage
+            .merkle_contract_state_insert_range(contract, start_key, values)?;
+
+        let tracer = self.tracer.get_mut();
+        for ((key, before), value) in state_keys(start_key).zip(before).zip(values) {
+            let key = (contract, &key).into();
+            tracer.contract_state(StorageAccess::write(key, before, Some(*value)));
+        }
+
+        Ok(result)
+    }
+
+    fn merkle_contract_state_remove_range(
+        &mut self,
+        contract: &ContractId,
+        start_key: &Bytes32,
+        range: Word,
+    ) -> Result<Option<()>, Self::DataError> {
+        let before = self.owned_state_range(contract, start_key, range)?;
+        let result = self
+            .storage
+            .merkle_contract_state_remove_range(contract, start_key, range)?;
+
+        let tracer = self.tracer.get_mut();
+        for (key, before) in state_keys(start_key).zip(before) {
+            let key = (contract, &key).into();
+            tracer.contract_state(StorageAccess::write(key, before, None));
+        }
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,468 @@
+//! # Instruction tracing
+//! This module provides a step-by-step trace of the execution. For every executed
+//! instruction, an [`InstructionTracer`] receives an [`InstructionStep`] describing the
+//! location, the instruction, the gas it charged and the registers, memory and receipts
+//! it changed.
+//!
+//! Memory writes are detected by comparing the used memory, `[0, $sp)` and
+//! `[$hp, VM_MAX_RAM)`, with a copy kept by the tracer, so writes that don't change the
+//! content of the memory aren't reported.
+
+use alloc::{
+    boxed::Box,
+    vec::Vec,
+};
+use core::{
+    fmt,
+    ops::Range,
+};
+
+use dyn_clone::DynClone;
+use fuel_asm::{
+    Instruction,
+    PanicReason,
+    RawInstruction,
+    RegId,
+};
+use fuel_tx::Receipt;
+use fuel_types::{
+    ContractId,
+    Word,
+};
+
+use crate::consts::{
+    MEM_SIZE,
+    VM_REGISTER_COUNT,
+};
+
+use super::{
+    memory::{
+        used_memory,
+        Memory,
+    },
+    Interpreter,
+};
+
+#[cfg(feature = "trace-json")]
+pub use json::JsonLinesTracer;
+
+/// A register changed by an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegisterChange {
+    /// The index of the register.
+    pub register: u8,
+    /// The value before the instruction.
+    pub before: Word,
+    /// The value after the instruction.
+    pub after: Word,
+}
+
+/// A contiguous region of memory changed by an instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryWrite {
+    /// The address of the first changed byte.
+    pub start: Word,
+    /// The content of the region after the instruction.
+    #[cfg_attr(feature = "serde", serde(with = "hex_bytes"))]
+    pub bytes: Vec<u8>,
+}
+
+/// A single executed instruction and its effects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InstructionStep {
+    /// Number of instructions executed before this one in the transaction.
+    pub index: u64,
+    /// The contract being executed, or `None` for the script.
+    pub contract: Option<ContractId>,
+    /// The value of `$pc` before the instruction.
+    pub pc: Word,
+    /// The value of `$is` before the instruction.
+    pub is: Word,
+    /// The raw instruction.
+    pub raw: RawInstruction,
+    /// The decoded instruction; `None` if the raw instruction is invalid.
+    #[cfg_attr(feature = "serde", serde(with = "instruction_text"))]
+    pub instruction: Option<Instruction>,
+    /// The gas charged, as the decrease of `$ggas`.
+    pub gas_used: Word,
+    /// The registers whose value changed.
+    pub registers: Vec<RegisterChange>,
+    /// The regions of memory whose content changed.
+    pub memory: Vec<MemoryWrite>,
+    /// The receipts emitted.
+    pub receipts: Vec<Receipt>,
+    /// The reason of the panic, if the instruction panicked.
+    pub panic: Option<PanicReason>,
+}
+
+/// Receives every instruction executed by the interpreter, in order.
+pub trait InstructionTracer: DynClone {
+    /// Called after the execution of an instruction, successful or not.
+    fn on_instruction(&mut self, step: &InstructionStep);
+}
+
+dyn_clone::clone_trait_object!(InstructionTracer);
+
+impl InstructionTracer for Box<dyn InstructionTracer + Send + Sync> {
+    fn on_instruction(&mut self, step: &InstructionStep) {
+        (**self).on_instruction(step)
+    }
+}
+
+/// The tracer of an interpreter, along with the state needed to compute the steps.
+#[derive(Clone)]
+pub(crate) struct Tracing {
+    tracer: Box<dyn InstructionTracer + Send + Sync>,
+    /// The VM state after the latest traced instruction.
+    shadow: Option<Shadow>,
+    /// The state before the current instruction.
+    before: Option<Before>,
+    index: u64,
+}
+
+impl fmt::Debug for Tracing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracing")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone)]
+struct Shadow {
+    registers: [Word; VM_REGISTER_COUNT],
+    memory: Memory<MEM_SIZE>,
+    receipts: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Before {
+    contract: Option<ContractId>,
+}
+
+impl<S, Tx> Interpreter<S, Tx> {
+    /// Trace every executed instruction with `tracer`.
+    ///
+    /// The tracer keeps a copy of the VM memory to detect the memory writes.
+    pub fn set_instruction_tracer<T>(&mut self, tracer: T)
+    where
+        T: InstructionTracer + Send + Sync + 'static,
+    {
+        self.tracing = Some(Tracing {
+            tracer: Box::new(tracer),
+            shadow: None,
+            before: None,
+            index: 0,
+        });
+    }
+
+    /// Stop tracing the executed instructions.
+    pub fn remove_instruction_tracer(&mut self) {
+        self.tracing = None;
+    }
+
+    /// Restart the numbering of the steps, e.g. when a new transaction is
+    /// initialized.
+    pub(crate) fn reset_tracing(&mut self) {
+        if let Some(tracing) = self.tracing.as_mut() {
+            tracing.shadow = None;
+            tracing.before = None;
+            tracing.index = 0;
+        }
+    }
+
+    pub(crate) fn trace_before_instruction(&mut self) {
+        let Some(mut tracing) = self.tracing.take() else {
+            return
+        };
+
+        match tracing.shadow.as_mut() {
+            None => {
+                tracing.shadow = Some(Shadow {
+                    registers: self.registers,
+                    memory: self.memory.clone(),
+                    receipts: self.receipts.len(),
+                })
+            }
+            // The VM was changed outside of an instruction, e.g. when suspended by
+            // the debugger; those changes aren't part of the next step
+            Some(shadow)
+                if shadow.registers != self.registers
+                    || shadow.receipts != self.receipts.len() =>
+            {
+                for range in used_memory(&shadow.registers, &self.registers) {
+                    shadow.memory[range.clone()].copy_from_slice(&self.memory[range]);
+                }
+                shadow.registers = self.registers;
+                shadow.receipts = self.receipts.len();
+            }
+            Some(_) => {}
+        }
+
+        tracing.before = Some(Before {
+            contract: self.contract_id(),
+        });
+        self.tracing = Some(tracing);
+    }
+
+    pub(crate) fn trace_after_instruction(
+        &mut self,
+        raw: RawInstruction,
+        panic: Option<PanicReason>,
+    ) {
+        let Some(mut tracing) = self.tracing.take() else {
+            return
+        };
+        let (Some(shadow), Some(before)) =
+            (tracing.shadow.as_mut(), tracing.before.take())
+        else {
+            self.tracing = Some(tracing);
+            return
+        };
+
+        let registers = shadow
+            .registers
+            .iter()
+            .zip(self.registers.iter())
+            .enumerate()
+            .filter(|(_, (before, after))| before != after)
+            .map(|(register, (before, after))| RegisterChange {
+                register: register as u8,
+                before: *before,
+                after: *after,
+            })
+            .collect();
+
+        let mut memory = Vec::new();
+        for range in used_memory(&shadow.registers, &self.registers) {
+            for changed in
+                changed_ranges(&shadow.memory[range.clone()], &self.memory[range.clone()])
+            {
+                let changed = (range.start + changed.start)..(range.start + changed.end);
+                shadow.memory[changed.clone()]
+                    .copy_from_slice(&self.memory[changed.clone()]);
+                memory.push(MemoryWrite {
+                    start: changed.start as Word,
+                    bytes: self.memory[changed].to_vec(),
+                });
+            }
+        }
+
+        let receipts = self
+            .receipts
+            .as_ref()
+            .get(shadow.receipts..)
+            .unwrap_or_default()
+            .to_vec();
+
+        let step = InstructionStep {
+            index: tracing.index,
+            contract: before.contract,
+            pc: shadow.registers[RegId::PC],
+            is: shadow.registers[RegId::IS],
+            raw,
+            instruction: Instruction::try_from(raw).ok(),
+            gas_used: shadow.registers[RegId::GGAS]
+                .saturating_sub(self.registers[RegId::GGAS]),
+            registers,
+            memory,
+            receipts,
+            panic,
+        };
+
+        shadow.registers = self.registers;
+        shadow.receipts = self.receipts.len();
+        tracing.index += 1;
+        tracing.tracer.on_instruction(&step);
+
+        self.tracing = Some(tracing);
+    }
+}
+
+/// Ranges of the bytes that differ between `a` and `b`.
+fn changed_ranges(a: &[u8], b: &[u8]) -> Vec<Range<usize>> {
+    const CHUNK: usize = 64;
+
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut offset = 0;
+    for (a, b) in a.chunks(CHUNK).zip(b.chunks(CHUNK)) {
+        if a != b {
+            for (i, _) in a.iter().zip(b).enumerate().filter(|(_, (a, b))| a != b) {
+                let i = offset + i;
+                match ranges.last_mut() {
+                    Some(last) if last.end == i => last.end = i + 1,
+                    _ => ranges.push(i..i + 1),
+                }
+            }
+        }
+        offset += a.len();
+    }
+
+    ranges
+}
+
+#[cfg(feature = "serde")]
+pub(crate) mod hex_bytes {
+    use alloc::{
+        string::String,
+        vec::Vec,
+    };
+    use core::fmt::Write;
+
+    use serde::{
+        de::Error,
+        Deserialize,
+        Deserializer,
+        Serializer,
+    };
+
+    pub fn serialize<S: Serializer>(
+        bytes: &[u8],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut hex = String::with_capacity(2 * bytes.len());
+        for byte in bytes {
+            let _ = write!(hex, "{byte:02x}");
+        }
+
+        serializer.serialize_str(&hex)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        let hex = <&str>::deserialize(deserializer)?;
+        if hex.len() % 2 != 0 {
+            return Err(D::Error::custom("odd number of hex digits"))
+        }
+
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| {
+                hex.get(i..i + 2)
+                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                    .ok_or_else(|| D::Error::custom("invalid hex digit"))
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod instruction_text {
+    use alloc::string::{
+        String,
+        ToString,
+    };
+
+    use fuel_asm::Instruction;
+    use serde::{
+        de::Error,
+        Deserialize,
+        Deserializer,
+        Serialize,
+        Serializer,
+    };
+
+    pub fn serialize<S: Serializer>(
+        instruction: &Option<Instruction>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        instruction
+            .as_ref()
+            .map(ToString::to_string)
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Instruction>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|text| text.parse().map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(feature = "trace-json")]
+mod json {
+    use std::{
+        io::{
+            self,
+            Write,
+        },
+        sync::{
+            Arc,
+            Mutex,
+        },
+    };
+
+    use super::{
+        InstructionStep,
+        InstructionTracer,
+    };
+
+    /// An [`InstructionTracer`] writing each step as a line of JSON.
+    ///
+    /// Clones write to the same writer. Tracing stops at the first write error,
+    /// which can be retrieved with [`Self::take_error`].
+    #[derive(Debug)]
+    pub struct JsonLinesTracer<W> {
+        output: Arc<Mutex<Output<W>>>,
+    }
+
+    #[derive(Debug)]
+    struct Output<W> {
+        writer: W,
+        error: Option<io::Error>,
+    }
+
+    impl<W> Clone for JsonLinesTracer<W> {
+        fn clone(&self) -> Self {
+            Self {
+                output: self.output.clone(),
+            }
+        }
+    }
+
+    impl<W> JsonLinesTracer<W> {
+        /// Write the steps to `writer`.
+        pub fn new(writer: W) -> Self {
+            Self {
+                output: Arc::new(Mutex::new(Output {
+                    writer,
+                    error: None,
+                })),
+            }
+        }
+
+        /// Access the writer, e.g. to read the trace written to a buffer.
+        pub fn with_writer<R>(&self, f: impl FnOnce(&mut W) -> R) -> R {
+            let mut output = self.output.lock().expect("poisoned");
+            f(&mut output.writer)
+        }
+
+        /// Take the write error that stopped the tracing, if any.
+        pub fn take_error(&self) -> Option<io::Error> {
+            self.output.lock().expect("poisoned").error.take()
+        }
+    }
+
+    impl<W: Write> InstructionTracer for JsonLinesTracer<W> {
+        fn on_instruction(&mut self, step: &InstructionStep) {
+            let mut output = self.output.lock().expect("poisoned");
+            let Output { writer, error } = &mut *output;
+            if error.is_some() {
+                return
+            }
+
+            let result = serde_json::to_writer(&mut *writer, step)
+                .map_err(io::Error::from)
+                .and_then(|_| writer.write_all(b"\n"));
+            if let Err(e) = result {
+                error.replace(e);
+            }
+        }
+    }
+}
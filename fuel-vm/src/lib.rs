@@ -15,6 +15,7 @@ extern crate libm as _; // Not needed with stdlib
 
 pub mod arith;
 pub mod backtrace;
+pub mod block_executor;
 pub mod call;
 pub mod checked_transaction;
 pub mod constraints;
@@ -30,16 +31,25 @@ pub mod storage;
 pub mod transactor;
 pub mod util;
 
+#[cfg(feature = "dap")]
+pub mod dap;
+
 #[cfg(feature = "profile-any")]
 pub mod profiler;
 
+#[cfg(feature = "replay")]
+pub mod replay;
+
 #[cfg(test)]
 mod tests;
 
 #[cfg(not(feature = "profile-any"))]
 /// Placeholder
 pub mod profiler {
-    use crate::constraints::InstructionLocation;
+    use crate::{
+        call::CallFrame,
+        constraints::InstructionLocation,
+    };
 
     /// Placeholder profiler.
     #[derive(Default, Debug, Clone)]
@@ -51,6 +61,9 @@ pub mod profiler {
 
         /// Add gas to the current coverage location.
         pub fn add_gas(&mut self, _location: InstructionLocation, _gas_use: u64) {}
+
+        /// Set the call stack the gas is attributed to.
+        pub fn set_call_stack(&mut self, _frames: &[CallFrame]) {}
     }
 }
 
@@ -117,6 +130,7 @@ pub mod prelude {
 
     pub use crate::{
         backtrace::Backtrace,
+        block_executor::BlockExecutor,
         call::{
             Call,
             CallFrame,
@@ -137,14 +151,19 @@ pub mod prelude {
         predicate::RuntimePredicate,
         state::{
             Debugger,
+            DryRun,
             ProgramState,
+            ScriptGasEstimate,
             StateTransition,
             StateTransitionRef,
         },
         storage::{
             InterpreterStorage,
             MemoryStorage,
+            OverlayStorage,
             PredicateStorage,
+            RecordingStorage,
+            VersionedStorage,
         },
         transactor::Transactor,
     };
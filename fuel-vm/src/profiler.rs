@@ -16,10 +16,20 @@ use dyn_clone::DynClone;
 
 use fuel_types::ContractId;
 
+use fuel_asm::RegId;
+
 use crate::prelude::*;
 
 pub use crate::constraints::InstructionLocation;
 
+mod coverage;
+
+pub use coverage::{
+    CoverageReport,
+    SourceLocation,
+    SourceMap,
+};
+
 #[cfg(feature = "serde")]
 impl serde::Serialize for InstructionLocation {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -182,6 +192,8 @@ pub struct Profiler {
     receiver: Option<Box<dyn ProfileReceiver + Send + Sync>>,
     /// Collected profiling data
     data: ProfilingData,
+    /// Calls of the instruction being executed, from the script
+    call_stack: Vec<CallSite>,
 }
 
 impl Profiler {
@@ -220,6 +232,18 @@ impl Profiler {
     /// Add gas to the current coverage location.
     pub fn add_gas(&mut self, location: InstructionLocation, gas_use: u64) {
         self.data_mut().gas_mut().add(location, gas_use);
+        #[cfg(feature = "profile-gas")]
+        self.data.gas.add_call_stack(&self.call_stack, gas_use);
+    }
+
+    /// Set the call stack the gas is attributed to from the call frames of the VM.
+    #[allow(unused_variables)]
+    pub fn set_call_stack(&mut self, frames: &[CallFrame]) {
+        // Every instruction pushes or pops at most one frame
+        #[cfg(feature = "profile-gas")]
+        if frames.len() != self.call_stack.len() {
+            self.call_stack = frames.iter().map(CallSite::from).collect();
+        }
     }
 }
 
@@ -306,11 +330,169 @@ impl fmt::Display for CoverageProfilingData {
     }
 }
 
+/// A call of the call stack: the called contract, and the offset of the `CALL`
+/// instruction from the `$is` of the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallSite {
+    /// The called contract.
+    pub contract: ContractId,
+    /// Offset of the `CALL` instruction in the caller.
+    pub offset: u64,
+}
+
+impl CallSite {
+    /// New call of `contract` from `offset`
+    pub const fn new(contract: ContractId, offset: u64) -> Self {
+        Self { contract, offset }
+    }
+}
+
+impl From<&CallFrame> for CallSite {
+    fn from(frame: &CallFrame) -> Self {
+        // The frame keeps the registers of the caller at the time of the call
+        let registers = frame.registers();
+        let offset = registers[RegId::PC].saturating_sub(registers[RegId::IS]);
+
+        Self::new(*frame.to(), offset)
+    }
+}
+
+impl fmt::Display for CallSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}@{}", self.contract, self.offset)
+    }
+}
+
+/// The calls leading to an instruction, from the script.
+///
+/// Displayed as the semicolon-separated frames of the collapsed stack format, such as
+/// `script;0x1a..@12;0x2b..@40`, where each call is followed by the offset of its
+/// `CALL` instruction in the caller.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CallStack(Vec<CallSite>);
+
+impl CallStack {
+    /// New call stack from the outermost call
+    pub fn new(calls: Vec<CallSite>) -> Self {
+        Self(calls)
+    }
+
+    /// The calls, from the outermost one
+    pub fn calls(&self) -> &[CallSite] {
+        &self.0
+    }
+}
+
+impl core::borrow::Borrow<[CallSite]> for CallStack {
+    fn borrow(&self) -> &[CallSite] {
+        &self.0
+    }
+}
+
+impl fmt::Display for CallStack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "script")?;
+        self.0.iter().try_for_each(|call| write!(f, ";{call}"))
+    }
+}
+
+impl core::str::FromStr for CallStack {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const ERR: &str = "Invalid call stack";
+
+        let mut frames = s.split(';');
+        if frames.next() != Some("script") {
+            return Err(ERR)
+        }
+
+        frames
+            .map(|frame| {
+                let (contract, offset) = frame.split_once('@').ok_or(ERR)?;
+                Ok(CallSite::new(
+                    contract.parse().map_err(|_| ERR)?,
+                    offset.parse().map_err(|_| ERR)?,
+                ))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CallStack {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CallStack {
+    fn deserialize<D>(deserializer: D) -> Result<CallStack, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Gas used by a call and its callees.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallTree {
+    /// The call; `None` for the script.
+    pub call: Option<CallSite>,
+    /// Gas used by the instructions of the call, excluding the callees.
+    pub gas: u64,
+    /// Gas used by the call, including the callees.
+    pub total_gas: u64,
+    /// The calls made, ordered by call offset.
+    pub calls: Vec<CallTree>,
+}
+
+impl CallTree {
+    fn insert(&mut self, calls: &[CallSite], gas: u64) {
+        self.total_gas += gas;
+
+        let Some((call, callees)) = calls.split_first() else {
+            self.gas += gas;
+            return
+        };
+
+        let index = match self.calls.iter().position(|c| c.call == Some(*call)) {
+            Some(index) => index,
+            None => {
+                self.calls.push(CallTree {
+                    call: Some(*call),
+                    ..Default::default()
+                });
+                self.calls.len() - 1
+            }
+        };
+        self.calls[index].insert(callees, gas);
+    }
+
+    fn sort(&mut self) {
+        self.calls
+            .sort_by_key(|c| c.call.map(|call| (call.offset, call.contract)));
+        self.calls.iter_mut().for_each(Self::sort);
+    }
+}
+
 /// Used gas per memory address
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GasProfilingData {
     gas_use: PerLocation<u64>,
+    /// Gas used by the instructions of each call stack, excluding the callees
+    #[cfg_attr(feature = "serde", serde(default))]
+    call_stacks: HashMap<CallStack, u64>,
 }
 
 impl<'a> GasProfilingData {
@@ -339,6 +521,54 @@ impl<'a> GasProfilingData {
     pub fn values(&'a self) -> PerLocationValues<'a, u64> {
         PerLocationValues(self.gas_use.values())
     }
+
+    /// Increase gas used by the instructions of the innermost call of `calls`
+    pub fn add_call_stack(&mut self, calls: &[CallSite], amount: u64) {
+        match self.call_stacks.get_mut(calls) {
+            Some(gas) => *gas += amount,
+            None => {
+                self.call_stacks
+                    .insert(CallStack::new(calls.to_vec()), amount);
+            }
+        }
+    }
+
+    /// Get gas used by the instructions of the innermost call of the stack,
+    /// excluding its callees
+    pub fn call_stack(&self, calls: &[CallSite]) -> u64 {
+        self.call_stacks.get(calls).copied().unwrap_or(0)
+    }
+
+    /// Iterate through call stacks and the gas used by their innermost call,
+    /// excluding its callees
+    pub fn call_stacks(&'a self) -> impl Iterator<Item = (&'a CallStack, u64)> + 'a {
+        self.call_stacks.iter().map(|(stack, gas)| (stack, *gas))
+    }
+
+    /// Export the call stacks in the collapsed stack format of flamegraph tools: a
+    /// line per call stack with its frames and the gas used by its innermost call,
+    /// excluding its callees.
+    pub fn collapsed_stacks(&self) -> String {
+        let mut stacks: Vec<_> = self.call_stacks().collect();
+        stacks.sort();
+
+        stacks
+            .into_iter()
+            .map(|(stack, gas)| format!("{stack} {gas}\n"))
+            .collect()
+    }
+
+    /// Build the tree of the calls, with the gas used by each of them including
+    /// their callees
+    pub fn call_tree(&self) -> CallTree {
+        let mut tree = CallTree::default();
+        for (stack, gas) in self.call_stacks() {
+            tree.insert(stack.calls(), gas);
+        }
+        tree.sort();
+
+        tree
+    }
 }
 
 impl fmt::Display for GasProfilingData {
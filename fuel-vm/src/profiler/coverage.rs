@@ -0,0 +1,277 @@
+//! Export of the coverage data to the lcov and Cobertura formats
+
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{
+        String,
+        ToString,
+    },
+};
+use core::fmt::Write;
+
+use fuel_asm::Instruction;
+use fuel_types::ContractId;
+
+use super::{
+    CoverageProfilingData,
+    InstructionLocation,
+};
+
+/// Location of the source code of an instruction.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceLocation {
+    /// Path of the source file
+    pub file: String,
+    /// Line in the source file, starting from 1
+    pub line: u64,
+}
+
+impl SourceLocation {
+    /// New location from file and line
+    pub fn new(file: impl Into<String>, line: u64) -> Self {
+        Self {
+            file: file.into(),
+            line,
+        }
+    }
+}
+
+/// Map from bytecode offsets to the source code they were compiled from.
+///
+/// Each entry maps the instructions from its offset up to the offset of the next
+/// entry, so compilers only need to provide an entry where the source location
+/// changes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceMap {
+    locations: BTreeMap<u64, SourceLocation>,
+}
+
+impl SourceMap {
+    /// Map the instructions from `offset` to `location`
+    pub fn insert(&mut self, offset: u64, location: SourceLocation) {
+        self.locations.insert(offset, location);
+    }
+
+    /// Source location of the instruction at `offset`, if mapped
+    pub fn get(&self, offset: u64) -> Option<&SourceLocation> {
+        self.locations
+            .range(..=offset)
+            .next_back()
+            .map(|(_, location)| location)
+    }
+
+    /// Iterate through the mapped offsets and their source locations
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &SourceLocation)> {
+        self.locations
+            .iter()
+            .map(|(offset, location)| (*offset, location))
+    }
+}
+
+impl FromIterator<(u64, SourceLocation)> for SourceMap {
+    fn from_iter<T: IntoIterator<Item = (u64, SourceLocation)>>(iter: T) -> Self {
+        Self {
+            locations: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Line coverage aggregated from the coverage of many transactions and contracts.
+///
+/// Every merged [`CoverageProfilingData`] counts as a hit of the lines it executed.
+/// Programs without a source map are reported as a file named after their context
+/// (`script` or the contract id), with a line per instruction.
+#[derive(Debug, Clone, Default)]
+pub struct CoverageReport {
+    /// Hits per executed or known instruction, by context
+    hits: BTreeMap<Option<ContractId>, BTreeMap<u64, u64>>,
+    /// Source maps, by context
+    source_maps: BTreeMap<Option<ContractId>, SourceMap>,
+}
+
+impl CoverageReport {
+    /// Use `source_map` to report the lines of the script, if `context` is `None`,
+    /// or of the contract
+    pub fn set_source_map(&mut self, context: Option<ContractId>, source_map: SourceMap) {
+        self.source_maps.insert(context, source_map);
+    }
+
+    /// Report the instructions of `bytecode` that were never executed as uncovered.
+    ///
+    /// Not needed for programs with a source map, whose mapped lines are reported.
+    pub fn add_bytecode(&mut self, context: Option<ContractId>, bytecode: &[u8]) {
+        let hits = self.hits.entry(context).or_default();
+        (0..bytecode.len() as u64)
+            .step_by(Instruction::SIZE)
+            .for_each(|offset| {
+                hits.entry(offset).or_default();
+            });
+    }
+
+    /// Merge the executed instructions of `coverage`
+    pub fn add(&mut self, coverage: &CoverageProfilingData) {
+        for location in coverage.iter() {
+            *self
+                .hits
+                .entry(location.context())
+                .or_default()
+                .entry(location.offset())
+                .or_default() += 1;
+        }
+    }
+
+    /// Hits of an instruction
+    pub fn get(&self, location: &InstructionLocation) -> u64 {
+        self.hits
+            .get(&location.context())
+            .and_then(|hits| hits.get(&location.offset()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Hits per line, by source file.
+    ///
+    /// A line is hit as many times as the most executed of its instructions.
+    pub fn lines(&self) -> BTreeMap<String, BTreeMap<u64, u64>> {
+        let mut files: BTreeMap<String, BTreeMap<u64, u64>> = BTreeMap::new();
+
+        for (context, source_map) in &self.source_maps {
+            for (_, location) in source_map.iter() {
+                files
+                    .entry(location.file.clone())
+                    .or_default()
+                    .entry(location.line)
+                    .or_default();
+            }
+
+            let hits = self.hits.get(context).into_iter().flatten();
+            for (offset, count) in hits {
+                if let Some(location) = source_map.get(*offset) {
+                    let line = files
+                        .entry(location.file.clone())
+                        .or_default()
+                        .entry(location.line)
+                        .or_default();
+                    *line = (*line).max(*count);
+                }
+            }
+        }
+
+        let unmapped = self
+            .hits
+            .iter()
+            .filter(|(context, _)| !self.source_maps.contains_key(*context));
+        for (context, hits) in unmapped {
+            let file = files.entry(context_name(*context)).or_default();
+            for (offset, count) in hits {
+                file.insert(offset / Instruction::SIZE as u64 + 1, *count);
+            }
+        }
+
+        files
+    }
+
+    /// Export the report in the lcov tracefile format
+    pub fn to_lcov(&self) -> String {
+        let mut out = String::new();
+
+        for (file, lines) in self.lines() {
+            let _ = writeln!(out, "TN:");
+            let _ = writeln!(out, "SF:{file}");
+            for (line, hits) in &lines {
+                let _ = writeln!(out, "DA:{line},{hits}");
+            }
+            let _ = writeln!(out, "LF:{}", lines.len());
+            let _ = writeln!(out, "LH:{}", covered(&lines));
+            let _ = writeln!(out, "end_of_record");
+        }
+
+        out
+    }
+
+    /// Export the report in the Cobertura XML format
+    pub fn to_cobertura(&self) -> String {
+        let files = self.lines();
+        let valid: usize = files.values().map(|lines| lines.len()).sum();
+        let hit: usize = files.values().map(covered).sum();
+
+        let mut out = String::new();
+        let _ = writeln!(out, r#"<?xml version="1.0" ?>"#);
+        let _ = writeln!(
+            out,
+            r#"<coverage line-rate="{}" branch-rate="0" lines-covered="{hit}" lines-valid="{valid}" branches-covered="0" branches-valid="0" complexity="0" version="{}" timestamp="0">"#,
+            rate(hit, valid),
+            env!("CARGO_PKG_VERSION"),
+        );
+        let _ = writeln!(out, "  <sources>");
+        let _ = writeln!(out, "    <source>.</source>");
+        let _ = writeln!(out, "  </sources>");
+        let _ = writeln!(out, "  <packages>");
+        let _ = writeln!(
+            out,
+            r#"    <package name="fuel-vm" line-rate="{}" branch-rate="0" complexity="0">"#,
+            rate(hit, valid),
+        );
+        let _ = writeln!(out, "      <classes>");
+        for (file, lines) in &files {
+            let file = escape_xml(file);
+            let _ = writeln!(
+                out,
+                r#"        <class name="{file}" filename="{file}" line-rate="{}" branch-rate="0" complexity="0">"#,
+                rate(covered(lines), lines.len()),
+            );
+            let _ = writeln!(out, "          <methods/>");
+            let _ = writeln!(out, "          <lines>");
+            for (line, hits) in lines {
+                let _ = writeln!(
+                    out,
+                    r#"            <line number="{line}" hits="{hits}" branch="false"/>"#
+                );
+            }
+            let _ = writeln!(out, "          </lines>");
+            let _ = writeln!(out, "        </class>");
+        }
+        let _ = writeln!(out, "      </classes>");
+        let _ = writeln!(out, "    </package>");
+        let _ = writeln!(out, "  </packages>");
+        let _ = writeln!(out, "</coverage>");
+
+        out
+    }
+}
+
+/// Name of the file of a program without source map
+fn context_name(context: Option<ContractId>) -> String {
+    context
+        .map(|contract_id| format!("{contract_id:#x}"))
+        .unwrap_or_else(|| "script".to_string())
+}
+
+fn covered(lines: &BTreeMap<u64, u64>) -> usize {
+    lines.values().filter(|hits| **hits > 0).count()
+}
+
+fn rate(hit: usize, valid: usize) -> String {
+    if valid == 0 {
+        return "1".to_string()
+    }
+
+    format!("{:.4}", hit as f64 / valid as f64)
+}
+
+fn escape_xml(s: &str) -> String {
+    s.chars().fold(String::new(), |mut out, c| {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+        out
+    })
+}
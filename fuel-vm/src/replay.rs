@@ -0,0 +1,394 @@
+//! # Transaction replay
+//! Reproduction of the execution of a transaction outside of the node that executed
+//! it. A [`Snapshot`] holds the transaction, the consensus parameters, the block
+//! context, and the code, state slots and balances of the contracts the transaction
+//! accesses, as they were before its execution. It is serialized to be attached to a
+//! bug report, and loaded into a [`MemoryStorage`] to execute the transaction again.
+//!
+//! Only the accessed state slots and balances are captured, so the state and balance
+//! roots of the contracts after a replay differ from the ones of the original
+//! execution.
+
+use alloc::{
+    boxed::Box,
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    vec::Vec,
+};
+
+use fuel_tx::{
+    CheckError,
+    ConsensusParameters,
+    Contract,
+    Executable,
+    Transaction,
+};
+use fuel_types::{
+    AssetId,
+    BlockHeight,
+    Bytes32,
+    ContractId,
+    Salt,
+    Word,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    checked_transaction::{
+        CheckPredicates,
+        Checked,
+        IntoChecked,
+    },
+    error::InterpreterError,
+    interpreter::{
+        storage_access::{
+            StorageAccess,
+            StorageAccessTracer,
+            Traced,
+        },
+        trace::{
+            hex_bytes,
+            InstructionTracer,
+        },
+        CheckedMetadata,
+        ExecutableTransaction,
+        Interpreter,
+        InterpreterParams,
+    },
+    state::StateTransition,
+    storage::{
+        ContractsAssetKey,
+        ContractsAssetsStorage,
+        ContractsStateKey,
+        InterpreterStorage,
+        MemoryStorage,
+        OverlayStorage,
+    },
+};
+
+/// Replay error variants.
+#[derive(Debug, derive_more::Display)]
+pub enum ReplayError<StorageError> {
+    /// The transaction couldn't be checked or executed.
+    #[display(fmt = "{}", _0)]
+    Interpreter(InterpreterError<StorageError>),
+    /// `Mint` transactions aren't executed by the VM.
+    #[display(fmt = "`Mint` transactions can't be replayed")]
+    UnexpectedMint,
+}
+
+impl<StorageError> From<InterpreterError<StorageError>> for ReplayError<StorageError> {
+    fn from(error: InterpreterError<StorageError>) -> Self {
+        Self::Interpreter(error)
+    }
+}
+
+impl<StorageError> From<CheckError> for ReplayError<StorageError> {
+    fn from(error: CheckError) -> Self {
+        Self::Interpreter(error.into())
+    }
+}
+
+/// The timestamp and hash of a block read by the transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BlockSnapshot {
+    /// The height of the block.
+    pub height: BlockHeight,
+    /// The TAI64 timestamp of the block.
+    pub timestamp: Word,
+    /// The hash of the block.
+    pub hash: Bytes32,
+}
+
+/// A deployed contract, with the state slots and balances read or written by the
+/// transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractSnapshot {
+    /// The salt the contract was deployed with.
+    pub salt: Salt,
+    /// The bytecode of the contract.
+    #[serde(with = "hex_bytes")]
+    pub code: Vec<u8>,
+    /// The set state slots.
+    #[serde(default)]
+    pub state: BTreeMap<Bytes32, Bytes32>,
+    /// The set balances.
+    #[serde(default)]
+    pub balances: BTreeMap<AssetId, Word>,
+}
+
+/// A transaction along with everything needed to execute it again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The parameters of the chain.
+    pub consensus_parameters: ConsensusParameters,
+    /// The height of the block the transaction is executed in.
+    pub block_height: BlockHeight,
+    /// The coinbase of the block the transaction is executed in.
+    pub coinbase: ContractId,
+    /// The blocks whose timestamp or hash was read by the transaction.
+    #[serde(default)]
+    pub blocks: Vec<BlockSnapshot>,
+    /// The contracts accessed by the transaction, before its execution.
+    #[serde(default)]
+    pub contracts: BTreeMap<ContractId, ContractSnapshot>,
+    /// The transaction to execute.
+    pub transaction: Transaction,
+}
+
+impl Snapshot {
+    /// Execute `transaction` on top of `storage` to capture the entries it accesses.
+    ///
+    /// The writes of the execution are kept in memory, so `storage` is left untouched.
+    /// The transaction has to pass all the checks, including the signatures and
+    /// predicates ones.
+    pub fn capture<S>(
+        storage: &S,
+        transaction: Transaction,
+        consensus_parameters: ConsensusParameters,
+    ) -> Result<Self, ReplayError<S::DataError>>
+    where
+        S: InterpreterStorage,
+    {
+        let storage_error =
+            |error| ReplayError::Interpreter(InterpreterError::Storage(error));
+
+        let mut accessed = AccessedKeys::default();
+        accessed.contracts.extend(match &transaction {
+            Transaction::Script(script) => script.input_contracts(),
+            Transaction::Create(create) => create.input_contracts(),
+            Transaction::Mint(_) => Vec::new().into_iter(),
+        });
+        execute(
+            Traced::new(OverlayStorage::new(storage), &mut accessed),
+            transaction.clone(),
+            &consensus_parameters,
+            None,
+        )?;
+
+        let blocks = accessed
+            .blocks
+            .iter()
+            .map(|&height| {
+                Ok(BlockSnapshot {
+                    height,
+                    timestamp: storage.timestamp(height)?,
+                    hash: storage.block_hash(height)?,
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map_err(storage_error)?;
+
+        let mut contracts = BTreeMap::new();
+        for id in &accessed.contracts {
+            let Some(code) = storage.storage_contract(id).map_err(storage_error)? else {
+                continue
+            };
+            let salt = storage
+                .storage_contract_root(id)
+                .map_err(storage_error)?
+                .map(|info| info.0)
+                .unwrap_or_default();
+            let contract = ContractSnapshot {
+                salt,
+                code: code.into_owned().into(),
+                ..Default::default()
+            };
+            contracts.insert(*id, contract);
+        }
+
+        for key in &accessed.state {
+            let (Some(contract), Some(value)) = (
+                contracts.get_mut(key.contract_id()),
+                storage
+                    .merkle_contract_state(key.contract_id(), key.state_key())
+                    .map_err(storage_error)?,
+            ) else {
+                continue
+            };
+            contract.state.insert(*key.state_key(), value.into_owned());
+        }
+
+        for key in &accessed.balances {
+            let (Some(contract), Some(balance)) = (
+                contracts.get_mut(key.contract_id()),
+                storage
+                    .merkle_contract_asset_id_balance(key.contract_id(), key.asset_id())
+                    .map_err(storage_error)?,
+            ) else {
+                continue
+            };
+            contract.balances.insert(*key.asset_id(), balance);
+        }
+
+        Ok(Self {
+            consensus_parameters,
+            block_height: storage.block_height().map_err(storage_error)?,
+            coinbase: storage.coinbase().map_err(storage_error)?,
+            blocks,
+            contracts,
+            transaction,
+        })
+    }
+
+    /// Load the captured entries and block context into a new [`MemoryStorage`].
+    pub fn storage(&self) -> MemoryStorage {
+        let mut storage = MemoryStorage::new(self.block_height, self.coinbase);
+
+        for block in &self.blocks {
+            storage.set_timestamp(block.height, block.timestamp);
+            storage.set_block_hash(block.height, block.hash);
+        }
+
+        for (id, snapshot) in &self.contracts {
+            let contract = Contract::from(snapshot.code.as_slice());
+            storage
+                .deploy_contract_with_id(
+                    &snapshot.salt,
+                    &[],
+                    &contract,
+                    &contract.root(),
+                    id,
+                )
+                .expect("Infallible");
+
+            for (key, value) in &snapshot.state {
+                storage
+                    .merkle_contract_state_insert(id, key, value)
+                    .expect("Infallible");
+            }
+
+            for (asset_id, balance) in &snapshot.balances {
+                storage
+                    .merkle_contract_asset_id_balance_insert(id, asset_id, *balance)
+                    .expect("Infallible");
+            }
+        }
+
+        storage
+    }
+
+    /// Execute the transaction on top of the captured entries.
+    pub fn replay(
+        &self,
+    ) -> Result<StateTransition<Transaction>, ReplayError<core::convert::Infallible>>
+    {
+        execute(
+            self.storage(),
+            self.transaction.clone(),
+            &self.consensus_parameters,
+            None,
+        )
+    }
+
+    /// Execute the transaction on top of the captured entries, tracing every executed
+    /// instruction with `tracer`.
+    pub fn replay_traced<T>(
+        &self,
+        tracer: T,
+    ) -> Result<StateTransition<Transaction>, ReplayError<core::convert::Infallible>>
+    where
+        T: InstructionTracer + Send + Sync + 'static,
+    {
+        execute(
+            self.storage(),
+            self.transaction.clone(),
+            &self.consensus_parameters,
+            Some(Box::new(tracer)),
+        )
+    }
+}
+
+/// The storage entries accessed by the transaction. Written entries are included, as
+/// their value before the write is part of the pre-state.
+#[derive(Debug, Default)]
+struct AccessedKeys {
+    contracts: BTreeSet<ContractId>,
+    state: BTreeSet<ContractsStateKey>,
+    balances: BTreeSet<ContractsAssetKey>,
+    blocks: BTreeSet<BlockHeight>,
+}
+
+impl StorageAccessTracer for AccessedKeys {
+    fn contract_code(&mut self, access: StorageAccess<ContractId, Contract>) {
+        self.contracts.insert(access.key);
+    }
+
+    fn contract_code_read(
+        &mut self,
+        key: ContractId,
+        _code: &mut dyn FnMut() -> Option<Contract>,
+    ) {
+        self.contracts.insert(key);
+    }
+
+    fn contract_state(&mut self, access: StorageAccess<ContractsStateKey, Bytes32>) {
+        self.contracts.insert(*access.key.contract_id());
+        self.state.insert(access.key);
+    }
+
+    fn contract_balance(&mut self, access: StorageAccess<ContractsAssetKey, Word>) {
+        self.contracts.insert(*access.key.contract_id());
+        self.balances.insert(access.key);
+    }
+
+    fn block(&mut self, height: BlockHeight) {
+        self.blocks.insert(height);
+    }
+}
+
+fn execute<S>(
+    storage: S,
+    transaction: Transaction,
+    consensus_parameters: &ConsensusParameters,
+    tracer: Option<Box<dyn InstructionTracer + Send + Sync>>,
+) -> Result<StateTransition<Transaction>, ReplayError<S::DataError>>
+where
+    S: InterpreterStorage,
+{
+    match transaction {
+        Transaction::Script(script) => {
+            transact(storage, script, consensus_parameters, tracer)
+        }
+        Transaction::Create(create) => {
+            transact(storage, create, consensus_parameters, tracer)
+        }
+        Transaction::Mint(_) => Err(ReplayError::UnexpectedMint),
+    }
+}
+
+fn transact<S, Tx>(
+    storage: S,
+    transaction: Tx,
+    consensus_parameters: &ConsensusParameters,
+    tracer: Option<Box<dyn InstructionTracer + Send + Sync>>,
+) -> Result<StateTransition<Transaction>, ReplayError<S::DataError>>
+where
+    S: InterpreterStorage,
+    Tx: ExecutableTransaction + Into<Transaction>,
+    <Tx as IntoChecked>::Metadata: CheckedMetadata,
+    Checked<Tx>: CheckPredicates,
+{
+    let block_height = storage
+        .block_height()
+        .map_err(|error| ReplayError::Interpreter(InterpreterError::Storage(error)))?;
+    let checked = transaction.into_checked(block_height, consensus_parameters)?;
+
+    let mut interpreter =
+        Interpreter::with_storage(storage, InterpreterParams::from(consensus_parameters));
+    if let Some(tracer) = tracer {
+        interpreter.set_instruction_tracer(tracer);
+    }
+
+    let transition = interpreter.transact(checked)?;
+    Ok(StateTransition::new(
+        *transition.state(),
+        transition.tx().clone().into(),
+        transition.receipts().to_vec(),
+    ))
+}
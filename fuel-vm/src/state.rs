@@ -3,6 +3,8 @@
 use alloc::vec::Vec;
 
 use fuel_tx::Receipt;
+
+use crate::storage::StorageDiff;
 use fuel_types::{
     Bytes32,
     Word,
@@ -14,7 +16,10 @@ mod debugger;
 
 pub use debug::{
     Breakpoint,
+    Comparison,
     DebugEval,
+    RegisterCondition,
+    Trigger,
 };
 
 pub use debugger::Debugger;
@@ -76,8 +81,8 @@ pub enum ProgramState {
 impl PartialEq<Breakpoint> for ProgramState {
     fn eq(&self, other: &Breakpoint) -> bool {
         match self.debug_ref() {
-            Some(&DebugEval::Breakpoint(b)) => &b == other,
-            _ => false,
+            Some(debug) => debug.location() == Some(other),
+            None => false,
         }
     }
 }
@@ -218,3 +223,97 @@ impl<'a, Tx: Clone> From<StateTransitionRef<'a, Tx>> for ProgramState {
         t.state
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Representation of the result of a transaction execution that wasn't applied to
+/// the storage.
+pub struct DryRun<Tx> {
+    transition: StateTransition<Tx>,
+    changes: StorageDiff,
+}
+
+impl<Tx> DryRun<Tx> {
+    /// Create a new dry run representation.
+    pub const fn new(transition: StateTransition<Tx>, changes: StorageDiff) -> Self {
+        Self {
+            transition,
+            changes,
+        }
+    }
+
+    /// State transition of the execution.
+    pub const fn state_transition(&self) -> &StateTransition<Tx> {
+        &self.transition
+    }
+
+    /// Changes the execution would have made to the storage.
+    pub const fn storage_changes(&self) -> &StorageDiff {
+        &self.changes
+    }
+
+    /// Convert this instance into its internal attributes.
+    pub fn into_inner(self) -> (StateTransition<Tx>, StorageDiff) {
+        (self.transition, self.changes)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Gas estimation of a script transaction, from a dry run with the maximum gas.
+pub struct ScriptGasEstimate {
+    gas_used: Word,
+    gas_limit: Word,
+    max_fee: Word,
+    state: ProgramState,
+    receipts: Vec<Receipt>,
+}
+
+impl ScriptGasEstimate {
+    /// Create a new gas estimation representation.
+    pub const fn new(
+        gas_used: Word,
+        gas_limit: Word,
+        max_fee: Word,
+        state: ProgramState,
+        receipts: Vec<Receipt>,
+    ) -> Self {
+        Self {
+            gas_used,
+            gas_limit,
+            max_fee,
+            state,
+            receipts,
+        }
+    }
+
+    /// Gas used by the dry run.
+    pub const fn gas_used(&self) -> Word {
+        self.gas_used
+    }
+
+    /// Gas limit covering the gas used, with a safety margin.
+    pub const fn gas_limit(&self) -> Word {
+        self.gas_limit
+    }
+
+    /// Max fee of the transaction with the estimated gas limit, at its gas price.
+    pub const fn max_fee(&self) -> Word {
+        self.max_fee
+    }
+
+    /// Program state of the dry run.
+    pub const fn state(&self) -> &ProgramState {
+        &self.state
+    }
+
+    /// Receipts of the dry run.
+    pub fn receipts(&self) -> &[Receipt] {
+        self.receipts.as_slice()
+    }
+
+    /// Flag whether the dry run reverted, so the estimation may not hold.
+    pub fn should_revert(&self) -> bool {
+        self.receipts
+            .iter()
+            .any(|r| matches!(r, Receipt::Revert { .. } | Receipt::Panic { .. }))
+    }
+}
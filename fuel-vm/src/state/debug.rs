@@ -1,5 +1,12 @@
-use fuel_asm::Instruction;
+use fuel_asm::{
+    Instruction,
+    Opcode,
+    PanicReason,
+    RegId,
+};
+use fuel_tx::ReceiptRepr;
 use fuel_types::{
+    Bytes32,
     ContractId,
     Word,
 };
@@ -54,6 +61,110 @@ impl Breakpoint {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Comparison operator of a [`RegisterCondition`].
+pub enum Comparison {
+    /// `$rX == value`
+    Eq,
+    /// `$rX != value`
+    Ne,
+    /// `$rX < value`
+    Lt,
+    /// `$rX <= value`
+    Le,
+    /// `$rX > value`
+    Gt,
+    /// `$rX >= value`
+    Ge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Predicate over the value of a register, such as `$r10 == 0`.
+///
+/// A breakpoint set with a condition will only break the program if the
+/// condition holds when the location is reached.
+pub struct RegisterCondition {
+    register: u8,
+    comparison: Comparison,
+    value: Word,
+}
+
+impl RegisterCondition {
+    /// Create a new condition comparing `register` against `value`.
+    pub const fn new(register: RegId, comparison: Comparison, value: Word) -> Self {
+        Self {
+            register: register.to_u8(),
+            comparison,
+            value,
+        }
+    }
+
+    /// Register that is compared.
+    pub const fn register(&self) -> RegId {
+        RegId::new(self.register)
+    }
+
+    /// Comparison operator of the condition.
+    pub const fn comparison(&self) -> Comparison {
+        self.comparison
+    }
+
+    /// Value the register is compared against.
+    pub const fn value(&self) -> Word {
+        self.value
+    }
+
+    /// Evaluate the condition with the provided register set.
+    pub fn holds(&self, registers: &[Word]) -> bool {
+        let Some(&register) = registers.get(self.register as usize) else {
+            return false
+        };
+
+        match self.comparison {
+            Comparison::Eq => register == self.value,
+            Comparison::Ne => register != self.value,
+            Comparison::Lt => register < self.value,
+            Comparison::Le => register <= self.value,
+            Comparison::Gt => register > self.value,
+            Comparison::Ge => register >= self.value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Event, other than reaching a [`Breakpoint`], that suspends the program.
+///
+/// Changes of memory, storage and receipts are detected before the execution of
+/// the instruction following the one that caused them.
+pub enum Trigger {
+    /// The memory range `[start, start + len)` changed.
+    Memory {
+        /// Start address of the watched range.
+        start: Word,
+        /// Length of the watched range.
+        len: Word,
+    },
+    /// The storage slot `key` of `contract` changed.
+    Storage {
+        /// Contract owning the watched slot.
+        contract: ContractId,
+        /// Key of the watched slot.
+        key: Bytes32,
+    },
+    /// The next instruction has the watched opcode.
+    Opcode(Opcode),
+    /// A receipt of the watched kind was produced.
+    Receipt(ReceiptRepr),
+    /// The current instruction panicked with the watched reason.
+    ///
+    /// The panic isn't processed yet. Resuming the program processes the recorded
+    /// panic as usual, without executing or charging the instruction again.
+    Panic(PanicReason),
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// State evaluation of the interpreter that will describe if a program should
@@ -62,6 +173,9 @@ pub enum DebugEval {
     /// This evaluation should break the program in the location described in
     /// `Breakpoint`.
     Breakpoint(Breakpoint),
+    /// This evaluation should break the program in the location described in
+    /// `Breakpoint` because of the [`Trigger`].
+    Trigger(Breakpoint, Trigger),
     /// This evaluation should not break the program.
     Continue,
 }
@@ -92,4 +206,21 @@ impl DebugEval {
             _ => None,
         }
     }
+
+    /// Return the location where the program is suspended, regardless of the
+    /// cause; return `None` if the program should continue.
+    pub const fn location(&self) -> Option<&Breakpoint> {
+        match self {
+            Self::Breakpoint(b) | Self::Trigger(b, _) => Some(b),
+            Self::Continue => None,
+        }
+    }
+
+    /// Return the event that suspended the program, if it wasn't a breakpoint.
+    pub const fn trigger(&self) -> Option<&Trigger> {
+        match self {
+            Self::Trigger(_, t) => Some(t),
+            _ => None,
+        }
+    }
 }
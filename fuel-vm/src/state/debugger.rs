@@ -2,13 +2,27 @@ use crate::state::{
     Breakpoint,
     DebugEval,
     ProgramState,
+    RegisterCondition,
 };
 
+use fuel_asm::{
+    Opcode,
+    PanicReason,
+};
+use fuel_tx::{
+    Receipt,
+    ReceiptRepr,
+};
 use fuel_types::{
+    Bytes32,
     ContractId,
     Word,
 };
 
+use alloc::{
+    collections::BTreeMap,
+    vec::Vec,
+};
 use hashbrown::{
     HashMap,
     HashSet,
@@ -22,6 +36,20 @@ pub struct Debugger {
     /// Single-stepping mode triggers a breakpoint after each instruction
     single_stepping: bool,
     breakpoints: HashMap<ContractId, HashSet<Word>>,
+    conditions: HashMap<Breakpoint, RegisterCondition>,
+    /// Watched memory ranges with the last observed content, ordered to report
+    /// simultaneous changes deterministically.
+    memory_watchpoints: BTreeMap<(Word, Word), Option<Vec<u8>>>,
+    /// Watched storage slots with the last observed value, ordered to report
+    /// simultaneous changes deterministically.
+    storage_watchpoints: BTreeMap<(ContractId, Bytes32), Option<Option<Bytes32>>>,
+    opcodes: HashSet<Opcode>,
+    receipts: HashSet<ReceiptRepr>,
+    /// Number of receipts already checked against the watched kinds.
+    receipts_seen: usize,
+    panics: HashSet<PanicReason>,
+    /// Panic reported in a location, processed when resuming from it.
+    reported_panic: Option<(Breakpoint, PanicReason)>,
     last_state: Option<ProgramState>,
 }
 
@@ -48,6 +76,8 @@ impl Debugger {
         let contract = *breakpoint.contract();
         let pc = breakpoint.pc();
 
+        self.conditions.remove(&breakpoint);
+
         self.breakpoints
             .get_mut(&contract)
             .map(|set| set.insert(pc))
@@ -61,12 +91,188 @@ impl Debugger {
             });
     }
 
+    /// Set a new breakpoint in the provided location that breaks only if the
+    /// condition holds.
+    pub fn set_conditional_breakpoint(
+        &mut self,
+        breakpoint: Breakpoint,
+        condition: RegisterCondition,
+    ) {
+        self.set_breakpoint(breakpoint);
+        self.conditions.insert(breakpoint, condition);
+    }
+
     /// Remove a breakpoint, if existent.
     pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint) {
         self.is_active = true;
         self.breakpoints
             .get_mut(breakpoint.contract())
             .map(|set| set.remove(&breakpoint.pc()));
+        self.conditions.remove(breakpoint);
+    }
+
+    /// Condition of the breakpoint in the provided location, if any.
+    pub fn breakpoint_condition(
+        &self,
+        breakpoint: &Breakpoint,
+    ) -> Option<&RegisterCondition> {
+        self.conditions.get(breakpoint)
+    }
+
+    /// Break when the content of the memory range `[start, start + len)` changes.
+    pub fn watch_memory(&mut self, start: Word, len: Word) {
+        self.is_active = true;
+        self.memory_watchpoints.insert((start, len), None);
+    }
+
+    /// Stop watching a memory range.
+    pub fn unwatch_memory(&mut self, start: Word, len: Word) {
+        self.memory_watchpoints.remove(&(start, len));
+    }
+
+    /// Break when the value of the storage slot `key` of `contract` changes.
+    pub fn watch_storage(&mut self, contract: ContractId, key: Bytes32) {
+        self.is_active = true;
+        self.storage_watchpoints.insert((contract, key), None);
+    }
+
+    /// Stop watching a storage slot.
+    pub fn unwatch_storage(&mut self, contract: &ContractId, key: &Bytes32) {
+        self.storage_watchpoints.remove(&(*contract, *key));
+    }
+
+    /// Break before the execution of any instruction with the provided opcode.
+    pub fn break_on_opcode(&mut self, opcode: Opcode) {
+        self.is_active = true;
+        self.opcodes.insert(opcode);
+    }
+
+    /// Stop breaking on an opcode.
+    pub fn remove_opcode_break(&mut self, opcode: Opcode) {
+        self.opcodes.remove(&opcode);
+    }
+
+    /// Break after a receipt of the provided kind is produced.
+    pub fn break_on_receipt(&mut self, receipt: ReceiptRepr) {
+        self.is_active = true;
+        self.receipts.insert(receipt);
+    }
+
+    /// Stop breaking on a receipt kind.
+    pub fn remove_receipt_break(&mut self, receipt: ReceiptRepr) {
+        self.receipts.remove(&receipt);
+    }
+
+    /// Break when an instruction panics with the provided reason.
+    pub fn break_on_panic(&mut self, reason: PanicReason) {
+        self.is_active = true;
+        self.panics.insert(reason);
+    }
+
+    /// Stop breaking on a panic reason.
+    pub fn remove_panic_break(&mut self, reason: PanicReason) {
+        self.panics.remove(&reason);
+    }
+
+    pub(crate) fn breaks_on_opcode(&self, opcode: Opcode) -> bool {
+        self.opcodes.contains(&opcode)
+    }
+
+    pub(crate) fn breaks_on_panic(&self, reason: PanicReason) -> bool {
+        self.panics.contains(&reason)
+    }
+
+    /// Remember the panic reported in the location, to be processed when resuming
+    /// instead of executing the instruction again.
+    pub(crate) fn report_panic(&mut self, location: Breakpoint, reason: PanicReason) {
+        self.reported_panic = Some((location, reason));
+    }
+
+    /// Take the panic reported in the location, if any.
+    pub(crate) fn take_reported_panic(
+        &mut self,
+        location: &Breakpoint,
+    ) -> Option<PanicReason> {
+        self.reported_panic
+            .take()
+            .filter(|(reported, _)| reported == location)
+            .map(|(_, reason)| reason)
+    }
+
+    /// Return the first receipt kind to break on among the receipts produced
+    /// since the last call.
+    pub(crate) fn eval_receipts(&mut self, receipts: &[Receipt]) -> Option<ReceiptRepr> {
+        // The receipts are reset for every transaction, and removed when stepping
+        // backwards
+        let seen = self.receipts_seen.min(receipts.len());
+        self.receipts_seen = receipts.len();
+
+        receipts[seen..]
+            .iter()
+            .map(ReceiptRepr::from)
+            .find(|receipt| self.receipts.contains(receipt))
+    }
+
+    /// Compare the watched memory ranges with `memory`, returning the first one
+    /// that changed in the order of the ranges. The observed content is updated.
+    pub(crate) fn eval_memory(&mut self, memory: &[u8]) -> Option<(Word, Word)> {
+        let mut changed = None;
+
+        for (&(start, len), snapshot) in self.memory_watchpoints.iter_mut() {
+            let content = usize::try_from(start)
+                .ok()
+                .zip(usize::try_from(len).ok())
+                .and_then(|(start, len)| memory.get(start..start.checked_add(len)?));
+
+            if let Some(content) = content {
+                if let Some(previous) = snapshot {
+                    if previous.as_slice() != content {
+                        previous.copy_from_slice(content);
+                        changed = changed.or(Some((start, len)));
+                    }
+                } else {
+                    snapshot.replace(content.to_vec());
+                }
+            }
+        }
+
+        changed
+    }
+
+    /// Compare the watched storage slots using `read`, returning the first one
+    /// that changed in the order of the slots. The observed value is updated.
+    pub(crate) fn eval_storage<E, F>(
+        &mut self,
+        mut read: F,
+    ) -> Result<Option<(ContractId, Bytes32)>, E>
+    where
+        F: FnMut(&ContractId, &Bytes32) -> Result<Option<Bytes32>, E>,
+    {
+        let mut changed = None;
+
+        for (&(contract, key), snapshot) in self.storage_watchpoints.iter_mut() {
+            let value = read(&contract, &key)?;
+
+            match snapshot {
+                Some(previous) if *previous != value => {
+                    *previous = value;
+                    changed = changed.or(Some((contract, key)));
+                }
+                Some(_) => (),
+                None => {
+                    snapshot.replace(value);
+                }
+            }
+        }
+
+        Ok(changed)
+    }
+
+    /// Update the observed memory content and receipts without reporting
+    /// changes; used when the VM is suspended.
+    pub(crate) fn sync(&mut self, memory: &[u8], receipts: usize) {
+        self.eval_memory(memory);
+        self.receipts_seen = receipts;
     }
 
     /// Evaluate the current state of the interpreter whether or not a
@@ -12,14 +12,28 @@ use fuel_types::{
 
 mod interpreter;
 mod memory;
+mod overlay;
 pub(crate) mod predicate;
+mod recording;
+mod versioned;
 
 pub use interpreter::{
     ContractsAssetsStorage,
     InterpreterStorage,
 };
-pub use memory::MemoryStorage;
+pub(crate) use memory::state_keys;
+pub use memory::{
+    BlockHeader,
+    MemoryStorage,
+};
+pub use overlay::OverlayStorage;
 pub use predicate::PredicateStorage;
+pub use recording::{
+    RecordingStorage,
+    StorageChange,
+    StorageDiff,
+};
+pub use versioned::VersionedStorage;
 
 /// The storage table for contract's raw byte code.
 pub struct ContractsRawCode;
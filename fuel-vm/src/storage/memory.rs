@@ -37,9 +37,13 @@ use tai64::Tai64;
 use alloc::{
     borrow::Cow,
     collections::BTreeMap,
+    string::String,
     vec::Vec,
 };
-use core::convert::Infallible;
+use core::{
+    convert::Infallible,
+    mem,
+};
 
 use super::interpreter::ContractsAssetsStorage;
 
@@ -51,6 +55,66 @@ struct MemoryStorageInner {
     contract_code_root: BTreeMap<ContractId, (Salt, Bytes32)>,
 }
 
+/// The header data of a block exposed to the interpreter through the `TIME`, `BHSH`
+/// and `CB` instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlockHeader {
+    /// The height of the block.
+    pub height: BlockHeight,
+    /// The TAI64 timestamp of the block.
+    pub timestamp: Word,
+    /// The hash of the block.
+    pub hash: Bytes32,
+    /// The coinbase contract of the block.
+    pub coinbase: ContractId,
+}
+
+/// Explicitly set header fields of a block. Unset fields fall back to the values
+/// derived from the block height.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct BlockContext {
+    timestamp: Option<Word>,
+    hash: Option<Bytes32>,
+    coinbase: Option<ContractId>,
+}
+
+#[derive(Debug, Clone)]
+/// A named position in the undo log of a [`MemoryStorage`].
+struct Savepoint {
+    name: String,
+    block_height: BlockHeight,
+    undo_len: usize,
+}
+
+#[derive(Debug, Clone)]
+/// A change to a [`MemoryStorage`], holding what undoing it restores.
+enum Undo {
+    Contract(ContractId, Option<Contract>),
+    ContractInfo(ContractId, Option<(Salt, Bytes32)>),
+    Balance(ContractsAssetKey, Option<Word>),
+    State(ContractsStateKey, Option<Bytes32>),
+    Memory(MemoryStorageInner),
+    Transacted(MemoryStorageInner),
+    Persisted(MemoryStorageInner),
+    Block(BlockHeight, Option<BlockContext>),
+}
+
+#[derive(Debug, Default, Clone)]
+/// The savepoints of a [`MemoryStorage`], and the changes made since the oldest one.
+struct Journal {
+    savepoints: Vec<Savepoint>,
+    undo_log: Vec<Undo>,
+}
+
+impl Journal {
+    /// Record a change. Nothing is recorded while there are no savepoints.
+    fn record(&mut self, undo: impl FnOnce() -> Undo) {
+        if !self.savepoints.is_empty() {
+            self.undo_log.push(undo());
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 /// In-memory storage implementation for the interpreter.
 ///
@@ -59,12 +123,23 @@ struct MemoryStorageInner {
 /// - memory: the transactions will be applied to this state.
 /// - transacted: will receive the committed `memory` state.
 /// - persisted: will receive the persisted `transacted` state.
+///
+/// On top of these, it keeps a stack of named savepoints. While there are savepoints,
+/// every change records the value it overwrites in an undo log, so pushing a
+/// savepoint doesn't copy the state. Rolling back to a savepoint undoes the changes
+/// made since, including the block height and the headers of the blocks.
+///
+/// The timestamp, hash and coinbase of each block can be set explicitly. Otherwise,
+/// the timestamp of a block is `UNIX_EPOCH + height * 10`, its hash is the hash of
+/// its height, and its coinbase is the one the storage was created with.
 pub struct MemoryStorage {
     block_height: BlockHeight,
     coinbase: ContractId,
+    blocks: BTreeMap<BlockHeight, BlockContext>,
     memory: MemoryStorageInner,
     transacted: MemoryStorageInner,
     persisted: MemoryStorageInner,
+    journal: Journal,
 }
 
 impl MemoryStorage {
@@ -73,9 +148,11 @@ impl MemoryStorage {
         Self {
             block_height,
             coinbase,
+            blocks: Default::default(),
             memory: Default::default(),
             transacted: Default::default(),
             persisted: Default::default(),
+            journal: Default::default(),
         }
     }
 
@@ -102,31 +179,192 @@ impl MemoryStorage {
 
     /// Set the transacted state to the memory state.
     pub fn commit(&mut self) {
-        self.transacted = self.memory.clone();
+        let transacted = mem::replace(&mut self.transacted, self.memory.clone());
+        self.journal.record(|| Undo::Transacted(transacted));
     }
 
     /// Revert the memory state to the transacted state.
     pub fn revert(&mut self) {
-        self.memory = self.transacted.clone();
+        let memory = mem::replace(&mut self.memory, self.transacted.clone());
+        self.journal.record(|| Undo::Memory(memory));
     }
 
     /// Revert the memory and transacted changes to the persisted state.
     pub fn rollback(&mut self) {
-        self.memory = self.persisted.clone();
-        self.transacted = self.persisted.clone();
+        let memory = mem::replace(&mut self.memory, self.persisted.clone());
+        let transacted = mem::replace(&mut self.transacted, self.persisted.clone());
+        self.journal.record(|| Undo::Memory(memory));
+        self.journal.record(|| Undo::Transacted(transacted));
     }
 
     /// Persist the changes from transacted to memory+persisted state.
     pub fn persist(&mut self) {
-        self.memory = self.transacted.clone();
-        self.persisted = self.transacted.clone();
+        let memory = mem::replace(&mut self.memory, self.transacted.clone());
+        let persisted = mem::replace(&mut self.persisted, self.transacted.clone());
+        self.journal.record(|| Undo::Memory(memory));
+        self.journal.record(|| Undo::Persisted(persisted));
+    }
+
+    /// Push a new savepoint with the given name on top of the savepoint stack.
+    ///
+    /// Names don't have to be unique; operations on a name refer to the most recent
+    /// savepoint with that name.
+    pub fn savepoint(&mut self, name: impl Into<String>) {
+        self.journal.savepoints.push(Savepoint {
+            name: name.into(),
+            block_height: self.block_height,
+            undo_len: self.journal.undo_log.len(),
+        });
+    }
+
+    /// Restore the state captured by the savepoint with the given name, and discard
+    /// all the savepoints pushed after it. The savepoint itself is kept, so the
+    /// storage can be rolled back to it again.
+    ///
+    /// Returns `false`, leaving the storage untouched, if there is no such savepoint.
+    pub fn rollback_to_savepoint(&mut self, name: &str) -> bool {
+        let Some(index) = self.savepoint_index(name) else {
+            return false
+        };
+
+        self.journal.savepoints.truncate(index + 1);
+        let savepoint = &self.journal.savepoints[index];
+        self.block_height = savepoint.block_height;
+
+        let undone = self.journal.undo_log.split_off(savepoint.undo_len);
+        for undo in undone.into_iter().rev() {
+            self.undo(undo);
+        }
+        true
+    }
+
+    /// Remove the savepoint with the given name, and all the savepoints pushed after
+    /// it, keeping the current state.
+    ///
+    /// Returns `false` if there is no such savepoint.
+    pub fn release_savepoint(&mut self, name: &str) -> bool {
+        let Some(index) = self.savepoint_index(name) else {
+            return false
+        };
+
+        // The changes made since are still undone by rolling back to an older savepoint
+        self.journal.savepoints.truncate(index);
+        if self.journal.savepoints.is_empty() {
+            self.journal.undo_log.clear();
+        }
+        true
+    }
+
+    /// Iterate over the names of the savepoints, from the oldest to the most recent.
+    pub fn savepoints(&self) -> impl Iterator<Item = &str> {
+        self.journal
+            .savepoints
+            .iter()
+            .map(|savepoint| savepoint.name.as_str())
+    }
+
+    fn savepoint_index(&self, name: &str) -> Option<usize> {
+        self.journal
+            .savepoints
+            .iter()
+            .rposition(|savepoint| savepoint.name == name)
+    }
+
+    fn undo(&mut self, undo: Undo) {
+        match undo {
+            Undo::Contract(key, previous) => {
+                restore(&mut self.memory.contracts, key, previous)
+            }
+            Undo::ContractInfo(key, previous) => {
+                restore(&mut self.memory.contract_code_root, key, previous)
+            }
+            Undo::Balance(key, previous) => {
+                restore(&mut self.memory.balances, key, previous)
+            }
+            Undo::State(key, previous) => {
+                restore(&mut self.memory.contract_state, key, previous)
+            }
+            Undo::Memory(previous) => self.memory = previous,
+            Undo::Transacted(previous) => self.transacted = previous,
+            Undo::Persisted(previous) => self.persisted = previous,
+            Undo::Block(height, previous) => restore(&mut self.blocks, height, previous),
+        }
     }
 
-    #[cfg(feature = "test-helpers")]
     /// Set the block height of the chain
     pub fn set_block_height(&mut self, block_height: BlockHeight) {
         self.block_height = block_height;
     }
+
+    /// Move the chain to the next block. Returns the new block height.
+    pub fn advance_block(&mut self) -> BlockHeight {
+        self.block_height = self
+            .block_height
+            .succ()
+            .expect("The block height overflowed");
+        self.block_height
+    }
+
+    /// Set the timestamp of the block at `height`.
+    pub fn set_timestamp(&mut self, height: BlockHeight, timestamp: Word) {
+        self.update_block(height, |context| context.timestamp = Some(timestamp));
+    }
+
+    /// Set the hash of the block at `height`.
+    pub fn set_block_hash(&mut self, height: BlockHeight, hash: Bytes32) {
+        self.update_block(height, |context| context.hash = Some(hash));
+    }
+
+    /// Set the coinbase of the block at `height`.
+    pub fn set_block_coinbase(&mut self, height: BlockHeight, coinbase: ContractId) {
+        self.update_block(height, |context| context.coinbase = Some(coinbase));
+    }
+
+    fn update_block(
+        &mut self,
+        height: BlockHeight,
+        update: impl FnOnce(&mut BlockContext),
+    ) {
+        let previous = self.blocks.get(&height).copied();
+        update(self.blocks.entry(height).or_default());
+        self.journal.record(|| Undo::Block(height, previous));
+    }
+
+    /// Set the timestamp, hash and coinbase of each of the given blocks, e.g. to
+    /// replay the header history of an existing chain. The current block height is
+    /// left unchanged.
+    pub fn import_headers<I>(&mut self, headers: I)
+    where
+        I: IntoIterator<Item = BlockHeader>,
+    {
+        for header in headers {
+            let context = BlockContext {
+                timestamp: Some(header.timestamp),
+                hash: Some(header.hash),
+                coinbase: Some(header.coinbase),
+            };
+            let previous = self.blocks.insert(header.height, context);
+            self.journal.record(|| Undo::Block(header.height, previous));
+        }
+    }
+
+    /// The header of the block at `height`, as seen by the interpreter.
+    pub fn block_header(&self, height: BlockHeight) -> BlockHeader {
+        const GENESIS: Tai64 = Tai64::UNIX_EPOCH;
+        const INTERVAL: Word = 10;
+
+        let context = self.blocks.get(&height).copied().unwrap_or_default();
+        BlockHeader {
+            height,
+            timestamp: context
+                .timestamp
+                .unwrap_or_else(|| (GENESIS + (*height as Word * INTERVAL)).0),
+            hash: context
+                .hash
+                .unwrap_or_else(|| Hasher::hash(height.to_be_bytes())),
+            coinbase: context.coinbase.unwrap_or(self.coinbase),
+        }
+    }
 }
 
 impl Default for MemoryStorage {
@@ -156,18 +394,25 @@ impl StorageMutate<ContractsRawCode> for MemoryStorage {
         key: &ContractId,
         value: &[u8],
     ) -> Result<Option<Contract>, Infallible> {
-        Ok(self.memory.contracts.insert(*key, value.into()))
+        let previous = self.memory.contracts.insert(*key, value.into());
+        self.journal
+            .record(|| Undo::Contract(*key, previous.clone()));
+        Ok(previous)
     }
 
     fn remove(&mut self, key: &ContractId) -> Result<Option<Contract>, Infallible> {
-        Ok(self.memory.contracts.remove(key))
+        let previous = self.memory.contracts.remove(key);
+        self.journal
+            .record(|| Undo::Contract(*key, previous.clone()));
+        Ok(previous)
     }
 }
 
 impl StorageWrite<ContractsRawCode> for MemoryStorage {
     fn write(&mut self, key: &ContractId, buf: Vec<u8>) -> Result<usize, Infallible> {
         let size = buf.len();
-        self.memory.contracts.insert(*key, Contract::from(buf));
+        let previous = self.memory.contracts.insert(*key, Contract::from(buf));
+        self.journal.record(|| Undo::Contract(*key, previous));
         Ok(size)
     }
 
@@ -181,6 +426,7 @@ impl StorageWrite<ContractsRawCode> for MemoryStorage {
     {
         let size = buf.len();
         let last = self.memory.contracts.insert(*key, Contract::from(buf));
+        self.journal.record(|| Undo::Contract(*key, last.clone()));
         Ok((size, last.map(Vec::from)))
     }
 
@@ -188,7 +434,10 @@ impl StorageWrite<ContractsRawCode> for MemoryStorage {
         &mut self,
         key: &<ContractsRawCode as Mappable>::Key,
     ) -> Result<Option<Vec<u8>>, Self::Error> {
-        Ok(self.memory.contracts.remove(key).map(Vec::from))
+        let previous = self.memory.contracts.remove(key);
+        self.journal
+            .record(|| Undo::Contract(*key, previous.clone()));
+        Ok(previous.map(Vec::from))
     }
 }
 
@@ -237,14 +486,18 @@ impl StorageMutate<ContractsInfo> for MemoryStorage {
         key: &ContractId,
         value: &(Salt, Bytes32),
     ) -> Result<Option<(Salt, Bytes32)>, Infallible> {
-        Ok(self.memory.contract_code_root.insert(*key, *value))
+        let previous = self.memory.contract_code_root.insert(*key, *value);
+        self.journal.record(|| Undo::ContractInfo(*key, previous));
+        Ok(previous)
     }
 
     fn remove(
         &mut self,
         key: &ContractId,
     ) -> Result<Option<(Salt, Bytes32)>, Infallible> {
-        Ok(self.memory.contract_code_root.remove(key))
+        let previous = self.memory.contract_code_root.remove(key);
+        self.journal.record(|| Undo::ContractInfo(*key, previous));
+        Ok(previous)
     }
 }
 
@@ -272,14 +525,18 @@ impl StorageMutate<ContractsAssets> for MemoryStorage {
         key: &<ContractsAssets as Mappable>::Key,
         value: &Word,
     ) -> Result<Option<Word>, Infallible> {
-        Ok(self.memory.balances.insert(*key, *value))
+        let previous = self.memory.balances.insert(*key, *value);
+        self.journal.record(|| Undo::Balance(*key, previous));
+        Ok(previous)
     }
 
     fn remove(
         &mut self,
         key: &<ContractsAssets as Mappable>::Key,
     ) -> Result<Option<Word>, Infallible> {
-        Ok(self.memory.balances.remove(key))
+        let previous = self.memory.balances.remove(key);
+        self.journal.record(|| Undo::Balance(*key, previous));
+        Ok(previous)
     }
 }
 
@@ -324,14 +581,18 @@ impl StorageMutate<ContractsState> for MemoryStorage {
         key: &<ContractsState as Mappable>::Key,
         value: &Bytes32,
     ) -> Result<Option<Bytes32>, Infallible> {
-        Ok(self.memory.contract_state.insert(*key, *value))
+        let previous = self.memory.contract_state.insert(*key, *value);
+        self.journal.record(|| Undo::State(*key, previous));
+        Ok(previous)
     }
 
     fn remove(
         &mut self,
         key: &<ContractsState as Mappable>::Key,
     ) -> Result<Option<Bytes32>, Infallible> {
-        Ok(self.memory.contract_state.remove(key))
+        let previous = self.memory.contract_state.remove(key);
+        self.journal.record(|| Undo::State(*key, previous));
+        Ok(previous)
     }
 }
 
@@ -361,18 +622,15 @@ impl InterpreterStorage for MemoryStorage {
     }
 
     fn timestamp(&self, height: BlockHeight) -> Result<Word, Self::DataError> {
-        const GENESIS: Tai64 = Tai64::UNIX_EPOCH;
-        const INTERVAL: Word = 10;
-
-        Ok((GENESIS + (*height as Word * INTERVAL)).0)
+        Ok(self.block_header(height).timestamp)
     }
 
     fn block_hash(&self, block_height: BlockHeight) -> Result<Bytes32, Infallible> {
-        Ok(Hasher::hash(block_height.to_be_bytes()))
+        Ok(self.block_header(block_height).hash)
     }
 
     fn coinbase(&self) -> Result<ContractId, Infallible> {
-        Ok(self.coinbase)
+        Ok(self.block_header(self.block_height).coinbase)
     }
 
     fn merkle_contract_state_range(
@@ -434,7 +692,10 @@ impl InterpreterStorage for MemoryStorage {
             (key, *value)
         })
         .collect();
-        self.memory.contract_state.extend(values);
+        for (key, value) in values {
+            let previous = self.memory.contract_state.insert(key, value);
+            self.journal.record(|| Undo::State(key, previous));
+        }
         Ok((!any_unset_key).then_some(()))
     }
 
@@ -445,29 +706,43 @@ impl InterpreterStorage for MemoryStorage {
         range: Word,
     ) -> Result<Option<()>, Self::DataError> {
         let mut all_set_key = true;
-        let mut values: hashbrown::HashSet<_> =
-            core::iter::successors(Some(**start_key), |n| {
-                let mut n = *n;
-                if add_one(&mut n) {
-                    None
-                } else {
-                    Some(n)
-                }
-            })
-            .take(range as usize)
-            .collect();
-        self.memory.contract_state.retain(|key, _| {
-            let c = key.contract_id();
-            let k = key.state_key();
-            let r = values.remove(&**k);
-            all_set_key &= c == contract && r;
-            c != contract || !r
-        });
-        Ok((all_set_key && values.is_empty()).then_some(()))
+        for key in state_keys(start_key).take(range as usize) {
+            let key = (contract, &key).into();
+            let previous = self.memory.contract_state.remove(&key);
+            all_set_key &= previous.is_some();
+            if previous.is_some() {
+                self.journal.record(|| Undo::State(key, previous));
+            }
+        }
+        Ok(all_set_key.then_some(()))
+    }
+}
+
+fn restore<K: Ord, V>(map: &mut BTreeMap<K, V>, key: K, previous: Option<V>) {
+    match previous {
+        Some(value) => {
+            map.insert(key, value);
+        }
+        None => {
+            map.remove(&key);
+        }
     }
 }
 
-fn add_one(a: &mut [u8; 32]) -> bool {
+/// The keys of a contract's state from `start_key` onwards.
+pub(crate) fn state_keys(start_key: &Bytes32) -> impl Iterator<Item = Bytes32> {
+    core::iter::successors(Some(**start_key), |n| {
+        let mut n = *n;
+        if add_one(&mut n) {
+            None
+        } else {
+            Some(n)
+        }
+    })
+    .map(Bytes32::from)
+}
+
+pub(super) fn add_one(a: &mut [u8; 32]) -> bool {
     let right = u128::from_be_bytes(a[16..].try_into().unwrap());
     let (right, of) = right.overflowing_add(1);
     a[16..].copy_from_slice(&right.to_be_bytes()[..]);
@@ -518,4 +793,238 @@ mod tests {
             .map(|v| v.map(|v| v.into_owned()))
             .collect()
     }
+
+    #[test_case(&[&key(0), &key(1), &key(5)], &key(0), 2 => (Some(()), 1))]
+    #[test_case(&[&key(0), &key(1), &key(5)], &key(4), 2 => (None, 2))]
+    #[test_case(&[&key(0), &key(1)], &key(0), 3 => (None, 0))]
+    #[test_case(&[], &key(0), 0 => (Some(()), 0))]
+    fn test_contract_state_remove_range(
+        store: &[&[u8; 32]],
+        start: &[u8; 32],
+        range: Word,
+    ) -> (Option<()>, usize) {
+        let mut mem = MemoryStorage::default();
+        for k in store {
+            mem.memory.contract_state.insert(
+                (&ContractId::default(), &(**k).into()).into(),
+                Bytes32::zeroed(),
+            );
+        }
+        // The same keys of another contract are neither removed nor counted as set
+        mem.memory.contract_state.insert(
+            (&ContractId::from([1; 32]), &(*start).into()).into(),
+            [1; 32].into(),
+        );
+        let removed = mem
+            .merkle_contract_state_remove_range(
+                &ContractId::default(),
+                &(*start).into(),
+                range,
+            )
+            .unwrap();
+        (removed, mem.memory.contract_state.len() - 1)
+    }
+
+    #[test]
+    fn rollback_to_savepoint_restores_all_states() {
+        let contract = ContractId::default();
+        let key = Bytes32::zeroed();
+        let mut mem = MemoryStorage::default();
+
+        mem.merkle_contract_state_insert(&contract, &key, &[1; 32].into())
+            .unwrap();
+        mem.commit();
+        mem.savepoint("first");
+
+        mem.merkle_contract_state_insert(&contract, &key, &[2; 32].into())
+            .unwrap();
+        mem.commit();
+        mem.persist();
+        mem.savepoint("second");
+
+        mem.merkle_contract_state_insert(&contract, &key, &[3; 32].into())
+            .unwrap();
+        assert_eq!(
+            mem.savepoints().collect::<Vec<_>>(),
+            vec!["first", "second"]
+        );
+
+        assert!(mem.rollback_to_savepoint("second"));
+        assert_eq!(*mem.contract_state(&contract, &key), Bytes32::from([2; 32]));
+        assert_eq!(
+            mem.savepoints().collect::<Vec<_>>(),
+            vec!["first", "second"]
+        );
+
+        assert!(mem.rollback_to_savepoint("first"));
+        assert_eq!(*mem.contract_state(&contract, &key), Bytes32::from([1; 32]));
+        assert_eq!(mem.savepoints().collect::<Vec<_>>(), vec!["first"]);
+
+        // The persisted state was restored as well.
+        mem.rollback();
+        assert_eq!(*mem.contract_state(&contract, &key), Bytes32::zeroed());
+
+        assert!(!mem.rollback_to_savepoint("second"));
+    }
+
+    #[test]
+    fn rollback_to_savepoint_restores_the_block_context() {
+        let mut mem = MemoryStorage::default();
+        let height = BlockHeight::from(2);
+        let derived = mem.block_header(height);
+        mem.set_timestamp(height, 1234);
+
+        mem.savepoint("before");
+        mem.advance_block();
+        mem.set_timestamp(height, 5678);
+        mem.set_block_hash(height, Bytes32::from([1; 32]));
+        mem.import_headers([BlockHeader {
+            height: 3.into(),
+            timestamp: 9999,
+            hash: Bytes32::from([3; 32]),
+            coinbase: ContractId::from([3; 32]),
+        }]);
+        mem.merkle_contract_state_insert_range(
+            &ContractId::default(),
+            &Bytes32::zeroed(),
+            &[[1; 32].into(), [2; 32].into()],
+        )
+        .unwrap();
+        mem.merkle_contract_state_remove_range(
+            &ContractId::default(),
+            &Bytes32::zeroed(),
+            1,
+        )
+        .unwrap();
+
+        assert!(mem.rollback_to_savepoint("before"));
+        assert_eq!(mem.block_height().unwrap(), 1.into());
+        assert_eq!(mem.block_header(height).timestamp, 1234);
+        assert_eq!(mem.block_header(height).hash, derived.hash);
+        assert_eq!(
+            mem.block_header(3.into()),
+            MemoryStorage::default().block_header(3.into())
+        );
+        assert_eq!(mem.all_contract_state().count(), 0);
+        assert!(mem.journal.undo_log.is_empty());
+
+        // Nothing is recorded once the last savepoint is released
+        mem.set_timestamp(height, 5678);
+        assert!(!mem.journal.undo_log.is_empty());
+        assert!(mem.release_savepoint("before"));
+        assert!(mem.journal.undo_log.is_empty());
+        mem.set_timestamp(height, 1234);
+        assert!(mem.journal.undo_log.is_empty());
+    }
+
+    #[test]
+    fn release_savepoint_keeps_the_current_state() {
+        let contract = ContractId::default();
+        let key = Bytes32::zeroed();
+        let mut mem = MemoryStorage::default();
+
+        mem.savepoint("a");
+        mem.savepoint("b");
+        mem.savepoint("a");
+        mem.merkle_contract_state_insert(&contract, &key, &[1; 32].into())
+            .unwrap();
+
+        // The most recent savepoint with the name is released.
+        assert!(mem.release_savepoint("a"));
+        assert_eq!(mem.savepoints().collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!(*mem.contract_state(&contract, &key), Bytes32::from([1; 32]));
+
+        assert!(mem.release_savepoint("a"));
+        assert_eq!(mem.savepoints().count(), 0);
+        assert_eq!(*mem.contract_state(&contract, &key), Bytes32::from([1; 32]));
+
+        assert!(!mem.release_savepoint("a"));
+    }
+
+    #[test]
+    fn rollback_to_savepoint_reverts_deployed_contracts() {
+        use crate::prelude::*;
+        use fuel_asm::{
+            op,
+            RegId,
+        };
+
+        let deploy = |salt: Salt| {
+            let program: Witness = vec![op::ret(RegId::ONE)]
+                .into_iter()
+                .collect::<Vec<u8>>()
+                .into();
+            let contract = Contract::from(program.as_ref());
+            let state_root = Contract::default_state_root();
+            let id = contract.id(&salt, &contract.root(), &state_root);
+            let tx = TransactionBuilder::create(program, salt, vec![])
+                .gas_limit(1_000_000)
+                .add_random_fee_input()
+                .add_output(Output::contract_created(id, state_root))
+                .finalize_checked(Default::default());
+            (id, tx)
+        };
+        let (first, first_tx) = deploy([1; 32].into());
+        let (second, second_tx) = deploy([2; 32].into());
+
+        let mut client = MemoryClient::default();
+        client.as_mut().savepoint("genesis");
+        client.deploy(first_tx).expect("failed to deploy");
+        client.as_mut().savepoint("first");
+        client.deploy(second_tx).expect("failed to deploy");
+
+        let exists = |client: &MemoryClient, id| {
+            client.as_ref().storage_contract_exists(id).unwrap()
+        };
+        assert!(exists(&client, &first) && exists(&client, &second));
+
+        assert!(client.as_mut().rollback_to_savepoint("first"));
+        assert!(exists(&client, &first) && !exists(&client, &second));
+
+        assert!(client.as_mut().rollback_to_savepoint("genesis"));
+        assert!(!exists(&client, &first) && !exists(&client, &second));
+    }
+
+    #[test]
+    fn block_header_falls_back_to_derived_values() {
+        let mut mem = MemoryStorage::default();
+        let height = BlockHeight::from(5);
+
+        let derived = mem.block_header(height);
+        assert_eq!(derived.timestamp, (Tai64::UNIX_EPOCH + 50).0);
+        assert_eq!(derived.hash, Hasher::hash(height.to_be_bytes()));
+        assert_eq!(derived.coinbase, mem.coinbase().unwrap());
+
+        mem.set_timestamp(height, 1234);
+        mem.set_block_coinbase(height, ContractId::from([1; 32]));
+        let header = mem.block_header(height);
+        assert_eq!(header.timestamp, 1234);
+        assert_eq!(header.hash, derived.hash);
+        assert_eq!(header.coinbase, ContractId::from([1; 32]));
+    }
+
+    #[test]
+    fn block_context_follows_the_current_block() {
+        let mut mem = MemoryStorage::default();
+        let headers: Vec<_> = (1..=3u32)
+            .map(|height| BlockHeader {
+                height: height.into(),
+                timestamp: 1000 + height as Word,
+                hash: Bytes32::from([height as u8; 32]),
+                coinbase: ContractId::from([height as u8; 32]),
+            })
+            .collect();
+        mem.import_headers(headers.iter().copied());
+
+        assert_eq!(mem.block_height().unwrap(), 1.into());
+        assert_eq!(mem.coinbase().unwrap(), headers[0].coinbase);
+
+        assert_eq!(mem.advance_block(), 2.into());
+        assert_eq!(mem.coinbase().unwrap(), headers[1].coinbase);
+        for header in headers.iter() {
+            assert_eq!(mem.block_header(header.height), *header);
+            assert_eq!(mem.timestamp(header.height).unwrap(), header.timestamp);
+            assert_eq!(mem.block_hash(header.height).unwrap(), header.hash);
+        }
+    }
 }
@@ -0,0 +1,582 @@
+use crate::storage::{
+    ContractsAssets,
+    ContractsInfo,
+    ContractsRawCode,
+    ContractsState,
+    InterpreterStorage,
+};
+
+use fuel_storage::{
+    Mappable,
+    MerkleRoot,
+    MerkleRootStorage,
+    StorageInspect,
+    StorageMutate,
+    StorageRead,
+    StorageSize,
+};
+use fuel_tx::Contract;
+use fuel_types::{
+    BlockHeight,
+    Bytes32,
+    ContractId,
+    Salt,
+    Word,
+};
+
+use alloc::{
+    borrow::Cow,
+    collections::BTreeMap,
+    vec::Vec,
+};
+use core::{
+    mem,
+    ops::Deref,
+};
+
+use super::{
+    interpreter::ContractsAssetsStorage,
+    memory::state_keys,
+    recording::record,
+    StorageChange,
+    StorageDiff,
+};
+
+/// Storage wrapper reading through to the underlying storage, and keeping the
+/// writes in memory as a [`StorageDiff`] instead of applying them.
+///
+/// The underlying storage is only read, so it can be borrowed or shared, e.g. as
+/// `&S` or `Arc<S>`.
+///
+/// The merkle roots are the ones of the underlying storage, and don't account for
+/// the pending writes.
+#[derive(Debug, Default, Clone)]
+pub struct OverlayStorage<S> {
+    storage: S,
+    diff: StorageDiff,
+}
+
+impl<S> OverlayStorage<S> {
+    /// Wrap the provided storage.
+    pub fn new(storage: S) -> Self {
+        Self {
+            storage,
+            diff: StorageDiff::default(),
+        }
+    }
+
+    /// The underlying storage, without the pending writes.
+    pub const fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// The pending writes.
+    pub const fn diff(&self) -> &StorageDiff {
+        &self.diff
+    }
+
+    /// Return the pending writes, discarding them from the overlay.
+    pub fn take_diff(&mut self) -> StorageDiff {
+        mem::take(&mut self.diff)
+    }
+
+    /// Return the underlying storage, discarding the pending writes.
+    pub fn into_inner(self) -> S {
+        self.storage
+    }
+}
+
+/// The pending value of `key`, if it was written.
+fn pending<'a, K, T>(
+    table: &'a BTreeMap<K, StorageChange<T>>,
+    key: &K,
+) -> Option<Option<&'a T>>
+where
+    K: Ord,
+{
+    table.get(key).map(|change| change.after.as_ref())
+}
+
+impl<S> StorageInspect<ContractsRawCode> for OverlayStorage<S>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+{
+    type Error = <S::Target as InterpreterStorage>::DataError;
+
+    fn get(&self, key: &ContractId) -> Result<Option<Cow<'_, Contract>>, Self::Error> {
+        match pending(&self.diff.contracts, key) {
+            Some(value) => Ok(value.map(Cow::Borrowed)),
+            None => StorageInspect::<ContractsRawCode>::get(self.storage.deref(), key),
+        }
+    }
+
+    fn contains_key(&self, key: &ContractId) -> Result<bool, Self::Error> {
+        match pending(&self.diff.contracts, key) {
+            Some(value) => Ok(value.is_some()),
+            None => StorageInspect::<ContractsRawCode>::contains_key(
+                self.storage.deref(),
+                key,
+            ),
+        }
+    }
+}
+
+impl<S> StorageMutate<ContractsRawCode> for OverlayStorage<S>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+{
+    fn insert(
+        &mut self,
+        key: &ContractId,
+        value: &[u8],
+    ) -> Result<Option<Contract>, Self::Error> {
+        let before =
+            StorageInspect::<ContractsRawCode>::get(self, key)?.map(Cow::into_owned);
+        record(
+            &mut self.diff.contracts,
+            *key,
+            before.clone(),
+            Some(value.into()),
+        );
+        Ok(before)
+    }
+
+    fn remove(&mut self, key: &ContractId) -> Result<Option<Contract>, Self::Error> {
+        let before =
+            StorageInspect::<ContractsRawCode>::get(self, key)?.map(Cow::into_owned);
+        record(&mut self.diff.contracts, *key, before.clone(), None);
+        Ok(before)
+    }
+}
+
+impl<S> StorageSize<ContractsRawCode> for OverlayStorage<S>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+{
+    fn size_of_value(&self, key: &ContractId) -> Result<Option<usize>, Self::Error> {
+        match pending(&self.diff.contracts, key) {
+            Some(value) => Ok(value.map(|contract| contract.as_ref().len())),
+            None => {
+                StorageSize::<ContractsRawCode>::size_of_value(self.storage.deref(), key)
+            }
+        }
+    }
+}
+
+impl<S> StorageRead<ContractsRawCode> for OverlayStorage<S>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+{
+    fn read(
+        &self,
+        key: &ContractId,
+        buf: &mut [u8],
+    ) -> Result<Option<usize>, Self::Error> {
+        match pending(&self.diff.contracts, key) {
+            Some(Some(contract)) => {
+                let bytes = contract.as_ref();
+                let len = bytes.len().min(buf.len());
+                buf[..len].copy_from_slice(&bytes[..len]);
+                Ok(Some(len))
+            }
+            Some(None) => Ok(None),
+            None => StorageRead::<ContractsRawCode>::read(self.storage.deref(), key, buf),
+        }
+    }
+
+    fn read_alloc(&self, key: &ContractId) -> Result<Option<Vec<u8>>, Self::Error> {
+        match pending(&self.diff.contracts, key) {
+            Some(value) => Ok(value.map(|contract| contract.as_ref().to_vec())),
+            None => {
+                StorageRead::<ContractsRawCode>::read_alloc(self.storage.deref(), key)
+            }
+        }
+    }
+}
+
+impl<S> StorageInspect<ContractsInfo> for OverlayStorage<S>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+{
+    type Error = <S::Target as InterpreterStorage>::DataError;
+
+    fn get(
+        &self,
+        key: &ContractId,
+    ) -> Result<Option<Cow<'_, (Salt, Bytes32)>>, Self::Error> {
+        match pending(&self.diff.contracts_info, key) {
+            Some(value) => Ok(value.map(Cow::Borrowed)),
+            None => StorageInspect::<ContractsInfo>::get(self.storage.deref(), key),
+        }
+    }
+
+    fn contains_key(&self, key: &ContractId) -> Result<bool, Self::Error> {
+        match pending(&self.diff.contracts_info, key) {
+            Some(value) => Ok(value.is_some()),
+            None => {
+                StorageInspect::<ContractsInfo>::contains_key(self.storage.deref(), key)
+            }
+        }
+    }
+}
+
+impl<S> StorageMutate<ContractsInfo> for OverlayStorage<S>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+{
+    fn insert(
+        &mut self,
+        key: &ContractId,
+        value: &(Salt, Bytes32),
+    ) -> Result<Option<(Salt, Bytes32)>, Self::Error> {
+        let before =
+            StorageInspect::<ContractsInfo>::get(self, key)?.map(Cow::into_owned);
+        record(&mut self.diff.contracts_info, *key, before, Some(*value));
+        Ok(before)
+    }
+
+    fn remove(
+        &mut self,
+        key: &ContractId,
+    ) -> Result<Option<(Salt, Bytes32)>, Self::Error> {
+        let before =
+            StorageInspect::<ContractsInfo>::get(self, key)?.map(Cow::into_owned);
+        record(&mut self.diff.contracts_info, *key, before, None);
+        Ok(before)
+    }
+}
+
+impl<S> StorageInspect<ContractsAssets> for OverlayStorage<S>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+{
+    type Error = <S::Target as InterpreterStorage>::DataError;
+
+    fn get(
+        &self,
+        key: &<ContractsAssets as Mappable>::Key,
+    ) -> Result<Option<Cow<'_, Word>>, Self::Error> {
+        match pending(&self.diff.balances, key) {
+            Some(value) => Ok(value.map(Cow::Borrowed)),
+            None => StorageInspect::<ContractsAssets>::get(self.storage.deref(), key),
+        }
+    }
+
+    fn contains_key(
+        &self,
+        key: &<ContractsAssets as Mappable>::Key,
+    ) -> Result<bool, Self::Error> {
+        match pending(&self.diff.balances, key) {
+            Some(value) => Ok(value.is_some()),
+            None => {
+                StorageInspect::<ContractsAssets>::contains_key(self.storage.deref(), key)
+            }
+        }
+    }
+}
+
+impl<S> StorageMutate<ContractsAssets> for OverlayStorage<S>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+{
+    fn insert(
+        &mut self,
+        key: &<ContractsAssets as Mappable>::Key,
+        value: &Word,
+    ) -> Result<Option<Word>, Self::Error> {
+        let before =
+            StorageInspect::<ContractsAssets>::get(self, key)?.map(Cow::into_owned);
+        record(&mut self.diff.balances, *key, before, Some(*value));
+        Ok(before)
+    }
+
+    fn remove(
+        &mut self,
+        key: &<ContractsAssets as Mappable>::Key,
+    ) -> Result<Option<Word>, Self::Error> {
+        let before =
+            StorageInspect::<ContractsAssets>::get(self, key)?.map(Cow::into_owned);
+        record(&mut self.diff.balances, *key, before, None);
+        Ok(before)
+    }
+}
+
+impl<S> MerkleRootStorage<ContractId, ContractsAssets> for OverlayStorage<S>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+{
+    fn root(&self, parent: &ContractId) -> Result<MerkleRoot, Self::Error> {
+        MerkleRootStorage::<ContractId, ContractsAssets>::root(
+            self.storage.deref(),
+            parent,
+        )
+    }
+}
+
+impl<S> StorageInspect<ContractsState> for OverlayStorage<S>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+{
+    type Error = <S::Target as InterpreterStorage>::DataError;
+
+    fn get(
+        &self,
+        key: &<ContractsState as Mappable>::Key,
+    ) -> Result<Option<Cow<'_, Bytes32>>, Self::Error> {
+        match pending(&self.diff.state, key) {
+            Some(value) => Ok(value.map(Cow::Borrowed)),
+            None => StorageInspect::<ContractsState>::get(self.storage.deref(), key),
+        }
+    }
+
+    fn contains_key(
+        &self,
+        key: &<ContractsState as Mappable>::Key,
+    ) -> Result<bool, Self::Error> {
+        match pending(&self.diff.state, key) {
+            Some(value) => Ok(value.is_some()),
+            None => {
+                StorageInspect::<ContractsState>::contains_key(self.storage.deref(), key)
+            }
+        }
+    }
+}
+
+impl<S> StorageMutate<ContractsState> for OverlayStorage<S>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+{
+    fn insert(
+        &mut self,
+        key: &<ContractsState as Mappable>::Key,
+        value: &Bytes32,
+    ) -> Result<Option<Bytes32>, Self::Error> {
+        let before =
+            StorageInspect::<ContractsState>::get(self, key)?.map(Cow::into_owned);
+        record(&mut self.diff.state, *key, before, Some(*value));
+        Ok(before)
+    }
+
+    fn remove(
+        &mut self,
+        key: &<ContractsState as Mappable>::Key,
+    ) -> Result<Option<Bytes32>, Self::Error> {
+        let before =
+            StorageInspect::<ContractsState>::get(self, key)?.map(Cow::into_owned);
+        record(&mut self.diff.state, *key, before, None);
+        Ok(before)
+    }
+}
+
+impl<S> MerkleRootStorage<ContractId, ContractsState> for OverlayStorage<S>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+{
+    fn root(&self, parent: &ContractId) -> Result<MerkleRoot, Self::Error> {
+        MerkleRootStorage::<ContractId, ContractsState>::root(
+            self.storage.deref(),
+            parent,
+        )
+    }
+}
+
+impl<S> ContractsAssetsStorage for OverlayStorage<S>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+{
+}
+
+impl<S> InterpreterStorage for OverlayStorage<S>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+{
+    type DataError = <S::Target as InterpreterStorage>::DataError;
+
+    fn block_height(&self) -> Result<BlockHeight, Self::DataError> {
+        self.storage.block_height()
+    }
+
+    fn timestamp(&self, height: BlockHeight) -> Result<Word, Self::DataError> {
+        self.storage.timestamp(height)
+    }
+
+    fn block_hash(&self, block_height: BlockHeight) -> Result<Bytes32, Self::DataError> {
+        self.storage.block_hash(block_height)
+    }
+
+    fn coinbase(&self) -> Result<ContractId, Self::DataError> {
+        self.storage.coinbase()
+    }
+
+    fn merkle_contract_state_range(
+        &self,
+        id: &ContractId,
+        start_key: &Bytes32,
+        range: Word,
+    ) -> Result<Vec<Option<Cow<'_, Bytes32>>>, Self::DataError> {
+        let values = self
+            .storage
+            .merkle_contract_state_range(id, start_key, range)?;
+
+        Ok(state_keys(start_key)
+            .zip(values)
+            .map(
+                |(key, value)| match pending(&self.diff.state, &(id, &key).into()) {
+                    Some(value) => value.map(Cow::Borrowed),
+                    None => value,
+                },
+            )
+            .collect())
+    }
+
+    fn merkle_contract_state_insert_range(
+        &mut self,
+        contract: &ContractId,
+        start_key: &Bytes32,
+        values: &[Bytes32],
+    ) -> Result<Option<()>, Self::DataError> {
+        let before = self.owned_state_range(contract, start_key, values.len() as Word)?;
+        let all_set = before.iter().all(Option::is_some);
+
+        for ((key, before), value) in state_keys(start_key).zip(before).zip(values) {
+            let key = (contract, &key).into();
+            record(&mut self.diff.state, key, before, Some(*value));
+        }
+
+        Ok(all_set.then_some(()))
+    }
+
+    fn merkle_contract_state_remove_range(
+        &mut self,
+        contract: &ContractId,
+        start_key: &Bytes32,
+        range: Word,
+    ) -> Result<Option<()>, Self::DataError> {
+        let before = self.owned_state_range(contract, start_key, range)?;
+        let all_set = before.iter().all(Option::is_some);
+
+        for (key, before) in state_keys(start_key).zip(before) {
+            let key = (contract, &key).into();
+            record(&mut self.diff.state, key, before, None);
+        }
+
+        Ok(all_set.then_some(()))
+    }
+}
+
+impl<S> OverlayStorage<S>
+where
+    S: Deref,
+    S::Target: InterpreterStorage,
+{
+    fn owned_state_range(
+        &self,
+        contract: &ContractId,
+        start_key: &Bytes32,
+        range: Word,
+    ) -> Result<Vec<Option<Bytes32>>, <S::Target as InterpreterStorage>::DataError> {
+        Ok(self
+            .merkle_contract_state_range(contract, start_key, range)?
+            .into_iter()
+            .map(|value| value.map(Cow::into_owned))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use fuel_types::AssetId;
+
+    fn state_key(k: u8) -> Bytes32 {
+        let mut key = Bytes32::zeroed();
+        key[31] = k;
+        key
+    }
+
+    #[test]
+    fn writes_are_kept_in_the_overlay() {
+        let contract = ContractId::from([1u8; 32]);
+        let asset = AssetId::from([2u8; 32]);
+
+        let mut memory = MemoryStorage::default();
+        memory
+            .merkle_contract_state_insert(&contract, &state_key(0), &[1u8; 32].into())
+            .unwrap();
+        memory
+            .merkle_contract_state_insert(&contract, &state_key(2), &[2u8; 32].into())
+            .unwrap();
+
+        let mut storage = OverlayStorage::new(&mut memory);
+        storage
+            .merkle_contract_state_insert(&contract, &state_key(0), &[3u8; 32].into())
+            .unwrap();
+        storage
+            .merkle_contract_asset_id_balance_insert(&contract, &asset, 10)
+            .unwrap();
+        let all_set = storage
+            .merkle_contract_state_remove_range(&contract, &state_key(1), 2)
+            .unwrap();
+        assert_eq!(all_set, None);
+
+        // Reads see the pending writes
+        assert_eq!(
+            storage
+                .merkle_contract_state(&contract, &state_key(0))
+                .unwrap()
+                .map(Cow::into_owned),
+            Some([3u8; 32].into())
+        );
+        assert_eq!(
+            storage
+                .merkle_contract_state_range(&contract, &state_key(0), 3)
+                .unwrap()
+                .into_iter()
+                .map(|value| value.map(Cow::into_owned))
+                .collect::<Vec<_>>(),
+            [Some([3u8; 32].into()), None, None]
+        );
+        assert_eq!(
+            storage
+                .merkle_contract_asset_id_balance(&contract, &asset)
+                .unwrap(),
+            Some(10)
+        );
+
+        let diff = storage.take_diff();
+        assert_eq!(diff.state.len(), 2);
+        assert_eq!(diff.balances.len(), 1);
+
+        // The underlying storage is untouched
+        assert_eq!(
+            memory
+                .merkle_contract_state_range(&contract, &state_key(0), 3)
+                .unwrap()
+                .into_iter()
+                .map(|value| value.map(Cow::into_owned))
+                .collect::<Vec<_>>(),
+            [Some([1u8; 32].into()), None, Some([2u8; 32].into())]
+        );
+        assert_eq!(
+            memory
+                .merkle_contract_asset_id_balance(&contract, &asset)
+                .unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,695 @@
+use crate::storage::{
+    ContractsAssetKey,
+    ContractsAssets,
+    ContractsInfo,
+    ContractsRawCode,
+    ContractsState,
+    ContractsStateKey,
+    InterpreterStorage,
+};
+
+use fuel_storage::{
+    Mappable,
+    MerkleRoot,
+    MerkleRootStorage,
+    StorageInspect,
+    StorageMutate,
+    StorageRead,
+    StorageSize,
+};
+use fuel_tx::Contract;
+use fuel_types::{
+    BlockHeight,
+    Bytes32,
+    ContractId,
+    Salt,
+    Word,
+};
+
+use alloc::{
+    borrow::Cow,
+    collections::{
+        btree_map::Entry,
+        BTreeMap,
+    },
+    vec::Vec,
+};
+use core::mem;
+
+use super::{
+    interpreter::ContractsAssetsStorage,
+    memory::state_keys,
+};
+
+/// The value of a storage entry before and after a sequence of writes.
+///
+/// `None` means the entry is unset.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StorageChange<T> {
+    /// The value before the first write.
+    pub before: Option<T>,
+    /// The value after the last write.
+    pub after: Option<T>,
+}
+
+/// The storage entries changed by a sequence of writes, grouped by table.
+///
+/// Entries written back to their original value are not part of the diff.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StorageDiff {
+    /// Changes of [`ContractsRawCode`].
+    pub contracts: BTreeMap<ContractId, StorageChange<Contract>>,
+    /// Changes of [`ContractsInfo`].
+    pub contracts_info: BTreeMap<ContractId, StorageChange<(Salt, Bytes32)>>,
+    /// Changes of [`ContractsAssets`].
+    pub balances: BTreeMap<ContractsAssetKey, StorageChange<Word>>,
+    /// Changes of [`ContractsState`].
+    pub state: BTreeMap<ContractsStateKey, StorageChange<Bytes32>>,
+}
+
+impl StorageDiff {
+    /// Return `true` if no entry was changed.
+    pub fn is_empty(&self) -> bool {
+        self.contracts.is_empty()
+            && self.contracts_info.is_empty()
+            && self.balances.is_empty()
+            && self.state.is_empty()
+    }
+
+    /// Append the changes of `other`, which happened after the changes of `self`.
+    pub fn merge(&mut self, other: StorageDiff) {
+        fn merge_table<K, T>(
+            table: &mut BTreeMap<K, StorageChange<T>>,
+            other: BTreeMap<K, StorageChange<T>>,
+        ) where
+            K: Ord,
+            T: PartialEq,
+        {
+            for (key, change) in other {
+                record(table, key, change.before, change.after);
+            }
+        }
+
+        merge_table(&mut self.contracts, other.contracts);
+        merge_table(&mut self.contracts_info, other.contracts_info);
+        merge_table(&mut self.balances, other.balances);
+        merge_table(&mut self.state, other.state);
+    }
+
+    /// Write the `before` value of every changed entry back into `storage`.
+    pub fn revert<S>(&self, storage: &mut S) -> Result<(), S::DataError>
+    where
+        S: InterpreterStorage,
+    {
+        self.write(storage, false)
+    }
+
+    /// Write the `after` value of every changed entry into `storage`.
+    pub fn apply<S>(&self, storage: &mut S) -> Result<(), S::DataError>
+    where
+        S: InterpreterStorage,
+    {
+        self.write(storage, true)
+    }
+
+    /// Write the `after` value of every changed entry if `after` is set, and the
+    /// `before` value otherwise.
+    fn write<S>(&self, storage: &mut S, after: bool) -> Result<(), S::DataError>
+    where
+        S: InterpreterStorage,
+    {
+        fn value<T>(change: &StorageChange<T>, after: bool) -> &Option<T> {
+            if after {
+                &change.after
+            } else {
+                &change.before
+            }
+        }
+
+        for (key, change) in &self.contracts {
+            match value(change, after) {
+                Some(contract) => {
+                    StorageMutate::<ContractsRawCode>::insert(
+                        storage,
+                        key,
+                        contract.as_ref(),
+                    )?;
+                }
+                None => {
+                    StorageMutate::<ContractsRawCode>::remove(storage, key)?;
+                }
+            }
+        }
+
+        for (key, change) in &self.contracts_info {
+            match value(change, after) {
+                Some(info) => {
+                    StorageMutate::<ContractsInfo>::insert(storage, key, info)?;
+                }
+                None => {
+                    StorageMutate::<ContractsInfo>::remove(storage, key)?;
+                }
+            }
+        }
+
+        for (key, change) in &self.balances {
+            match value(change, after) {
+                Some(balance) => {
+                    StorageMutate::<ContractsAssets>::insert(storage, key, balance)?;
+                }
+                None => {
+                    StorageMutate::<ContractsAssets>::remove(storage, key)?;
+                }
+            }
+        }
+
+        for (key, change) in &self.state {
+            match value(change, after) {
+                Some(value) => {
+                    StorageMutate::<ContractsState>::insert(storage, key, value)?;
+                }
+                None => {
+                    StorageMutate::<ContractsState>::remove(storage, key)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Record a write of `after` over `before`, keeping the oldest `before` of the entry.
+pub(super) fn record<K, T>(
+    table: &mut BTreeMap<K, StorageChange<T>>,
+    key: K,
+    before: Option<T>,
+    after: Option<T>,
+) where
+    K: Ord,
+    T: PartialEq,
+{
+    match table.entry(key) {
+        Entry::Occupied(mut entry) => {
+            entry.get_mut().after = after;
+            if entry.get().before == entry.get().after {
+                entry.remove();
+            }
+        }
+        Entry::Vacant(entry) => {
+            if before != after {
+                entry.insert(StorageChange { before, after });
+            }
+        }
+    }
+}
+
+/// Storage wrapper recording every write to the underlying storage as a
+/// [`StorageDiff`].
+///
+/// Writes done through [`Self::storage_mut`] bypass the recording.
+#[derive(Debug, Default, Clone)]
+pub struct RecordingStorage<S> {
+    storage: S,
+    diff: StorageDiff,
+}
+
+impl<S> RecordingStorage<S> {
+    /// Wrap the provided storage.
+    pub fn new(storage: S) -> Self {
+        Self {
+            storage,
+            diff: StorageDiff::default(),
+        }
+    }
+
+    /// The underlying storage.
+    pub const fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Mutable access to the underlying storage, without recording.
+    pub fn storage_mut(&mut self) -> &mut S {
+        &mut self.storage
+    }
+
+    /// The changes recorded so far.
+    pub const fn diff(&self) -> &StorageDiff {
+        &self.diff
+    }
+
+    /// Return the changes recorded so far and start a new recording.
+    pub fn take_diff(&mut self) -> StorageDiff {
+        mem::take(&mut self.diff)
+    }
+
+    /// Return the underlying storage, discarding the recording.
+    pub fn into_inner(self) -> S {
+        self.storage
+    }
+}
+
+impl<S> RecordingStorage<S>
+where
+    S: InterpreterStorage,
+{
+    /// Undo the changes recorded so far and start a new recording.
+    pub fn revert(&mut self) -> Result<(), S::DataError> {
+        let diff = self.take_diff();
+        diff.revert(&mut self.storage)
+    }
+}
+
+impl<S> StorageInspect<ContractsRawCode> for RecordingStorage<S>
+where
+    S: InterpreterStorage,
+{
+    type Error = S::DataError;
+
+    fn get(&self, key: &ContractId) -> Result<Option<Cow<'_, Contract>>, Self::Error> {
+        StorageInspect::<ContractsRawCode>::get(&self.storage, key)
+    }
+
+    fn contains_key(&self, key: &ContractId) -> Result<bool, Self::Error> {
+        StorageInspect::<ContractsRawCode>::contains_key(&self.storage, key)
+    }
+}
+
+impl<S> StorageMutate<ContractsRawCode> for RecordingStorage<S>
+where
+    S: InterpreterStorage,
+{
+    fn insert(
+        &mut self,
+        key: &ContractId,
+        value: &[u8],
+    ) -> Result<Option<Contract>, Self::Error> {
+        let before =
+            StorageMutate::<ContractsRawCode>::insert(&mut self.storage, key, value)?;
+        record(
+            &mut self.diff.contracts,
+            *key,
+            before.clone(),
+            Some(value.into()),
+        );
+        Ok(before)
+    }
+
+    fn remove(&mut self, key: &ContractId) -> Result<Option<Contract>, Self::Error> {
+        let before = StorageMutate::<ContractsRawCode>::remove(&mut self.storage, key)?;
+        record(&mut self.diff.contracts, *key, before.clone(), None);
+        Ok(before)
+    }
+}
+
+impl<S> StorageSize<ContractsRawCode> for RecordingStorage<S>
+where
+    S: InterpreterStorage,
+{
+    fn size_of_value(&self, key: &ContractId) -> Result<Option<usize>, Self::Error> {
+        StorageSize::<ContractsRawCode>::size_of_value(&self.storage, key)
+    }
+}
+
+impl<S> StorageRead<ContractsRawCode> for RecordingStorage<S>
+where
+    S: InterpreterStorage,
+{
+    fn read(
+        &self,
+        key: &ContractId,
+        buf: &mut [u8],
+    ) -> Result<Option<usize>, Self::Error> {
+        StorageRead::<ContractsRawCode>::read(&self.storage, key, buf)
+    }
+
+    fn read_alloc(&self, key: &ContractId) -> Result<Option<Vec<u8>>, Self::Error> {
+        StorageRead::<ContractsRawCode>::read_alloc(&self.storage, key)
+    }
+}
+
+impl<S> StorageInspect<ContractsInfo> for RecordingStorage<S>
+where
+    S: InterpreterStorage,
+{
+    type Error = S::DataError;
+
+    fn get(
+        &self,
+        key: &ContractId,
+    ) -> Result<Option<Cow<'_, (Salt, Bytes32)>>, Self::Error> {
+        StorageInspect::<ContractsInfo>::get(&self.storage, key)
+    }
+
+    fn contains_key(&self, key: &ContractId) -> Result<bool, Self::Error> {
+        StorageInspect::<ContractsInfo>::contains_key(&self.storage, key)
+    }
+}
+
+impl<S> StorageMutate<ContractsInfo> for RecordingStorage<S>
+where
+    S: InterpreterStorage,
+{
+    fn insert(
+        &mut self,
+        key: &ContractId,
+        value: &(Salt, Bytes32),
+    ) -> Result<Option<(Salt, Bytes32)>, Self::Error> {
+        let before =
+            StorageMutate::<ContractsInfo>::insert(&mut self.storage, key, value)?;
+        record(&mut self.diff.contracts_info, *key, before, Some(*value));
+        Ok(before)
+    }
+
+    fn remove(
+        &mut self,
+        key: &ContractId,
+    ) -> Result<Option<(Salt, Bytes32)>, Self::Error> {
+        let before = StorageMutate::<ContractsInfo>::remove(&mut self.storage, key)?;
+        record(&mut self.diff.contracts_info, *key, before, None);
+        Ok(before)
+    }
+}
+
+impl<S> StorageInspect<ContractsAssets> for RecordingStorage<S>
+where
+    S: InterpreterStorage,
+{
+    type Error = S::DataError;
+
+    fn get(
+        &self,
+        key: &<ContractsAssets as Mappable>::Key,
+    ) -> Result<Option<Cow<'_, Word>>, Self::Error> {
+        StorageInspect::<ContractsAssets>::get(&self.storage, key)
+    }
+
+    fn contains_key(
+        &self,
+        key: &<ContractsAssets as Mappable>::Key,
+    ) -> Result<bool, Self::Error> {
+        StorageInspect::<ContractsAssets>::contains_key(&self.storage, key)
+    }
+}
+
+impl<S> StorageMutate<ContractsAssets> for RecordingStorage<S>
+where
+    S: InterpreterStorage,
+{
+    fn insert(
+        &mut self,
+        key: &<ContractsAssets as Mappable>::Key,
+        value: &Word,
+    ) -> Result<Option<Word>, Self::Error> {
+        let before =
+            StorageMutate::<ContractsAssets>::insert(&mut self.storage, key, value)?;
+        record(&mut self.diff.balances, *key, before, Some(*value));
+        Ok(before)
+    }
+
+    fn remove(
+        &mut self,
+        key: &<ContractsAssets as Mappable>::Key,
+    ) -> Result<Option<Word>, Self::Error> {
+        let before = StorageMutate::<ContractsAssets>::remove(&mut self.storage, key)?;
+        record(&mut self.diff.balances, *key, before, None);
+        Ok(before)
+    }
+}
+
+impl<S> MerkleRootStorage<ContractId, ContractsAssets> for RecordingStorage<S>
+where
+    S: InterpreterStorage,
+{
+    fn root(&self, parent: &ContractId) -> Result<MerkleRoot, Self::Error> {
+        MerkleRootStorage::<ContractId, ContractsAssets>::root(&self.storage, parent)
+    }
+}
+
+impl<S> StorageInspect<ContractsState> for RecordingStorage<S>
+where
+    S: InterpreterStorage,
+{
+    type Error = S::DataError;
+
+    fn get(
+        &self,
+        key: &<ContractsState as Mappable>::Key,
+    ) -> Result<Option<Cow<'_, Bytes32>>, Self::Error> {
+        StorageInspect::<ContractsState>::get(&self.storage, key)
+    }
+
+    fn contains_key(
+        &self,
+        key: &<ContractsState as Mappable>::Key,
+    ) -> Result<bool, Self::Error> {
+        StorageInspect::<ContractsState>::contains_key(&self.storage, key)
+    }
+}
+
+impl<S> StorageMutate<ContractsState> for RecordingStorage<S>
+where
+    S: InterpreterStorage,
+{
+    fn insert(
+        &mut self,
+        key: &<ContractsState as Mappable>::Key,
+        value: &Bytes32,
+    ) -> Result<Option<Bytes32>, Self::Error> {
+        let before =
+            StorageMutate::<ContractsState>::insert(&mut self.storage, key, value)?;
+        record(&mut self.diff.state, *key, before, Some(*value));
+        Ok(before)
+    }
+
+    fn remove(
+        &mut self,
+        key: &<ContractsState as Mappable>::Key,
+    ) -> Result<Option<Bytes32>, Self::Error> {
+        let before = StorageMutate::<ContractsState>::remove(&mut self.storage, key)?;
+        record(&mut self.diff.state, *key, before, None);
+        Ok(before)
+    }
+}
+
+impl<S> MerkleRootStorage<ContractId, ContractsState> for RecordingStorage<S>
+where
+    S: InterpreterStorage,
+{
+    fn root(&self, parent: &ContractId) -> Result<MerkleRoot, Self::Error> {
+        MerkleRootStorage::<ContractId, ContractsState>::root(&self.storage, parent)
+    }
+}
+
+impl<S> ContractsAssetsStorage for RecordingStorage<S> where S: InterpreterStorage {}
+
+impl<S> InterpreterStorage for RecordingStorage<S>
+where
+    S: InterpreterStorage,
+{
+    type DataError = S::DataError;
+
+    fn block_height(&self) -> Result<BlockHeight, Self::DataError> {
+        self.storage.block_height()
+    }
+
+    fn timestamp(&self, height: BlockHeight) -> Result<Word, Self::DataError> {
+        self.storage.timestamp(height)
+    }
+
+    fn block_hash(&self, block_height: BlockHeight) -> Result<Bytes32, Self::DataError> {
+        self.storage.block_hash(block_height)
+    }
+
+    fn coinbase(&self) -> Result<ContractId, Self::DataError> {
+        self.storage.coinbase()
+    }
+
+    fn merkle_contract_state_range(
+        &self,
+        id: &ContractId,
+        start_key: &Bytes32,
+        range: Word,
+    ) -> Result<Vec<Option<Cow<'_, Bytes32>>>, Self::DataError> {
+        self.storage
+            .merkle_contract_state_range(id, start_key, range)
+    }
+
+    fn merkle_contract_state_insert_range(
+        &mut self,
+        contract: &ContractId,
+        start_key: &Bytes32,
+        values: &[Bytes32],
+    ) -> Result<Option<()>, Self::DataError> {
+        let before = self.owned_state_range(contract, start_key, values.len() as Word)?;
+        let result = self
+            .storage
+            .merkle_contract_state_insert_range(contract, start_key, values)?;
+
+        for ((key, before), value) in state_keys(start_key).zip(before).zip(values) {
+            let key = (contract, &key).into();
+            record(&mut self.diff.state, key, before, Some(*value));
+        }
+
+        Ok(result)
+    }
+
+    fn merkle_contract_state_remove_range(
+        &mut self,
+        contract: &ContractId,
+        start_key: &Bytes32,
+        range: Word,
+    ) -> Result<Option<()>, Self::DataError> {
+        let before = self.owned_state_range(contract, start_key, range)?;
+        let result = self
+            .storage
+            .merkle_contract_state_remove_range(contract, start_key, range)?;
+
+        for (key, before) in state_keys(start_key).zip(before) {
+            let key = (contract, &key).into();
+            record(&mut self.diff.state, key, before, None);
+        }
+
+        Ok(result)
+    }
+}
+
+impl<S> RecordingStorage<S>
+where
+    S: InterpreterStorage,
+{
+    fn owned_state_range(
+        &self,
+        contract: &ContractId,
+        start_key: &Bytes32,
+        range: Word,
+    ) -> Result<Vec<Option<Bytes32>>, S::DataError> {
+        Ok(self
+            .storage
+            .merkle_contract_state_range(contract, start_key, range)?
+            .into_iter()
+            .map(|value| value.map(Cow::into_owned))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use alloc::vec;
+    use fuel_types::AssetId;
+
+    fn state_key(k: u8) -> Bytes32 {
+        let mut key = Bytes32::zeroed();
+        key[31] = k;
+        key
+    }
+
+    #[test]
+    fn writes_are_recorded_with_their_original_value() {
+        let contract = ContractId::from([1u8; 32]);
+        let asset = AssetId::from([2u8; 32]);
+
+        let mut memory = MemoryStorage::default();
+        memory
+            .merkle_contract_state_insert(&contract, &state_key(0), &[1u8; 32].into())
+            .unwrap();
+
+        let mut storage = RecordingStorage::new(memory);
+        storage
+            .merkle_contract_state_insert(&contract, &state_key(0), &[2u8; 32].into())
+            .unwrap();
+        storage
+            .merkle_contract_state_insert(&contract, &state_key(0), &[3u8; 32].into())
+            .unwrap();
+        storage
+            .merkle_contract_asset_id_balance_insert(&contract, &asset, 10)
+            .unwrap();
+        storage
+            .merkle_contract_state_insert_range(
+                &contract,
+                &state_key(1),
+                &[[4u8; 32].into(), [5u8; 32].into()],
+            )
+            .unwrap();
+        storage
+            .merkle_contract_state_remove_range(&contract, &state_key(2), 1)
+            .unwrap();
+
+        let diff = storage.diff();
+        assert_eq!(diff.state.len(), 2);
+        assert_eq!(
+            diff.state[&(&contract, &state_key(0)).into()],
+            StorageChange {
+                before: Some([1u8; 32].into()),
+                after: Some([3u8; 32].into()),
+            }
+        );
+        assert_eq!(
+            diff.state[&(&contract, &state_key(1)).into()],
+            StorageChange {
+                before: None,
+                after: Some([4u8; 32].into()),
+            }
+        );
+        assert_eq!(
+            diff.balances[&(&contract, &asset).into()],
+            StorageChange {
+                before: None,
+                after: Some(10),
+            }
+        );
+    }
+
+    #[test]
+    fn revert_restores_the_original_values() {
+        let contract = ContractId::from([1u8; 32]);
+        let asset = AssetId::from([2u8; 32]);
+
+        let mut memory = MemoryStorage::default();
+        memory
+            .merkle_contract_state_insert(&contract, &state_key(0), &[1u8; 32].into())
+            .unwrap();
+        memory
+            .merkle_contract_asset_id_balance_insert(&contract, &asset, 5)
+            .unwrap();
+
+        let mut storage = RecordingStorage::new(memory);
+        storage
+            .merkle_contract_state_remove(&contract, &state_key(0))
+            .unwrap();
+        storage
+            .merkle_contract_state_insert(&contract, &state_key(1), &[2u8; 32].into())
+            .unwrap();
+        storage
+            .merkle_contract_asset_id_balance_insert(&contract, &asset, 15)
+            .unwrap();
+        storage
+            .storage_contract_insert(&contract, &vec![1u8; 4].into())
+            .unwrap();
+        assert!(!storage.diff().is_empty());
+
+        storage.revert().unwrap();
+
+        assert!(storage.diff().is_empty());
+        let memory = storage.into_inner();
+        assert_eq!(
+            memory
+                .merkle_contract_state(&contract, &state_key(0))
+                .unwrap()
+                .map(Cow::into_owned),
+            Some([1u8; 32].into())
+        );
+        assert!(memory
+            .merkle_contract_state(&contract, &state_key(1))
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            memory
+                .merkle_contract_asset_id_balance(&contract, &asset)
+                .unwrap(),
+            Some(5)
+        );
+        assert!(!memory.storage_contract_exists(&contract).unwrap());
+    }
+}
@@ -0,0 +1,821 @@
+use crate::{
+    crypto,
+    storage::{
+        ContractsAssetKey,
+        ContractsAssets,
+        ContractsInfo,
+        ContractsRawCode,
+        ContractsState,
+        ContractsStateKey,
+        InterpreterStorage,
+    },
+};
+
+use fuel_crypto::Hasher;
+use fuel_storage::{
+    Mappable,
+    MerkleRoot,
+    MerkleRootStorage,
+    StorageInspect,
+    StorageMutate,
+    StorageRead,
+    StorageSize,
+    StorageWrite,
+};
+use fuel_tx::Contract;
+use fuel_types::{
+    AssetId,
+    BlockHeight,
+    Bytes32,
+    ContractId,
+    Salt,
+    Word,
+};
+use tai64::Tai64;
+
+use alloc::{
+    borrow::Cow,
+    collections::BTreeMap,
+    vec::Vec,
+};
+use core::{
+    convert::Infallible,
+    ops::RangeInclusive,
+};
+
+use super::{
+    interpreter::ContractsAssetsStorage,
+    memory::state_keys,
+};
+
+/// A table made of a base map and a stack of copy-on-write overlays.
+///
+/// An overlay records only the entries changed since it was opened; `None` marks a
+/// removed entry. Reads go through the overlays from the newest to the oldest before
+/// falling back to the base map.
+#[derive(Debug, Clone)]
+struct VersionedTable<K, V> {
+    base: BTreeMap<K, V>,
+    overlays: Vec<BTreeMap<K, Option<V>>>,
+}
+
+impl<K, V> Default for VersionedTable<K, V> {
+    fn default() -> Self {
+        Self {
+            base: BTreeMap::new(),
+            overlays: Vec::new(),
+        }
+    }
+}
+
+impl<K, V> VersionedTable<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    fn get(&self, key: &K) -> Option<&V> {
+        self.overlays
+            .iter()
+            .rev()
+            .find_map(|overlay| overlay.get(key))
+            .map_or_else(|| self.base.get(key), Option::as_ref)
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if self.overlays.is_empty() {
+            return self.base.insert(key, value)
+        }
+
+        let previous = self.get(&key).cloned();
+        self.overlays
+            .last_mut()
+            .expect("The overlay is present")
+            .insert(key, Some(value));
+        previous
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        if self.overlays.is_empty() {
+            return self.base.remove(key)
+        }
+
+        let previous = self.get(key).cloned();
+        if previous.is_some() {
+            self.overlays
+                .last_mut()
+                .expect("The overlay is present")
+                .insert(key.clone(), None);
+        }
+        previous
+    }
+
+    /// The entries within `range`, with all overlays applied.
+    fn range(&self, range: RangeInclusive<K>) -> BTreeMap<&K, &V> {
+        let mut entries: BTreeMap<_, _> = self.base.range(range.clone()).collect();
+        for overlay in self.overlays.iter() {
+            for (key, value) in overlay.range(range.clone()) {
+                match value {
+                    Some(value) => entries.insert(key, value),
+                    None => entries.remove(key),
+                };
+            }
+        }
+        entries
+    }
+
+    fn checkpoint(&mut self) {
+        self.overlays.push(BTreeMap::new());
+    }
+
+    fn commit(&mut self) {
+        let Some(overlay) = self.overlays.pop() else {
+            return
+        };
+
+        match self.overlays.last_mut() {
+            Some(parent) => parent.extend(overlay),
+            None => overlay.into_iter().for_each(|(key, value)| match value {
+                Some(value) => {
+                    self.base.insert(key, value);
+                }
+                None => {
+                    self.base.remove(&key);
+                }
+            }),
+        }
+    }
+
+    fn revert(&mut self) {
+        self.overlays.pop();
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct VersionedStorageInner {
+    contracts: VersionedTable<ContractId, Contract>,
+    balances: VersionedTable<ContractsAssetKey, Word>,
+    contract_state: VersionedTable<ContractsStateKey, Bytes32>,
+    contract_code_root: VersionedTable<ContractId, (Salt, Bytes32)>,
+}
+
+macro_rules! for_each_table {
+    ($inner:expr, $method:ident) => {{
+        $inner.contracts.$method();
+        $inner.balances.$method();
+        $inner.contract_state.$method();
+        $inner.contract_code_root.$method();
+    }};
+}
+
+#[derive(Debug, Clone)]
+/// In-memory storage implementation for the interpreter with copy-on-write
+/// checkpoints.
+///
+/// Unlike [`MemoryStorage`](super::MemoryStorage), which copies its whole state to
+/// move between its fixed levels, this storage stacks overlays on top of a base
+/// state:
+///
+/// - [`Self::checkpoint`] opens a new overlay that receives all subsequent changes.
+/// - [`Self::commit`] merges the newest overlay into the one below it, or into the base
+///   state if it is the only one.
+/// - [`Self::revert`] discards the newest overlay and its changes.
+///
+/// The cost of these operations is proportional to the number of entries changed
+/// in the overlay, not to the size of the state. Changes made while no checkpoint
+/// is open are applied directly to the base state.
+pub struct VersionedStorage {
+    block_height: BlockHeight,
+    coinbase: ContractId,
+    inner: VersionedStorageInner,
+    checkpoints: usize,
+}
+
+impl VersionedStorage {
+    /// Create a new versioned storage.
+    pub fn new(block_height: BlockHeight, coinbase: ContractId) -> Self {
+        Self {
+            block_height,
+            coinbase,
+            inner: Default::default(),
+            checkpoints: 0,
+        }
+    }
+
+    /// The number of open checkpoints.
+    pub fn checkpoints(&self) -> usize {
+        self.checkpoints
+    }
+
+    /// Open a new checkpoint. Returns the number of open checkpoints, including the
+    /// new one.
+    pub fn checkpoint(&mut self) -> usize {
+        for_each_table!(self.inner, checkpoint);
+        self.checkpoints += 1;
+        self.checkpoints
+    }
+
+    /// Merge the changes of the newest checkpoint into its parent, and close it.
+    ///
+    /// Does nothing if there are no open checkpoints.
+    pub fn commit(&mut self) {
+        if self.checkpoints > 0 {
+            for_each_table!(self.inner, commit);
+            self.checkpoints -= 1;
+        }
+    }
+
+    /// Discard the changes of the newest checkpoint, and close it.
+    ///
+    /// Does nothing if there are no open checkpoints.
+    pub fn revert(&mut self) {
+        if self.checkpoints > 0 {
+            for_each_table!(self.inner, revert);
+            self.checkpoints -= 1;
+        }
+    }
+
+    /// Merge the changes of all open checkpoints into the base state, and close them.
+    pub fn persist(&mut self) {
+        while self.checkpoints > 0 {
+            self.commit();
+        }
+    }
+
+    /// Fetch a mapping from the contract state.
+    pub fn contract_state(
+        &self,
+        contract: &ContractId,
+        key: &Bytes32,
+    ) -> Cow<'_, Bytes32> {
+        const DEFAULT_STATE: Bytes32 = Bytes32::zeroed();
+
+        self.inner
+            .contract_state
+            .get(&(contract, key).into())
+            .map(Cow::Borrowed)
+            .unwrap_or(Cow::Borrowed(&DEFAULT_STATE))
+    }
+
+    /// Fetch the balance of an asset from a contract.
+    pub fn contract_balance(&self, contract: &ContractId, asset_id: &AssetId) -> Word {
+        self.inner
+            .balances
+            .get(&(contract, asset_id).into())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    #[cfg(feature = "test-helpers")]
+    /// Set the block height of the chain
+    pub fn set_block_height(&mut self, block_height: BlockHeight) {
+        self.block_height = block_height;
+    }
+}
+
+impl Default for VersionedStorage {
+    fn default() -> Self {
+        let block_height = 1.into();
+        let coinbase = ContractId::from(*Hasher::hash(b"coinbase"));
+
+        Self::new(block_height, coinbase)
+    }
+}
+
+impl StorageInspect<ContractsRawCode> for VersionedStorage {
+    type Error = Infallible;
+
+    fn get(&self, key: &ContractId) -> Result<Option<Cow<'_, Contract>>, Infallible> {
+        Ok(self.inner.contracts.get(key).map(Cow::Borrowed))
+    }
+
+    fn contains_key(&self, key: &ContractId) -> Result<bool, Infallible> {
+        Ok(self.inner.contracts.contains_key(key))
+    }
+}
+
+impl StorageMutate<ContractsRawCode> for VersionedStorage {
+    fn insert(
+        &mut self,
+        key: &ContractId,
+        value: &[u8],
+    ) -> Result<Option<Contract>, Infallible> {
+        Ok(self.inner.contracts.insert(*key, value.into()))
+    }
+
+    fn remove(&mut self, key: &ContractId) -> Result<Option<Contract>, Infallible> {
+        Ok(self.inner.contracts.remove(key))
+    }
+}
+
+impl StorageWrite<ContractsRawCode> for VersionedStorage {
+    fn write(&mut self, key: &ContractId, buf: Vec<u8>) -> Result<usize, Infallible> {
+        let size = buf.len();
+        self.inner.contracts.insert(*key, Contract::from(buf));
+        Ok(size)
+    }
+
+    fn replace(
+        &mut self,
+        key: &<ContractsRawCode as Mappable>::Key,
+        buf: Vec<u8>,
+    ) -> Result<(usize, Option<Vec<u8>>), Self::Error>
+    where
+        Self: StorageSize<ContractsRawCode>,
+    {
+        let size = buf.len();
+        let last = self.inner.contracts.insert(*key, Contract::from(buf));
+        Ok((size, last.map(Vec::from)))
+    }
+
+    fn take(
+        &mut self,
+        key: &<ContractsRawCode as Mappable>::Key,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self.inner.contracts.remove(key).map(Vec::from))
+    }
+}
+
+impl StorageSize<ContractsRawCode> for VersionedStorage {
+    fn size_of_value(&self, key: &ContractId) -> Result<Option<usize>, Infallible> {
+        Ok(self.inner.contracts.get(key).map(|c| c.as_ref().len()))
+    }
+}
+
+impl StorageRead<ContractsRawCode> for VersionedStorage {
+    fn read(
+        &self,
+        key: &ContractId,
+        buf: &mut [u8],
+    ) -> Result<Option<usize>, Self::Error> {
+        Ok(self.inner.contracts.get(key).map(|c| {
+            let len = buf.len().min(c.as_ref().len());
+            buf[..len].copy_from_slice(&c.as_ref()[..len]);
+            len
+        }))
+    }
+
+    fn read_alloc(&self, key: &ContractId) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self.inner.contracts.get(key).map(|c| c.as_ref().to_vec()))
+    }
+}
+
+impl StorageInspect<ContractsInfo> for VersionedStorage {
+    type Error = Infallible;
+
+    fn get(
+        &self,
+        key: &ContractId,
+    ) -> Result<Option<Cow<'_, (Salt, Bytes32)>>, Infallible> {
+        Ok(self.inner.contract_code_root.get(key).map(Cow::Borrowed))
+    }
+
+    fn contains_key(&self, key: &ContractId) -> Result<bool, Infallible> {
+        Ok(self.inner.contract_code_root.contains_key(key))
+    }
+}
+
+impl StorageMutate<ContractsInfo> for VersionedStorage {
+    fn insert(
+        &mut self,
+        key: &ContractId,
+        value: &(Salt, Bytes32),
+    ) -> Result<Option<(Salt, Bytes32)>, Infallible> {
+        Ok(self.inner.contract_code_root.insert(*key, *value))
+    }
+
+    fn remove(
+        &mut self,
+        key: &ContractId,
+    ) -> Result<Option<(Salt, Bytes32)>, Infallible> {
+        Ok(self.inner.contract_code_root.remove(key))
+    }
+}
+
+impl StorageInspect<ContractsAssets> for VersionedStorage {
+    type Error = Infallible;
+
+    fn get(
+        &self,
+        key: &<ContractsAssets as Mappable>::Key,
+    ) -> Result<Option<Cow<'_, Word>>, Infallible> {
+        Ok(self.inner.balances.get(key).map(Cow::Borrowed))
+    }
+
+    fn contains_key(
+        &self,
+        key: &<ContractsAssets as Mappable>::Key,
+    ) -> Result<bool, Infallible> {
+        Ok(self.inner.balances.contains_key(key))
+    }
+}
+
+impl StorageMutate<ContractsAssets> for VersionedStorage {
+    fn insert(
+        &mut self,
+        key: &<ContractsAssets as Mappable>::Key,
+        value: &Word,
+    ) -> Result<Option<Word>, Infallible> {
+        Ok(self.inner.balances.insert(*key, *value))
+    }
+
+    fn remove(
+        &mut self,
+        key: &<ContractsAssets as Mappable>::Key,
+    ) -> Result<Option<Word>, Infallible> {
+        Ok(self.inner.balances.remove(key))
+    }
+}
+
+impl MerkleRootStorage<ContractId, ContractsAssets> for VersionedStorage {
+    fn root(&self, parent: &ContractId) -> Result<MerkleRoot, Infallible> {
+        let start = (parent, &AssetId::zeroed()).into();
+        let end = (parent, &AssetId::new([u8::MAX; 32])).into();
+        let root = self
+            .inner
+            .balances
+            .range(start..=end)
+            .into_values()
+            .map(|balance| balance.to_be_bytes());
+
+        Ok(crypto::ephemeral_merkle_root(root).into())
+    }
+}
+
+impl StorageInspect<ContractsState> for VersionedStorage {
+    type Error = Infallible;
+
+    fn get(
+        &self,
+        key: &<ContractsState as Mappable>::Key,
+    ) -> Result<Option<Cow<'_, Bytes32>>, Infallible> {
+        Ok(self.inner.contract_state.get(key).map(Cow::Borrowed))
+    }
+
+    fn contains_key(
+        &self,
+        key: &<ContractsState as Mappable>::Key,
+    ) -> Result<bool, Infallible> {
+        Ok(self.inner.contract_state.contains_key(key))
+    }
+}
+
+impl StorageMutate<ContractsState> for VersionedStorage {
+    fn insert(
+        &mut self,
+        key: &<ContractsState as Mappable>::Key,
+        value: &Bytes32,
+    ) -> Result<Option<Bytes32>, Infallible> {
+        Ok(self.inner.contract_state.insert(*key, *value))
+    }
+
+    fn remove(
+        &mut self,
+        key: &<ContractsState as Mappable>::Key,
+    ) -> Result<Option<Bytes32>, Infallible> {
+        Ok(self.inner.contract_state.remove(key))
+    }
+}
+
+impl MerkleRootStorage<ContractId, ContractsState> for VersionedStorage {
+    fn root(&self, parent: &ContractId) -> Result<MerkleRoot, Infallible> {
+        let start = (parent, &Bytes32::zeroed()).into();
+        let end = (parent, &Bytes32::new([u8::MAX; 32])).into();
+        let root = self.inner.contract_state.range(start..=end).into_values();
+
+        Ok(crypto::ephemeral_merkle_root(root).into())
+    }
+}
+
+impl ContractsAssetsStorage for VersionedStorage {}
+
+impl InterpreterStorage for VersionedStorage {
+    type DataError = Infallible;
+
+    fn block_height(&self) -> Result<BlockHeight, Infallible> {
+        Ok(self.block_height)
+    }
+
+    fn timestamp(&self, height: BlockHeight) -> Result<Word, Self::DataError> {
+        const GENESIS: Tai64 = Tai64::UNIX_EPOCH;
+        const INTERVAL: Word = 10;
+
+        Ok((GENESIS + (*height as Word * INTERVAL)).0)
+    }
+
+    fn block_hash(&self, block_height: BlockHeight) -> Result<Bytes32, Infallible> {
+        Ok(Hasher::hash(block_height.to_be_bytes()))
+    }
+
+    fn coinbase(&self) -> Result<ContractId, Infallible> {
+        Ok(self.coinbase)
+    }
+
+    fn merkle_contract_state_range(
+        &self,
+        id: &ContractId,
+        start_key: &Bytes32,
+        range: Word,
+    ) -> Result<Vec<Option<Cow<'_, Bytes32>>>, Self::DataError> {
+        Ok(state_keys(start_key)
+            .take(range as usize)
+            .map(|key| {
+                self.inner
+                    .contract_state
+                    .get(&(id, &key).into())
+                    .map(Cow::Borrowed)
+            })
+            .collect())
+    }
+
+    fn merkle_contract_state_insert_range(
+        &mut self,
+        contract: &ContractId,
+        start_key: &Bytes32,
+        values: &[Bytes32],
+    ) -> Result<Option<()>, Self::DataError> {
+        let mut any_unset_key = false;
+        for (key, value) in state_keys(start_key).zip(values) {
+            let key = (contract, &key).into();
+            any_unset_key |= self.inner.contract_state.insert(key, *value).is_none();
+        }
+        Ok((!any_unset_key).then_some(()))
+    }
+
+    fn merkle_contract_state_remove_range(
+        &mut self,
+        contract: &ContractId,
+        start_key: &Bytes32,
+        range: Word,
+    ) -> Result<Option<()>, Self::DataError> {
+        let mut all_set_key = true;
+        for key in state_keys(start_key).take(range as usize) {
+            let key = (contract, &key).into();
+            all_set_key &= self.inner.contract_state.remove(&key).is_some();
+        }
+        Ok(all_set_key.then_some(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        interpreter::InterpreterParams,
+        prelude::*,
+        storage::MemoryStorage,
+    };
+    use alloc::vec;
+    use fuel_asm::{
+        op,
+        RegId,
+    };
+    use fuel_tx::{
+        ConsensusParameters,
+        TransactionBuilder,
+    };
+
+    fn state_key(k: u8) -> Bytes32 {
+        let mut key = Bytes32::zeroed();
+        key[31] = k;
+        key
+    }
+
+    #[test]
+    fn revert_discards_only_the_newest_checkpoint() {
+        let mut storage = VersionedStorage::default();
+        let contract = ContractId::zeroed();
+
+        storage
+            .merkle_contract_state_insert(&contract, &state_key(0), &state_key(1))
+            .unwrap();
+        assert_eq!(storage.checkpoint(), 1);
+        storage
+            .merkle_contract_state_insert(&contract, &state_key(0), &state_key(2))
+            .unwrap();
+        storage
+            .merkle_contract_state_insert(&contract, &state_key(1), &state_key(2))
+            .unwrap();
+        assert_eq!(storage.checkpoint(), 2);
+        storage
+            .merkle_contract_state_remove(&contract, &state_key(0))
+            .unwrap();
+        assert!(!storage
+            .storage::<ContractsState>()
+            .contains_key(&(&contract, &state_key(0)).into())
+            .unwrap());
+
+        storage.revert();
+        assert_eq!(storage.checkpoints(), 1);
+        assert_eq!(
+            *storage.contract_state(&contract, &state_key(0)),
+            state_key(2)
+        );
+        assert_eq!(
+            *storage.contract_state(&contract, &state_key(1)),
+            state_key(2)
+        );
+
+        storage.revert();
+        assert_eq!(storage.checkpoints(), 0);
+        assert_eq!(
+            *storage.contract_state(&contract, &state_key(0)),
+            state_key(1)
+        );
+        assert!(storage
+            .merkle_contract_state(&contract, &state_key(1))
+            .unwrap()
+            .is_none());
+
+        // Reverting without open checkpoints does nothing.
+        storage.revert();
+        assert_eq!(
+            *storage.contract_state(&contract, &state_key(0)),
+            state_key(1)
+        );
+    }
+
+    #[test]
+    fn commit_merges_the_newest_checkpoint_into_its_parent() {
+        let mut storage = VersionedStorage::default();
+        let contract = ContractId::zeroed();
+        let asset_id = AssetId::zeroed();
+
+        storage
+            .merkle_contract_asset_id_balance_insert(&contract, &asset_id, 10)
+            .unwrap();
+        storage.checkpoint();
+        storage
+            .merkle_contract_asset_id_balance_insert(&contract, &asset_id, 20)
+            .unwrap();
+        storage.checkpoint();
+        storage
+            .merkle_contract_asset_id_balance_insert(&contract, &asset_id, 30)
+            .unwrap();
+
+        storage.commit();
+        assert_eq!(storage.checkpoints(), 1);
+        assert_eq!(storage.contract_balance(&contract, &asset_id), 30);
+
+        // The parent checkpoint now holds the committed changes.
+        storage.revert();
+        assert_eq!(storage.contract_balance(&contract, &asset_id), 10);
+
+        storage.checkpoint();
+        storage.checkpoint();
+        storage
+            .merkle_contract_asset_id_balance_insert(&contract, &asset_id, 40)
+            .unwrap();
+        storage.persist();
+        assert_eq!(storage.checkpoints(), 0);
+        assert_eq!(storage.contract_balance(&contract, &asset_id), 40);
+    }
+
+    fn populate<S>(storage: &mut S, contract: &ContractId, other: &ContractId)
+    where
+        S: InterpreterStorage<DataError = Infallible>,
+    {
+        let values = [state_key(1), state_key(2), state_key(3)];
+        storage
+            .merkle_contract_state_insert_range(contract, &state_key(2), &values)
+            .unwrap();
+        storage
+            .merkle_contract_state_insert(other, &state_key(3), &state_key(4))
+            .unwrap();
+        storage
+            .merkle_contract_asset_id_balance_insert(contract, &AssetId::zeroed(), 5)
+            .unwrap();
+        storage
+            .merkle_contract_asset_id_balance_insert(other, &AssetId::zeroed(), 6)
+            .unwrap();
+    }
+
+    #[test]
+    fn state_ranges_and_roots_match_memory_storage() {
+        let mut versioned = VersionedStorage::default();
+        let mut memory = MemoryStorage::default();
+        let contract = ContractId::zeroed();
+        let other = ContractId::new([1; 32]);
+
+        populate(&mut versioned, &contract, &other);
+        populate(&mut memory, &contract, &other);
+        versioned.checkpoint();
+        versioned
+            .merkle_contract_state_remove(&contract, &state_key(3))
+            .unwrap();
+        memory
+            .merkle_contract_state_remove(&contract, &state_key(3))
+            .unwrap();
+
+        for (start, range) in [(0, 6), (2, 3), (3, 1), (4, 2)] {
+            assert_eq!(
+                versioned
+                    .merkle_contract_state_range(&contract, &state_key(start), range)
+                    .unwrap(),
+                memory
+                    .merkle_contract_state_range(&contract, &state_key(start), range)
+                    .unwrap()
+            );
+        }
+        for id in [contract, other] {
+            assert_eq!(
+                MerkleRootStorage::<ContractId, ContractsState>::root(&versioned, &id),
+                MerkleRootStorage::<ContractId, ContractsState>::root(&memory, &id),
+            );
+            assert_eq!(
+                MerkleRootStorage::<ContractId, ContractsAssets>::root(&versioned, &id),
+                MerkleRootStorage::<ContractId, ContractsAssets>::root(&memory, &id),
+            );
+        }
+    }
+
+    #[test]
+    fn state_range_operations_report_unset_keys() {
+        let mut storage = VersionedStorage::default();
+        let contract = ContractId::zeroed();
+        let values = [state_key(1), state_key(2)];
+
+        assert_eq!(
+            storage
+                .merkle_contract_state_insert_range(&contract, &state_key(0), &values)
+                .unwrap(),
+            None
+        );
+        storage.checkpoint();
+        assert_eq!(
+            storage
+                .merkle_contract_state_insert_range(&contract, &state_key(0), &values)
+                .unwrap(),
+            Some(())
+        );
+        assert_eq!(
+            storage
+                .merkle_contract_state_remove_range(&contract, &state_key(0), 2)
+                .unwrap(),
+            Some(())
+        );
+        assert_eq!(
+            storage
+                .merkle_contract_state_remove_range(&contract, &state_key(0), 2)
+                .unwrap(),
+            None
+        );
+
+        storage.revert();
+        assert_eq!(
+            storage
+                .merkle_contract_state_range(&contract, &state_key(0), 3)
+                .unwrap(),
+            vec![
+                Some(Cow::Borrowed(&values[0])),
+                Some(Cow::Borrowed(&values[1])),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn transactor_deploys_contracts_into_checkpoints() {
+        let consensus_params = ConsensusParameters::standard();
+        let interpreter_params = InterpreterParams::from(&consensus_params);
+
+        let program: Witness = vec![op::ret(RegId::ONE)]
+            .into_iter()
+            .collect::<Vec<u8>>()
+            .into();
+        let salt = Salt::zeroed();
+        let slots = vec![StorageSlot::new(state_key(1), state_key(2))];
+        let contract = Contract::from(program.as_ref());
+        let state_root = Contract::initial_state_root(slots.iter());
+        let id = contract.id(&salt, &contract.root(), &state_root);
+
+        let tx = TransactionBuilder::create(program, salt, slots)
+            .gas_limit(1_000_000)
+            .add_random_fee_input()
+            .add_output(Output::contract_created(id, state_root))
+            .finalize_checked(Default::default());
+
+        let mut storage = VersionedStorage::default();
+        storage.checkpoint();
+        let mut transactor =
+            Transactor::<_, Script>::new(&mut storage, interpreter_params.clone());
+        assert!(transactor.deploy(tx.clone()).is_ok());
+        assert!(storage.storage_contract_exists(&id).unwrap());
+        assert_eq!(*storage.contract_state(&id, &state_key(1)), state_key(2));
+
+        storage.revert();
+        assert!(!storage.storage_contract_exists(&id).unwrap());
+        assert_eq!(
+            *storage.contract_state(&id, &state_key(1)),
+            Bytes32::zeroed()
+        );
+
+        let mut transactor =
+            Transactor::<_, Script>::new(&mut storage, interpreter_params);
+        assert!(transactor.deploy(tx).is_ok());
+        assert!(storage.storage_contract_exists(&id).unwrap());
+    }
+}
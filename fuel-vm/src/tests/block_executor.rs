@@ -0,0 +1,424 @@
+use alloc::{
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    block_executor::{
+        BlockExecutor,
+        BlockExecutorError,
+    },
+    checked_transaction::{
+        Checked,
+        CheckedTransaction,
+        IntoChecked,
+    },
+    crypto,
+    interpreter::InterpreterParams,
+    prelude::{
+        field::{
+            InputContract,
+            MintAmount,
+            MintAssetId,
+            OutputContract,
+            TxPointer as TxPointerField,
+        },
+        *,
+    },
+    script_with_data_offset,
+    storage::{
+        ContractsAssets,
+        ContractsAssetsStorage,
+        StorageChange,
+    },
+    util::test_helpers::TestBuilder,
+};
+use fuel_asm::{
+    op,
+    RegId,
+};
+use fuel_types::canonical::Serialize;
+use rand::{
+    rngs::StdRng,
+    Rng,
+    SeedableRng,
+};
+
+const GAS_PRICE: Word = 1;
+const GAS_LIMIT: Word = 1_000_000;
+
+fn consensus_params() -> ConsensusParameters {
+    let mut consensus_params = ConsensusParameters::standard();
+    consensus_params.fee_params = FeeParameters::default().with_gas_price_factor(1);
+    consensus_params
+}
+
+/// Script transferring `amount` of `asset_id` to `contract_id`.
+fn transfer(
+    test_context: &mut TestBuilder,
+    contract_id: ContractId,
+    asset_id: AssetId,
+    amount: Word,
+) -> Checked<Script> {
+    let (script, _) = script_with_data_offset!(
+        data_offset,
+        vec![
+            op::movi(0x10, data_offset as Immediate18),
+            op::movi(0x11, amount as Immediate18),
+            op::movi(0x12, (data_offset + 32) as Immediate18),
+            op::tr(0x10, 0x11, 0x12),
+            op::ret(RegId::ONE),
+        ],
+        test_context.get_tx_params().tx_offset()
+    );
+    let script_data = [contract_id.as_ref(), asset_id.as_ref()]
+        .into_iter()
+        .flatten()
+        .copied()
+        .collect();
+
+    test_context
+        .start_script(script, script_data)
+        .gas_price(GAS_PRICE)
+        .gas_limit(GAS_LIMIT)
+        .coin_input(asset_id, 1000)
+        .coin_input(AssetId::BASE, GAS_LIMIT * 10)
+        .contract_input(contract_id)
+        .contract_output(&contract_id)
+        .build()
+}
+
+fn create(rng: &mut StdRng, code: Vec<Instruction>) -> (Checked<Create>, ContractId) {
+    let program: Witness = code.into_iter().collect::<Vec<u8>>().into();
+    let salt: Salt = rng.gen();
+    let contract = Contract::from(program.as_ref());
+    let state_root = Contract::initial_state_root([].iter());
+    let contract_id = contract.id(&salt, &contract.root(), &state_root);
+
+    let tx = TransactionBuilder::create(program, salt, vec![])
+        .gas_price(GAS_PRICE)
+        .gas_limit(GAS_LIMIT)
+        .with_fee_params(consensus_params().fee_params)
+        .add_unsigned_coin_input(
+            SecretKey::random(rng),
+            rng.gen(),
+            GAS_LIMIT * 10,
+            AssetId::BASE,
+            Default::default(),
+            Default::default(),
+        )
+        .add_output(Output::contract_created(contract_id, state_root))
+        .finalize_checked(Default::default());
+
+    (tx, contract_id)
+}
+
+#[test]
+fn block_executor_runs_transactions_and_mints_fees() {
+    let mut rng = StdRng::seed_from_u64(2322u64);
+    let asset_id: AssetId = rng.gen();
+
+    let mut test_context = TestBuilder::new(2322u64);
+    test_context.with_fee_params(consensus_params().fee_params);
+    let target = test_context
+        .setup_contract(vec![op::ret(RegId::ONE)], None, None)
+        .contract_id;
+    let coinbase = test_context
+        .setup_contract(vec![op::ret(RegId::ONE)], None, None)
+        .contract_id;
+
+    let succeeding = transfer(&mut test_context, target, asset_id, 400);
+    // Overspend to cause a revert
+    let reverting = transfer(&mut test_context, target, asset_id, 1100);
+    let (deploy, deployed) = create(&mut rng, vec![op::noop(), op::ret(RegId::ONE)]);
+
+    let mut storage = test_context.get_storage().clone();
+    let height = storage.block_height().unwrap();
+    storage.set_block_coinbase(height, coinbase);
+
+    let mut executor =
+        BlockExecutor::new(&mut storage, InterpreterParams::from(consensus_params()));
+    let block = executor
+        .execute(vec![succeeding.into(), reverting.into(), deploy.into()])
+        .expect("The block should be executed");
+
+    assert_eq!(block.transactions.len(), 3);
+    assert!(!block.transactions[0].reverted());
+    assert!(block.transactions[1].reverted());
+    assert!(!block.transactions[2].reverted());
+
+    for executed in &block.transactions {
+        assert!(executed.fee > 0);
+        let receipts_root = crypto::ephemeral_merkle_root(
+            executed.receipts.iter().map(|r| r.clone().to_bytes()),
+        );
+        assert_eq!(executed.receipts_root, receipts_root);
+    }
+
+    let total_fees: Word = block.transactions.iter().map(|tx| tx.fee).sum();
+    assert_eq!(block.total_fees, total_fees);
+    assert_eq!(*block.mint.mint_amount(), total_fees);
+    assert_eq!(block.mint.mint_asset_id(), &AssetId::BASE);
+    assert_eq!(block.mint.tx_pointer(), &TxPointer::new(height, 3));
+    assert_eq!(block.mint.input_contract().contract_id, coinbase);
+
+    // Only the changes of the succeeding transactions and the mint are applied
+    assert_eq!(
+        storage
+            .merkle_contract_asset_id_balance(&target, &asset_id)
+            .unwrap(),
+        Some(400)
+    );
+    assert_eq!(
+        storage
+            .merkle_contract_asset_id_balance(&coinbase, &AssetId::BASE)
+            .unwrap(),
+        Some(total_fees)
+    );
+    assert!(storage.storage_contract_exists(&deployed).unwrap());
+
+    let balance_root: Bytes32 = storage
+        .storage::<ContractsAssets>()
+        .root(&coinbase)
+        .unwrap()
+        .into();
+    assert_eq!(block.mint.output_contract().balance_root, balance_root);
+
+    let diff = &block.storage_diff;
+    assert_eq!(diff.balances.len(), 2);
+    assert_eq!(
+        diff.balances[&(&target, &asset_id).into()],
+        StorageChange {
+            before: None,
+            after: Some(400),
+        }
+    );
+    assert_eq!(
+        diff.balances[&(&coinbase, &AssetId::BASE).into()],
+        StorageChange {
+            before: None,
+            after: Some(total_fees),
+        }
+    );
+    assert_eq!(diff.contracts.len(), 1);
+    assert!(diff.contracts.contains_key(&deployed));
+    assert!(diff.state.is_empty());
+}
+
+#[test]
+fn block_executor_restores_the_storage_on_failure() {
+    let mut rng = StdRng::seed_from_u64(2322u64);
+    let (deploy, deployed) = create(&mut rng, vec![op::ret(RegId::ONE)]);
+
+    let mut storage = MemoryStorage::default();
+    let mut executor =
+        BlockExecutor::new(&mut storage, InterpreterParams::from(consensus_params()));
+
+    // The second deployment of the same contract fails
+    let err = executor
+        .execute(vec![deploy.clone().into(), deploy.into()])
+        .expect_err("The block should fail");
+    assert!(matches!(
+        err,
+        BlockExecutorError::Transaction {
+            index: 1,
+            error: InterpreterError::Panic(PanicReason::ContractIdAlreadyDeployed),
+        }
+    ));
+
+    assert!(!storage.storage_contract_exists(&deployed).unwrap());
+    assert!(storage.storage_contract_root(&deployed).unwrap().is_none());
+}
+
+#[test]
+fn block_executor_rejects_mint_transactions() {
+    let height = 1.into();
+    let mint: Checked<Mint> = Transaction::mint(
+        TxPointer::new(height, 0),
+        Default::default(),
+        Default::default(),
+        0,
+        AssetId::BASE,
+    )
+    .into_checked(height, &consensus_params())
+    .expect("Failed to check the mint");
+
+    let mut executor =
+        BlockExecutor::new(MemoryStorage::default(), InterpreterParams::default());
+    let err = executor
+        .execute(vec![mint.into()])
+        .expect_err("The block should fail");
+    assert!(matches!(err, BlockExecutorError::UnexpectedMint(0)));
+}
+
+#[test]
+fn block_executor_parallel_execution_matches_sequential_execution() {
+    let mut rng = StdRng::seed_from_u64(2322u64);
+    let asset_id: AssetId = rng.gen();
+
+    let mut test_context = TestBuilder::new(2322u64);
+    test_context.with_fee_params(consensus_params().fee_params);
+    let target = test_context
+        .setup_contract(vec![op::ret(RegId::ONE)], None, None)
+        .contract_id;
+    let other = test_context
+        .setup_contract(vec![op::ret(RegId::ONE)], None, None)
+        .contract_id;
+    let coinbase = test_context
+        .setup_contract(vec![op::ret(RegId::ONE)], None, None)
+        .contract_id;
+
+    // The second transfer to `target` conflicts with the first one
+    let transactions: Vec<CheckedTransaction> = vec![
+        transfer(&mut test_context, target, asset_id, 400).into(),
+        transfer(&mut test_context, other, asset_id, 300).into(),
+        transfer(&mut test_context, target, asset_id, 200).into(),
+        transfer(&mut test_context, other, asset_id, 1100).into(),
+        create(&mut rng, vec![op::noop(), op::ret(RegId::ONE)])
+            .0
+            .into(),
+    ];
+
+    let mut storage = test_context.get_storage().clone();
+    let height = storage.block_height().unwrap();
+    storage.set_block_coinbase(height, coinbase);
+    let mut parallel_storage = storage.clone();
+
+    let sequential = BlockExecutor::new(&mut storage, consensus_params().into())
+        .execute(transactions.clone())
+        .expect("The block should be executed");
+    let parallel = BlockExecutor::new(&mut parallel_storage, consensus_params().into())
+        .execute_parallel(transactions)
+        .expect("The block should be executed");
+
+    assert_eq!(parallel, sequential);
+    assert!(parallel.transactions[3].reverted());
+    for contract_id in [target, other, coinbase] {
+        for asset_id in [asset_id, AssetId::BASE] {
+            assert_eq!(
+                parallel_storage
+                    .merkle_contract_asset_id_balance(&contract_id, &asset_id)
+                    .unwrap(),
+                storage
+                    .merkle_contract_asset_id_balance(&contract_id, &asset_id)
+                    .unwrap(),
+            );
+        }
+    }
+    assert_eq!(
+        parallel_storage
+            .merkle_contract_asset_id_balance(&target, &asset_id)
+            .unwrap(),
+        Some(600)
+    );
+}
+
+#[test]
+fn block_executor_parallel_execution_restores_the_storage_on_failure() {
+    let mut rng = StdRng::seed_from_u64(2322u64);
+    let (deploy, deployed) = create(&mut rng, vec![op::ret(RegId::ONE)]);
+
+    let mut storage = MemoryStorage::default();
+    let mut executor =
+        BlockExecutor::new(&mut storage, InterpreterParams::from(consensus_params()));
+
+    // The second deployment only fails once the first one is committed
+    let err = executor
+        .execute_parallel(vec![deploy.clone().into(), deploy.into()])
+        .expect_err("The block should fail");
+    assert!(matches!(
+        err,
+        BlockExecutorError::Transaction {
+            index: 1,
+            error: InterpreterError::Panic(PanicReason::ContractIdAlreadyDeployed),
+        }
+    ));
+
+    assert!(!storage.storage_contract_exists(&deployed).unwrap());
+}
+
+#[test]
+fn block_executor_parallel_execution_matches_sequential_state_clearing() {
+    let state_key = |n: u8| {
+        let mut key = Bytes32::zeroed();
+        key[31] = n;
+        key
+    };
+
+    let mut test_context = TestBuilder::new(2322u64);
+    test_context.with_fee_params(consensus_params().fee_params);
+    let slots = [0, 1, 5]
+        .into_iter()
+        .map(|n| StorageSlot::new(state_key(n), Bytes32::from([n + 1; 32])))
+        .collect();
+    let contract_id = test_context
+        .setup_contract(
+            vec![
+                // The keys 0 and 4
+                op::movi(0x10, 2 * Bytes32::LEN as u32),
+                op::aloc(0x10),
+                op::movi(0x10, 4),
+                op::sb(RegId::HP, 0x10, 2 * Bytes32::LEN as u16 - 1),
+                op::addi(0x11, RegId::HP, Bytes32::LEN as u16),
+                // Clear the set slots 0 and 1, then the unset slot 4 and the set slot 5
+                op::movi(0x12, 2),
+                op::scwq(RegId::HP, 0x13, 0x12),
+                op::scwq(0x11, 0x14, 0x12),
+                op::log(0x13, 0x14, RegId::ZERO, RegId::ZERO),
+                op::ret(RegId::ONE),
+            ],
+            None,
+            Some(slots),
+        )
+        .contract_id;
+    let coinbase = test_context
+        .setup_contract(vec![op::ret(RegId::ONE)], None, None)
+        .contract_id;
+
+    let (script, _) = script_with_data_offset!(
+        data_offset,
+        vec![
+            op::movi(0x10, data_offset as Immediate18),
+            op::call(0x10, RegId::ZERO, RegId::ZERO, RegId::CGAS),
+            op::ret(RegId::ONE),
+        ],
+        test_context.get_tx_params().tx_offset()
+    );
+    let clear: Checked<Script> = test_context
+        .start_script(script, Call::new(contract_id, 0, 0).to_bytes())
+        .gas_price(GAS_PRICE)
+        .gas_limit(GAS_LIMIT)
+        .coin_input(AssetId::BASE, GAS_LIMIT * 10)
+        .contract_input(contract_id)
+        .contract_output(&contract_id)
+        .build();
+
+    let mut storage = test_context.get_storage().clone();
+    let height = storage.block_height().unwrap();
+    storage.set_block_coinbase(height, coinbase);
+    let mut parallel_storage = storage.clone();
+
+    let sequential = BlockExecutor::new(&mut storage, consensus_params().into())
+        .execute(vec![clear.clone().into()])
+        .expect("The block should be executed");
+    let parallel = BlockExecutor::new(&mut parallel_storage, consensus_params().into())
+        .execute_parallel(vec![clear.into()])
+        .expect("The block should be executed");
+
+    assert_eq!(parallel, sequential);
+    let logged =
+        parallel.transactions[0]
+            .receipts
+            .iter()
+            .find_map(|receipt| match receipt {
+                Receipt::Log { ra, rb, .. } => Some((*ra, *rb)),
+                _ => None,
+            });
+    assert_eq!(logged, Some((1, 0)));
+    for n in [0, 1, 4, 5] {
+        assert!(parallel_storage
+            .merkle_contract_state(&contract_id, &state_key(n))
+            .unwrap()
+            .is_none());
+    }
+}
@@ -37,7 +37,10 @@ use crate::{
     prelude::*,
 };
 
-use crate::interpreter::InterpreterParams;
+use crate::{
+    interpreter::InterpreterParams,
+    storage::BlockHeader,
+};
 
 use crate::script_with_data_offset;
 use fuel_asm::{
@@ -1827,6 +1830,64 @@ fn block_hash_works(
     assert_eq!(data.as_ref().unwrap(), &*expected);
 }
 
+#[test]
+fn block_context_works() {
+    let mut client = MemoryClient::default();
+
+    let gas_price = 0;
+    let gas_limit = 1_000_000;
+    let maturity = Default::default();
+
+    let headers: Vec<_> = (1..=3u32)
+        .map(|height| BlockHeader {
+            height: height.into(),
+            timestamp: 4_611_686_020_000_000_000 + height as Word,
+            hash: Bytes32::from([height as u8; 32]),
+            coinbase: ContractId::from([0x10 + height as u8; 32]),
+        })
+        .collect();
+    client.as_mut().import_headers(headers.iter().copied());
+    let current_height = client.as_mut().advance_block();
+    let current = headers[1];
+    let previous = headers[0];
+
+    #[rustfmt::skip]
+    let script = vec![
+        op::movi(0x10, 32),                         // allocation size
+        op::aloc(0x10),                             // allocate memory
+        op::movi(0x11, *previous.height),           // previous block height
+        op::time(0x12, 0x11),                       // previous block timestamp
+        op::log(0x12, 0x00, 0x00, 0x00),            // log timestamp
+        op::bhsh(RegId::HP, 0x11),                  // previous block hash
+        op::logd(0, 0, RegId::HP, 0x10),            // log hash
+        op::cb(RegId::HP),                          // current block coinbase
+        op::logd(0, 0, RegId::HP, 0x10),            // log coinbase
+        op::ret(RegId::ONE)
+    ];
+
+    let tx = TransactionBuilder::script(script.into_iter().collect(), vec![])
+        .gas_price(gas_price)
+        .gas_limit(gas_limit)
+        .maturity(maturity)
+        .add_random_fee_input()
+        .finalize_checked(current_height);
+
+    let receipts = client.transact(tx);
+    let [Receipt::Log { ra, .. }, Receipt::LogData {
+        data: Some(hash), ..
+    }, Receipt::LogData {
+        data: Some(coinbase),
+        ..
+    }, ..] = receipts
+    else {
+        panic!("expected log receipts");
+    };
+
+    assert_eq!(*ra, previous.timestamp);
+    assert_eq!(hash.as_slice(), previous.hash.as_ref());
+    assert_eq!(coinbase.as_slice(), current.coinbase.as_ref());
+}
+
 #[rstest::rstest]
 fn coinbase_works() {
     let mut client = MemoryClient::default();
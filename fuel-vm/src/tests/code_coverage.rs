@@ -12,6 +12,11 @@ use fuel_tx::{
 use fuel_vm::{
     consts::*,
     prelude::*,
+    profiler::{
+        CoverageReport,
+        SourceLocation,
+        SourceMap,
+    },
 };
 use rand::{
     rngs::StdRng,
@@ -110,3 +115,70 @@ fn code_coverage() {
         );
     }
 }
+
+#[test]
+fn coverage_report_export() {
+    let rng = &mut StdRng::seed_from_u64(2322u64);
+
+    let reg_a = 0x20;
+    let script_code: Vec<u8> = vec![
+        op::jnei(RegId::ZERO, RegId::ONE, 2), // Skip next
+        op::xor(reg_a, reg_a, reg_a),         // Skipped
+        op::movi(reg_a, 1),                   // Executed
+        op::ret(RegId::ONE),
+    ]
+    .into_iter()
+    .collect();
+
+    let mut report = CoverageReport::default();
+    report.add_bytecode(None, &script_code);
+
+    // Coverage is aggregated from many runs
+    for _ in 0..2 {
+        let tx_script = TransactionBuilder::script(script_code.clone(), vec![])
+            .add_unsigned_coin_input(
+                SecretKey::random(rng),
+                rng.gen(),
+                1,
+                Default::default(),
+                rng.gen(),
+                Default::default(),
+            )
+            .gas_limit(1_000_000)
+            .finalize_checked(Default::default());
+
+        let mut vm = Interpreter::with_memory_storage();
+        vm.with_profiler(GasProfiler::default());
+        vm.transact(tx_script).expect("Failed to execute script!");
+
+        report.add(vm.profiler().data().coverage());
+    }
+
+    assert_eq!(report.get(&InstructionLocation::new(None, 0)), 2);
+    assert_eq!(report.get(&InstructionLocation::new(None, 4)), 0);
+    assert_eq!(
+        report.to_lcov(),
+        "TN:\nSF:script\nDA:1,2\nDA:2,0\nDA:3,2\nDA:4,2\nLF:4\nLH:3\nend_of_record\n"
+    );
+
+    // The first two instructions are compiled from the same line
+    let source_map: SourceMap = [
+        (0, SourceLocation::new("src/main.sw", 3)),
+        (8, SourceLocation::new("src/main.sw", 4)),
+        (12, SourceLocation::new("src/lib.sw", 10)),
+    ]
+    .into_iter()
+    .collect();
+    report.set_source_map(None, source_map);
+
+    assert_eq!(
+        report.to_lcov(),
+        "TN:\nSF:src/lib.sw\nDA:10,2\nLF:1\nLH:1\nend_of_record\n\
+         TN:\nSF:src/main.sw\nDA:3,2\nDA:4,2\nLF:2\nLH:2\nend_of_record\n"
+    );
+
+    let cobertura = report.to_cobertura();
+    assert!(cobertura.contains(r#"lines-covered="3" lines-valid="3""#));
+    assert!(cobertura.contains(r#"<class name="src/main.sw" filename="src/main.sw""#));
+    assert!(cobertura.contains(r#"<line number="10" hits="2" branch="false"/>"#));
+}
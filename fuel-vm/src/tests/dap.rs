@@ -0,0 +1,376 @@
+use alloc::vec;
+use std::io::Cursor;
+
+use crate::{
+    checked_transaction::Checked,
+    dap::{
+        read_message,
+        write_message,
+        DapError,
+        DapServer,
+        Reference,
+    },
+    interpreter::InterpreterParams,
+    prelude::*,
+    script_with_data_offset,
+    storage::MemoryStorage,
+    util::test_helpers::{
+        checked_script,
+        TestBuilder,
+    },
+};
+use fuel_asm::{
+    op,
+    RegId,
+};
+use fuel_tx::ConsensusParameters;
+use fuel_types::canonical::Serialize;
+use serde_json::{
+    json,
+    Value,
+};
+
+const GAS_LIMIT: Word = 1_000_000;
+
+/// Serve `requests` and return the messages sent by the server.
+fn session(
+    vm: Interpreter<MemoryStorage, Script>,
+    tx: Checked<Script>,
+    requests: &[(&str, Value)],
+) -> Vec<Value> {
+    let mut input = vec![];
+    for (seq, (command, arguments)) in requests.iter().enumerate() {
+        let request = json!({
+            "seq": seq + 1,
+            "type": "request",
+            "command": command,
+            "arguments": arguments,
+        });
+        write_message(&mut input, &request).expect("Failed to write request");
+    }
+
+    let mut output = vec![];
+    DapServer::new(vm, tx, Cursor::new(input), &mut output)
+        .run()
+        .expect("Failed to serve the requests");
+
+    let mut output = Cursor::new(output);
+    core::iter::from_fn(|| read_message(&mut output).expect("Malformed message"))
+        .collect()
+}
+
+fn response<'a>(messages: &'a [Value], command: &str, nth: usize) -> &'a Value {
+    messages
+        .iter()
+        .filter(|m| m["type"] == "response" && m["command"] == command)
+        .nth(nth)
+        .unwrap_or_else(|| panic!("Missing response {nth} to `{command}`"))
+}
+
+fn events<'a>(messages: &'a [Value], event: &str) -> Vec<&'a Value> {
+    messages
+        .iter()
+        .filter(|m| m["type"] == "event" && m["event"] == event)
+        .collect()
+}
+
+fn variable<'a>(response: &'a Value, name: &str) -> &'a Value {
+    response["body"]["variables"]
+        .as_array()
+        .expect("Expected variables")
+        .iter()
+        .find(|v| v["name"] == name)
+        .unwrap_or_else(|| panic!("Missing variable `{name}`"))
+}
+
+fn launch(stop_on_entry: bool) -> Vec<(&'static str, Value)> {
+    vec![
+        ("initialize", json!({ "adapterID": "fuel-vm" })),
+        ("launch", json!({ "stopOnEntry": stop_on_entry })),
+    ]
+}
+
+#[test]
+fn breakpoints_and_stepping() {
+    let vm = Interpreter::with_memory_storage();
+    let tx = checked_script(
+        vec![
+            op::movi(0x10, 8),
+            op::movi(0x11, 16),
+            op::add(0x12, 0x10, 0x11),
+            op::log(0x12, RegId::ZERO, RegId::ZERO, RegId::ZERO),
+            op::ret(RegId::ONE),
+        ],
+        GAS_LIMIT,
+    );
+
+    let mut requests = launch(false);
+    requests.extend([
+        (
+            "setBreakpoints",
+            json!({
+                "source": { "name": "script" },
+                "breakpoints": [{ "line": 3 }, { "line": 0 }],
+            }),
+        ),
+        ("configurationDone", json!({})),
+        ("stackTrace", json!({ "threadId": 1 })),
+        (
+            "variables",
+            json!({ "variablesReference": Reference::Registers.to_id() }),
+        ),
+        ("next", json!({ "threadId": 1 })),
+        ("next", json!({ "threadId": 1 })),
+        (
+            "variables",
+            json!({ "variablesReference": Reference::Receipts.to_id() }),
+        ),
+        ("continue", json!({ "threadId": 1 })),
+        ("next", json!({ "threadId": 1 })),
+        ("disconnect", json!({})),
+        ("threads", json!({})),
+    ]);
+    let messages = session(vm, tx, &requests);
+
+    assert_eq!(events(&messages, "initialized").len(), 1);
+    assert_eq!(
+        response(&messages, "setBreakpoints", 0)["body"]["breakpoints"],
+        json!([
+            { "verified": true, "line": 3 },
+            { "verified": false, "message": "Lines start at 1" },
+        ])
+    );
+
+    let stopped = events(&messages, "stopped");
+    let reasons: Vec<_> = stopped.iter().map(|e| &e["body"]["reason"]).collect();
+    assert_eq!(reasons, ["breakpoint", "step", "step"]);
+
+    let frames = &response(&messages, "stackTrace", 0)["body"]["stackFrames"];
+    assert_eq!(frames.as_array().map(Vec::len), Some(1));
+    assert_eq!(frames[0]["line"], 3);
+    assert_eq!(frames[0]["source"]["name"], "script");
+
+    let registers = response(&messages, "variables", 0);
+    assert_eq!(variable(registers, "$r16")["value"], "8");
+    assert_eq!(variable(registers, "$r17")["value"], "16");
+    assert_eq!(variable(registers, "$r18")["value"], "0");
+
+    // The log instruction was executed by the second step
+    let receipts = &response(&messages, "variables", 1)["body"]["variables"];
+    let receipt = receipts
+        .as_array()
+        .and_then(|receipts| receipts.iter().find(|r| r["value"] == "Log"))
+        .expect("Missing log receipt");
+    assert_ne!(receipt["variablesReference"], 0);
+
+    // The program isn't suspended anymore once it returns
+    assert_eq!(events(&messages, "exited")[0]["body"]["exitCode"], 0);
+    assert_eq!(events(&messages, "terminated").len(), 1);
+    assert_eq!(response(&messages, "next", 2)["success"], false);
+
+    // The session ends with the disconnection
+    assert!(messages.iter().all(|m| m["command"] != "threads"));
+    let seqs: Vec<_> = messages
+        .iter()
+        .map(|m| m["seq"].as_i64().unwrap())
+        .collect();
+    assert_eq!(seqs, (1..=messages.len() as i64).collect::<Vec<_>>());
+}
+
+#[test]
+fn conditional_breakpoints_and_memory() {
+    let vm = Interpreter::with_memory_storage();
+    let tx = checked_script(
+        vec![
+            op::movi(0x10, 3),
+            op::movi(0x11, 32),
+            op::aloc(0x11),
+            // Loop: store the counter in the heap until it reaches zero
+            op::sw(RegId::HP, 0x10, 0),
+            op::subi(0x10, 0x10, 1),
+            op::jnzb(0x10, RegId::ZERO, 1),
+            op::ret(RegId::ONE),
+        ],
+        GAS_LIMIT,
+    );
+
+    let mut requests = launch(false);
+    requests.extend([
+        (
+            "setBreakpoints",
+            json!({
+                "source": { "sourceReference": 1 },
+                "breakpoints": [
+                    { "line": 4, "condition": "$r16 == 0x1" },
+                    { "line": 5, "condition": "$r16 ~ 1" },
+                ],
+            }),
+        ),
+        ("configurationDone", json!({})),
+        (
+            "variables",
+            json!({ "variablesReference": Reference::Memory.to_id() }),
+        ),
+        (
+            "variables",
+            json!({
+                "variablesReference": Reference::Heap.to_id(),
+                "start": 0,
+                "count": 1,
+            }),
+        ),
+        ("source", json!({ "sourceReference": 1 })),
+    ]);
+    let messages = session(vm, tx, &requests);
+
+    let breakpoints = &response(&messages, "setBreakpoints", 0)["body"]["breakpoints"];
+    assert_eq!(breakpoints[0]["verified"], true);
+    assert_eq!(breakpoints[1]["verified"], false);
+
+    // The breakpoint is skipped while `$r16` isn't 1
+    let stopped = events(&messages, "stopped");
+    assert_eq!(stopped.len(), 1);
+
+    let memory = response(&messages, "variables", 0);
+    let heap = variable(memory, "heap");
+    assert_eq!(heap["indexedVariables"], 1);
+    assert_eq!(heap["variablesReference"], Reference::Heap.to_id());
+
+    let rows = &response(&messages, "variables", 1)["body"]["variables"];
+    assert_eq!(rows.as_array().map(Vec::len), Some(1));
+    let mut row = "0".repeat(2 * 32);
+    row.replace_range(14..16, "02");
+    assert_eq!(rows[0]["value"], row);
+
+    let source = &response(&messages, "source", 0)["body"]["content"];
+    let lines: Vec<_> = source.as_str().unwrap().lines().collect();
+    assert_eq!(lines.len(), 7);
+    assert_eq!(lines[3], op::sw(RegId::HP, 0x10, 0).to_string());
+}
+
+#[test]
+fn stepping_into_and_over_calls() {
+    let mut test_context = TestBuilder::new(2322u64);
+
+    let program = vec![op::movi(0x10, 7), op::movi(0x11, 9), op::ret(RegId::ONE)];
+    let contract_id = test_context.setup_contract(program, None, None).contract_id;
+
+    let (script, _) = script_with_data_offset!(
+        data_offset,
+        vec![
+            op::movi(0x10, data_offset as Immediate18),
+            op::call(0x10, RegId::ZERO, RegId::ZERO, RegId::CGAS),
+            op::call(0x10, RegId::ZERO, RegId::ZERO, RegId::CGAS),
+            op::ret(RegId::ONE),
+        ],
+        test_context.get_tx_params().tx_offset()
+    );
+    let script_data = Call::new(contract_id, 0, 0).to_bytes();
+    let tx = test_context
+        .start_script(script, script_data)
+        .gas_limit(GAS_LIMIT)
+        .contract_input(contract_id)
+        .fee_input()
+        .contract_output(&contract_id)
+        .build();
+
+    let vm = Interpreter::<_, Script>::with_storage(
+        test_context.get_storage().clone(),
+        InterpreterParams::from(&ConsensusParameters::standard()),
+    );
+
+    let mut requests = launch(true);
+    requests.extend([
+        ("configurationDone", json!({})),
+        ("next", json!({ "threadId": 1 })),
+        // Into the first call
+        ("stepIn", json!({ "threadId": 1 })),
+        ("stepIn", json!({ "threadId": 1 })),
+        ("stackTrace", json!({ "threadId": 1 })),
+        (
+            "variables",
+            json!({ "variablesReference": Reference::CallFrames.to_id() }),
+        ),
+        (
+            "variables",
+            json!({ "variablesReference": Reference::CallFrame(0).to_id() }),
+        ),
+        ("scopes", json!({ "frameId": 1 })),
+        ("source", json!({ "source": { "sourceReference": 2 } })),
+        ("stepOut", json!({ "threadId": 1 })),
+        ("stackTrace", json!({ "threadId": 1 })),
+        // Over the second call
+        ("next", json!({ "threadId": 1 })),
+        ("stackTrace", json!({ "threadId": 1 })),
+    ]);
+    let messages = session(vm, tx, &requests);
+
+    let stopped = events(&messages, "stopped");
+    let reasons: Vec<_> = stopped.iter().map(|e| &e["body"]["reason"]).collect();
+    assert_eq!(reasons, ["entry", "step", "step", "step", "step", "step"]);
+
+    let frames = &response(&messages, "stackTrace", 0)["body"]["stackFrames"];
+    assert_eq!(frames.as_array().map(Vec::len), Some(2));
+    assert_eq!(frames[0]["source"]["name"], format!("{contract_id:#x}"));
+    assert_eq!(frames[0]["source"]["sourceReference"], 2);
+    assert_eq!(frames[0]["line"], 2);
+    assert_eq!(frames[1]["source"]["name"], "script");
+    assert_eq!(frames[1]["line"], 2);
+
+    let call_frames = response(&messages, "variables", 0);
+    assert_eq!(
+        variable(call_frames, "[0]")["value"],
+        format!("{contract_id:#x}")
+    );
+    let call_frame = response(&messages, "variables", 1);
+    assert_eq!(
+        variable(call_frame, "to")["value"],
+        format!("{contract_id:#x}")
+    );
+    assert_eq!(variable(call_frame, "code_size")["value"], "12");
+
+    // The registers of the caller are the ones saved in the call frame
+    let scopes = &response(&messages, "scopes", 0)["body"]["scopes"];
+    assert_eq!(
+        scopes[0]["variablesReference"],
+        Reference::CallFrameRegisters(0).to_id()
+    );
+
+    let source = &response(&messages, "source", 0)["body"]["content"];
+    assert_eq!(source.as_str().unwrap().lines().count(), 3);
+
+    // Out of the call, back to the script
+    let frames = &response(&messages, "stackTrace", 1)["body"]["stackFrames"];
+    assert_eq!(frames.as_array().map(Vec::len), Some(1));
+    assert_eq!(frames[0]["line"], 3);
+
+    // The second call is executed entirely by a single step
+    let frames = &response(&messages, "stackTrace", 2)["body"]["stackFrames"];
+    assert_eq!(frames.as_array().map(Vec::len), Some(1));
+    assert_eq!(frames[0]["line"], 4);
+}
+
+#[test]
+fn messages_are_framed_by_their_length() {
+    let message = json!({ "seq": 1, "type": "request", "command": "threads" });
+    let mut framed = vec![];
+    write_message(&mut framed, &message).expect("Failed to write message");
+    write_message(&mut framed, &message).expect("Failed to write message");
+
+    let mut reader = Cursor::new(framed.clone());
+    assert_eq!(read_message(&mut reader).unwrap(), Some(message.clone()));
+    assert_eq!(read_message(&mut reader).unwrap(), Some(message));
+    assert_eq!(read_message(&mut reader).unwrap(), None);
+
+    // Truncated content
+    framed.truncate(framed.len() - 1);
+    let mut reader = Cursor::new(framed);
+    read_message(&mut reader).expect("Failed to read message");
+    assert!(matches!(read_message(&mut reader), Err(DapError::Io(_))));
+
+    let mut reader = Cursor::new(b"Content-Type: json\r\n\r\n{}".to_vec());
+    assert!(matches!(
+        read_message(&mut reader),
+        Err(DapError::Header(_))
+    ));
+}
@@ -0,0 +1,100 @@
+use alloc::{
+    sync::Arc,
+    vec,
+};
+
+use crate::{
+    interpreter::InterpreterParams,
+    prelude::*,
+    script_with_data_offset,
+    storage::StorageChange,
+    util::test_helpers::TestBuilder,
+};
+use fuel_asm::{
+    op,
+    RegId,
+};
+use fuel_tx::ConsensusParameters;
+use fuel_types::canonical::Serialize;
+
+#[test]
+fn dry_run_returns_the_storage_changes_without_applying_them() {
+    let mut test_context = TestBuilder::new(2322u64);
+
+    // The contract writes `0x2a` to its state, at the key stored at the start of the
+    // memory
+    let program = vec![
+        op::movi(0x11, 0x2a),
+        op::sww(RegId::ZERO, 0x12, 0x11),
+        op::ret(RegId::ONE),
+    ];
+    let contract_id = test_context.setup_contract(program, None, None).contract_id;
+
+    let (script, _) = script_with_data_offset!(
+        data_offset,
+        vec![
+            op::movi(0x10, data_offset as Immediate18),
+            op::call(0x10, RegId::ZERO, RegId::ZERO, RegId::CGAS),
+            op::ret(RegId::ONE),
+        ],
+        test_context.get_tx_params().tx_offset()
+    );
+    let script_data = Call::new(contract_id, 0, 0).to_bytes();
+    let tx = test_context
+        .start_script(script, script_data)
+        .gas_limit(1_000_000)
+        .contract_input(contract_id)
+        .fee_input()
+        .contract_output(&contract_id)
+        .build();
+
+    let params = InterpreterParams::from(&ConsensusParameters::standard());
+    let storage = Arc::new(test_context.get_storage().clone());
+
+    // The storage is shared by concurrent dry runs
+    let dry_runs: Vec<_> = (0..2)
+        .map(|_| {
+            let (storage, tx, params) = (storage.clone(), tx.clone(), params.clone());
+            std::thread::spawn(move || Interpreter::dry_run(storage, tx, params))
+        })
+        .map(|handle| handle.join().unwrap().expect("Failed to dry run"))
+        .collect();
+    assert_eq!(dry_runs[0], dry_runs[1]);
+
+    let mut key = [0u8; 32];
+    key[7] = 0x2a;
+    let changes = dry_runs[0].storage_changes();
+    let (state_key, change) = changes.state.iter().next().expect("Missing change");
+    assert_eq!(changes.state.len(), 1);
+    assert_eq!(state_key.contract_id(), &contract_id);
+    assert_eq!(
+        change,
+        &StorageChange {
+            before: None,
+            after: Some(key.into()),
+        }
+    );
+    assert!(storage
+        .merkle_contract_state(&contract_id, state_key.state_key())
+        .unwrap()
+        .is_none());
+
+    // The dry run matches the execution
+    let mut transactor =
+        Transactor::<_, Script>::new(storage.as_ref().clone(), params.clone());
+    assert_eq!(transactor.dry_run(tx.clone()).unwrap(), dry_runs[0]);
+    transactor.transact(tx);
+    assert_eq!(
+        transactor.to_owned_state_transition().as_ref(),
+        Some(dry_runs[0].state_transition())
+    );
+    assert_eq!(
+        transactor
+            .interpreter()
+            .as_ref()
+            .merkle_contract_state(&contract_id, state_key.state_key())
+            .unwrap()
+            .map(|value| value.into_owned()),
+        Some(key.into())
+    );
+}
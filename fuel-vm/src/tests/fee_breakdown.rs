@@ -0,0 +1,207 @@
+use alloc::{
+    vec,
+    vec::Vec,
+};
+use core::iter;
+
+use crate::{
+    checked_transaction::{
+        CheckPredicateParams,
+        CheckPredicates,
+        EstimatePredicates,
+        IntoChecked,
+        SignatureRecovery,
+    },
+    interpreter::InterpreterParams,
+    prelude::{
+        field::Outputs,
+        *,
+    },
+};
+use fuel_asm::op;
+use rand::{
+    rngs::StdRng,
+    Rng,
+    SeedableRng,
+};
+
+#[test]
+fn fee_breakdown_explains_the_fees_and_the_refund() {
+    let input = 3_000_000_000;
+    let gas_limit = 1_000_000;
+
+    // arbitrary non-negligible primes
+    let factor = 5479;
+    let gas_price = 6197;
+
+    let consensus_params = ConsensusParameters {
+        fee_params: FeeParameters::default().with_gas_price_factor(factor),
+        ..ConsensusParameters::standard()
+    };
+
+    // Random script to consume some gas
+    let script = iter::repeat(op::add(0x10, 0x00, 0x01))
+        .take(6688)
+        .chain(iter::once(op::ret(0x01)))
+        .collect();
+
+    let transaction = TestBuilder::new(2322u64)
+        .with_fee_params(consensus_params.fee_params)
+        .start_script(script, vec![])
+        .gas_price(gas_price)
+        .gas_limit(gas_limit)
+        .coin_input(AssetId::default(), input)
+        .change_output(AssetId::default())
+        .build();
+
+    let breakdown = transaction
+        .fee_breakdown(&consensus_params)
+        .expect("failed to break the fee down");
+    let fee = transaction.metadata().fee;
+
+    assert_eq!(breakdown.min_fee, fee.min_fee());
+    assert_eq!(breakdown.max_fee, fee.max_fee());
+    assert_eq!(
+        breakdown.bytes_gas,
+        breakdown.fee_params.gas_per_byte * breakdown.metered_bytes
+    );
+    assert_eq!(
+        breakdown.min_gas,
+        breakdown.bytes_gas + breakdown.predicate_gas
+    );
+    assert_eq!(breakdown.max_gas, breakdown.bytes_gas + gas_limit);
+    assert_eq!(
+        breakdown.max_fee - breakdown.max_fee_rounding,
+        breakdown.max_gas * gas_price / factor
+    );
+    assert!(breakdown.max_fee_rounding <= 1);
+    // The test builder checks the predicates, there are none
+    assert_eq!(breakdown.predicate_gas_used, Some(0));
+    assert_eq!(
+        breakdown.signatures,
+        vec![SignatureRecovery {
+            input_index: 0,
+            witness_index: 0,
+            gas: consensus_params.gas_costs.ecr1,
+        }]
+    );
+
+    let mut interpreter = Interpreter::with_storage(
+        MemoryStorage::default(),
+        InterpreterParams::from(&consensus_params),
+    );
+    let result = interpreter
+        .transact(transaction)
+        .expect("failed to execute transaction");
+    let gas_used = result
+        .receipts()
+        .iter()
+        .find_map(Receipt::gas_used)
+        .expect("missing script result");
+    let change = result
+        .tx()
+        .outputs()
+        .iter()
+        .find_map(|o| match o {
+            Output::Change { amount, .. } => Some(*amount),
+            _ => None,
+        })
+        .expect("failed to fetch change");
+
+    let execution = breakdown
+        .execution(gas_used)
+        .expect("failed to compute the execution fee");
+
+    assert_eq!(
+        execution.refund,
+        TransactionFee::gas_refund_value(
+            &consensus_params.fee_params,
+            gas_limit - gas_used,
+            gas_price
+        )
+        .unwrap()
+    );
+    assert_eq!(change, input - execution.fee);
+}
+
+#[test]
+fn fee_breakdown_reports_predicates_and_signature_recoveries() {
+    let rng = &mut StdRng::seed_from_u64(2322u64);
+
+    let consensus_params = ConsensusParameters::standard();
+    let secret = SecretKey::random(rng);
+    let predicate: Vec<u8> = vec![op::ret(1)].into_iter().collect();
+    let owner = Input::predicate_owner(&predicate);
+
+    let mut transaction =
+        TransactionBuilder::script(vec![op::ret(1)].into_iter().collect(), vec![])
+            .gas_price(1)
+            .gas_limit(1_000)
+            .with_params(consensus_params.clone())
+            .add_unsigned_coin_input(
+                secret,
+                rng.gen(),
+                1_000_000,
+                AssetId::default(),
+                rng.gen(),
+                Default::default(),
+            )
+            .add_input(Input::coin_predicate(
+                rng.gen(),
+                owner,
+                1_000_000,
+                AssetId::default(),
+                rng.gen(),
+                Default::default(),
+                0,
+                predicate,
+                vec![],
+            ))
+            .add_unsigned_coin_input(
+                secret,
+                rng.gen(),
+                1_000_000,
+                AssetId::default(),
+                rng.gen(),
+                Default::default(),
+            )
+            .finalize();
+    transaction
+        .estimate_predicates(&CheckPredicateParams::from(&consensus_params))
+        .expect("failed to estimate the predicates");
+
+    let checked = transaction
+        .into_checked_basic(Default::default(), &consensus_params)
+        .expect("failed to check the transaction");
+    let unchecked = checked
+        .fee_breakdown(&consensus_params)
+        .expect("failed to break the fee down");
+
+    assert!(unchecked.predicate_gas > 0);
+    assert_eq!(unchecked.predicate_gas_used, None);
+    assert_eq!(
+        unchecked.signatures,
+        vec![
+            SignatureRecovery {
+                input_index: 0,
+                witness_index: 0,
+                gas: consensus_params.gas_costs.ecr1,
+            },
+            SignatureRecovery {
+                input_index: 2,
+                witness_index: 0,
+                gas: 0,
+            },
+        ]
+    );
+    assert_eq!(unchecked.signatures_gas(), consensus_params.gas_costs.ecr1);
+
+    let checked = checked
+        .check_predicates(&CheckPredicateParams::from(&consensus_params))
+        .expect("failed to check the predicates");
+    let breakdown = checked
+        .fee_breakdown(&consensus_params)
+        .expect("failed to break the fee down");
+
+    assert_eq!(breakdown.predicate_gas_used, Some(unchecked.predicate_gas));
+}
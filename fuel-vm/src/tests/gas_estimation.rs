@@ -0,0 +1,103 @@
+use alloc::vec;
+
+use crate::{
+    checked_transaction::IntoChecked,
+    interpreter::InterpreterParams,
+    prelude::*,
+    script_with_data_offset,
+    util::test_helpers::TestBuilder,
+};
+use fuel_asm::{
+    op,
+    RegId,
+};
+use fuel_tx::{
+    field::GasLimit,
+    ConsensusParameters,
+};
+use fuel_types::canonical::Serialize;
+
+#[test]
+fn estimate_script_gas_of_a_contract_call() {
+    let mut test_context = TestBuilder::new(2322u64);
+    let consensus_params = ConsensusParameters::standard();
+
+    // The contract loops, then writes to its state
+    let program = vec![
+        op::movi(0x10, 50),
+        op::subi(0x10, 0x10, 1),
+        op::jnzb(0x10, RegId::ZERO, 0),
+        op::movi(0x11, 0x2a),
+        op::sww(RegId::ZERO, 0x12, 0x11),
+        op::ret(RegId::ONE),
+    ];
+    let contract_id = test_context.setup_contract(program, None, None).contract_id;
+
+    let (script, _) = script_with_data_offset!(
+        data_offset,
+        vec![
+            op::movi(0x10, data_offset as Immediate18),
+            op::call(0x10, RegId::ZERO, RegId::ZERO, RegId::CGAS),
+            op::ret(RegId::ONE),
+        ],
+        test_context.get_tx_params().tx_offset()
+    );
+    let script_data = Call::new(contract_id, 0, 0).to_bytes();
+
+    // The gas limit is too low to run the script
+    let mut tx = test_context
+        .start_script(script, script_data)
+        .gas_limit(10)
+        .gas_price(1_000_000_000)
+        .contract_input(contract_id)
+        .coin_input(AssetId::BASE, 1_000_000)
+        .contract_output(&contract_id)
+        .build()
+        .transaction()
+        .clone();
+
+    let mut transactor = Transactor::<_, Script>::new(
+        test_context.get_storage().clone(),
+        InterpreterParams::from(&consensus_params),
+    );
+    let estimate = transactor
+        .estimate_script_gas(&mut tx, &consensus_params)
+        .expect("Failed to estimate the script gas");
+    assert!(!estimate.should_revert());
+    assert!(estimate.gas_used() > 50);
+    assert!(estimate.gas_limit() > estimate.gas_used());
+    assert_eq!(*tx.gas_limit(), estimate.gas_limit());
+
+    // The max fee follows the estimated limit
+    let fee = TransactionFee::checked_from_tx(&consensus_params.fee_params, &tx)
+        .expect("Failed to compute the fee");
+    assert_eq!(estimate.max_fee(), fee.max_fee());
+    let fee_of_max_gas = {
+        let mut tx = tx.clone();
+        *tx.gas_limit_mut() = consensus_params.tx_params.max_gas_per_tx;
+        TransactionFee::checked_from_tx(&consensus_params.fee_params, &tx)
+            .expect("Failed to compute the fee")
+    };
+    assert!(estimate.max_fee() < fee_of_max_gas.max_fee());
+
+    // The dry run doesn't write to the storage
+    let storage = transactor.interpreter().as_ref();
+    assert_eq!(
+        storage
+            .merkle_contract_state(&contract_id, &Bytes32::zeroed())
+            .unwrap(),
+        None
+    );
+
+    // The transaction runs within the estimated limit
+    let checked = tx
+        .into_checked_basic(Default::default(), &consensus_params)
+        .expect("The estimated transaction should be valid");
+    transactor.transact(checked);
+    let receipts = transactor.receipts().expect("Failed to execute script!");
+    let Some(Receipt::ScriptResult { result, gas_used }) = receipts.last() else {
+        panic!("Expected a script result")
+    };
+    assert_eq!(*result, ScriptExecutionResult::Success);
+    assert_eq!(*gas_used, estimate.gas_used());
+}
@@ -0,0 +1,217 @@
+use alloc::vec;
+use std::sync::{
+    Arc,
+    Mutex,
+};
+
+use crate::{
+    consts::VM_MAX_RAM,
+    interpreter::{
+        trace::{
+            InstructionStep,
+            InstructionTracer,
+            JsonLinesTracer,
+            MemoryWrite,
+            RegisterChange,
+        },
+        InterpreterParams,
+    },
+    prelude::*,
+    script_with_data_offset,
+    util::test_helpers::{
+        checked_script,
+        TestBuilder,
+    },
+};
+use fuel_asm::{
+    op,
+    RegId,
+};
+use fuel_tx::ConsensusParameters;
+use fuel_types::canonical::Serialize;
+
+const GAS_LIMIT: Word = 1_000_000;
+
+#[derive(Clone, Default)]
+struct Steps(Arc<Mutex<Vec<InstructionStep>>>);
+
+impl InstructionTracer for Steps {
+    fn on_instruction(&mut self, step: &InstructionStep) {
+        self.0.lock().unwrap().push(step.clone())
+    }
+}
+
+impl Steps {
+    fn take(&self) -> Vec<InstructionStep> {
+        core::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+#[test]
+fn every_instruction_is_traced() {
+    let steps = Steps::default();
+    let mut vm = Interpreter::<_, Script>::with_memory_storage();
+    vm.set_instruction_tracer(steps.clone());
+
+    let program = vec![
+        op::movi(0x10, 8),
+        op::aloc(0x10),
+        op::movi(0x11, 0x2a),
+        op::sw(RegId::HP, 0x11, 0),
+        op::log(0x11, RegId::ZERO, RegId::ZERO, RegId::ZERO),
+        op::ret(RegId::ONE),
+    ];
+    let receipts = vm
+        .transact(checked_script(program.clone(), GAS_LIMIT))
+        .expect("Failed to execute script!")
+        .receipts()
+        .to_vec();
+    let steps = steps.take();
+
+    assert_eq!(steps.len(), program.len());
+    for (index, (step, instruction)) in steps.iter().zip(&program).enumerate() {
+        assert_eq!(step.index, index as u64);
+        assert_eq!(step.contract, None);
+        assert_eq!(step.pc, step.is + 4 * index as Word);
+        assert_eq!(step.instruction, Some(*instruction));
+        assert!(step.gas_used > 0);
+        assert_eq!(step.panic, None);
+    }
+
+    // `movi $r16 8`
+    assert_eq!(
+        steps[0].registers,
+        vec![
+            RegisterChange {
+                register: RegId::PC.into(),
+                before: steps[0].pc,
+                after: steps[0].pc + 4,
+            },
+            RegisterChange {
+                register: RegId::GGAS.into(),
+                before: GAS_LIMIT,
+                after: GAS_LIMIT - steps[0].gas_used,
+            },
+            RegisterChange {
+                register: RegId::CGAS.into(),
+                before: GAS_LIMIT,
+                after: GAS_LIMIT - steps[0].gas_used,
+            },
+            RegisterChange {
+                register: 0x10,
+                before: 0,
+                after: 8,
+            },
+        ]
+    );
+    assert!(steps[0].memory.is_empty());
+
+    // `sw $hp $r17 0` changes only the last byte of the zeroed word
+    assert_eq!(
+        steps[3].memory,
+        vec![MemoryWrite {
+            start: VM_MAX_RAM - 1,
+            bytes: vec![0x2a],
+        }]
+    );
+
+    // `log` and `ret` emit their receipts
+    assert!(steps[..4].iter().all(|s| s.receipts.is_empty()));
+    assert_eq!(steps[4].receipts, receipts[..1]);
+    assert_eq!(steps[5].receipts, receipts[1..2]);
+
+    // The gas of the script is the sum of the gas charged by its instructions
+    let Some(Receipt::ScriptResult { gas_used, .. }) = receipts.last() else {
+        panic!("Expected a script result")
+    };
+    assert_eq!(steps.iter().map(|s| s.gas_used).sum::<Word>(), *gas_used);
+}
+
+#[test]
+fn calls_and_panics_are_traced() {
+    let mut test_context = TestBuilder::new(2322u64);
+
+    let program = vec![op::movi(0x10, 1), op::div(0x10, 0x10, RegId::ZERO)];
+    let contract_id = test_context.setup_contract(program, None, None).contract_id;
+
+    let (script, _) = script_with_data_offset!(
+        data_offset,
+        vec![
+            op::movi(0x10, data_offset as Immediate18),
+            op::call(0x10, RegId::ZERO, RegId::ZERO, RegId::CGAS),
+            op::ret(RegId::ONE),
+        ],
+        test_context.get_tx_params().tx_offset()
+    );
+    let script_data = Call::new(contract_id, 0, 0).to_bytes();
+    let tx = test_context
+        .start_script(script, script_data)
+        .gas_limit(GAS_LIMIT)
+        .contract_input(contract_id)
+        .fee_input()
+        .contract_output(&contract_id)
+        .build();
+
+    let steps = Steps::default();
+    let mut vm = Interpreter::<_, Script>::with_storage(
+        test_context.get_storage().clone(),
+        InterpreterParams::from(&ConsensusParameters::standard()),
+    );
+    vm.set_instruction_tracer(steps.clone());
+    let state = vm.transact(tx).expect("Failed to execute script!");
+    assert!(state.should_revert());
+    let steps = steps.take();
+
+    let contracts: Vec<_> = steps.iter().map(|s| s.contract).collect();
+    assert_eq!(
+        contracts,
+        vec![None, None, Some(contract_id), Some(contract_id)]
+    );
+
+    // The call pushes a call frame to the stack and emits a call receipt
+    assert!(!steps[1].memory.is_empty());
+    assert!(matches!(steps[1].receipts[..], [Receipt::Call { .. }]));
+
+    // The panic is processed after the instruction is traced
+    let panic = steps.last().unwrap();
+    assert_eq!(panic.panic, Some(PanicReason::ArithmeticError));
+    assert!(panic.receipts.is_empty());
+}
+
+#[test]
+fn trace_is_streamed_as_json_lines() {
+    let steps = Steps::default();
+    let json = JsonLinesTracer::new(Vec::<u8>::new());
+
+    let program = vec![
+        op::movi(0x10, 32),
+        op::aloc(0x10),
+        op::movi(0x11, 0xff),
+        op::sb(RegId::HP, 0x11, 3),
+        op::ret(RegId::ONE),
+    ];
+
+    let tx = checked_script(program, GAS_LIMIT);
+    let mut vm = Interpreter::<_, Script>::with_memory_storage();
+    vm.set_instruction_tracer(steps.clone());
+    vm.transact(tx.clone()).expect("Failed to execute script!");
+
+    // The memory isn't cleared between transactions, so a fresh VM is used
+    let mut vm = Interpreter::<_, Script>::with_memory_storage();
+    vm.set_instruction_tracer(json.clone());
+    vm.transact(tx).expect("Failed to execute script!");
+
+    let lines = json.with_writer(|output| String::from_utf8(output.clone()).unwrap());
+    assert!(json.take_error().is_none());
+
+    let streamed: Vec<InstructionStep> = lines
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Invalid step"))
+        .collect();
+    assert_eq!(streamed, steps.take());
+
+    let sb: serde_json::Value =
+        serde_json::from_str(lines.lines().nth(3).unwrap()).unwrap();
+    assert_eq!(sb["instruction"], "sb $hp $r17 3");
+    assert_eq!(sb["memory"][0]["bytes"], "ff");
+}
@@ -6,14 +6,20 @@ mod test_helpers;
 
 mod alu;
 mod backtrace;
+mod block_executor;
 mod blockchain;
 mod cgas;
 mod code_coverage;
 mod contract;
 mod crypto;
+mod dap;
+mod dry_run;
 mod encoding;
+mod fee_breakdown;
 mod flow;
+mod gas_estimation;
 mod gas_factor;
+mod instruction_trace;
 mod jump_absolute;
 mod jump_relative;
 mod log;
@@ -22,7 +28,10 @@ mod metadata;
 mod outputs;
 mod predicate;
 mod profile_gas;
+mod replay;
+mod reverse_stepping;
 mod serde_profile;
 mod spec;
+mod storage_access;
 mod validation;
 mod wideint;
@@ -5,7 +5,18 @@ use fuel_asm::{
     RegId,
 };
 use fuel_tx::TransactionBuilder;
-use fuel_vm::prelude::*;
+use fuel_types::canonical::Serialize;
+use fuel_vm::{
+    call::CallFrame,
+    interpreter::InterpreterParams,
+    prelude::*,
+    profiler::{
+        CallSite,
+        CallTree,
+    },
+    script_with_data_offset,
+    util::test_helpers::TestBuilder,
+};
 use rand::{
     rngs::StdRng,
     Rng,
@@ -104,3 +115,118 @@ fn profile_gas() {
     assert!(items0[0] == items1[0] && items0[0] == items2[0]);
     assert!(items0[1] == items1[1] && items0[1] == items2[1]);
 }
+
+#[test]
+fn profile_gas_per_call_stack() {
+    let mut test_context = TestBuilder::new(2322u64);
+    let gas_limit = 1_000_000;
+
+    // The inner contract loops before returning
+    let inner = test_context
+        .setup_contract(
+            vec![
+                op::movi(0x10, 10),
+                op::subi(0x10, 0x10, 1),
+                op::jnzb(0x10, RegId::ZERO, 0),
+                op::ret(RegId::ONE),
+            ],
+            None,
+            None,
+        )
+        .contract_id;
+
+    // The outer contract calls the contract described by its first argument
+    let outer = test_context
+        .setup_contract(
+            vec![
+                op::lw(0x10, RegId::FP, (CallFrame::a_offset() / 8) as Immediate12),
+                op::call(0x10, RegId::ZERO, RegId::ZERO, RegId::CGAS),
+                op::ret(RegId::ONE),
+            ],
+            None,
+            None,
+        )
+        .contract_id;
+
+    let (script, data_offset) = script_with_data_offset!(
+        data_offset,
+        vec![
+            op::movi(0x10, data_offset as Immediate18),
+            op::call(0x10, RegId::ZERO, RegId::ZERO, RegId::CGAS),
+            op::ret(RegId::ONE),
+        ],
+        test_context.get_tx_params().tx_offset()
+    );
+    let inner_call = data_offset as Word + Call::LEN as Word;
+    let script_data = [
+        Call::new(outer, inner_call, 0).to_bytes(),
+        Call::new(inner, 0, 0).to_bytes(),
+    ]
+    .concat();
+    let tx = test_context
+        .start_script(script, script_data)
+        .gas_limit(gas_limit)
+        .contract_input(outer)
+        .contract_input(inner)
+        .fee_input()
+        .contract_output(&outer)
+        .contract_output(&inner)
+        .build();
+
+    let output = GasProfiler::default();
+    let mut vm = Interpreter::<_, Script>::with_storage(
+        test_context.get_storage().clone(),
+        InterpreterParams::from(&ConsensusParameters::standard()),
+    );
+    vm.with_profiler(output.clone());
+    let state = vm.transact(tx).expect("Failed to execute script!");
+    assert!(!state.should_revert());
+
+    let data = output.data().expect("failed to fetch profiling data");
+    let gas = data.gas();
+    let total = gas.values().sum::<u64>();
+
+    // The calls are made by the second instruction of the script and of the
+    // outer contract
+    let outer_call = CallSite::new(outer, 4);
+    let inner_call = CallSite::new(inner, 4);
+    let script_gas = gas.call_stack(&[]);
+    let outer_gas = gas.call_stack(&[outer_call]);
+    let inner_gas = gas.call_stack(&[outer_call, inner_call]);
+    assert!(script_gas > 0 && outer_gas > 0 && inner_gas > 0);
+    assert_eq!(script_gas + outer_gas + inner_gas, total);
+
+    assert_eq!(
+        gas.collapsed_stacks(),
+        format!(
+            "script {script_gas}\n\
+             script;{outer:#x}@4 {outer_gas}\n\
+             script;{outer:#x}@4;{inner:#x}@4 {inner_gas}\n"
+        )
+    );
+
+    let tree = gas.call_tree();
+    assert_eq!(
+        tree,
+        CallTree {
+            call: None,
+            gas: script_gas,
+            total_gas: total,
+            calls: vec![CallTree {
+                call: Some(outer_call),
+                gas: outer_gas,
+                total_gas: outer_gas + inner_gas,
+                calls: vec![CallTree {
+                    call: Some(inner_call),
+                    gas: inner_gas,
+                    total_gas: inner_gas,
+                    calls: vec![],
+                }],
+            }],
+        }
+    );
+
+    let json = serde_json::to_string(&tree).expect("Failed to serialize the tree");
+    let deserialized: CallTree = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, tree);
+}
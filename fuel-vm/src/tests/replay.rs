@@ -0,0 +1,158 @@
+use alloc::vec;
+
+use crate::{
+    interpreter::{
+        trace::JsonLinesTracer,
+        InterpreterParams,
+    },
+    prelude::*,
+    replay::{
+        ReplayError,
+        Snapshot,
+    },
+    script_with_data_offset,
+    util::test_helpers::TestBuilder,
+};
+use fuel_asm::{
+    op,
+    RegId,
+};
+use fuel_tx::ConsensusParameters;
+use fuel_types::canonical::Serialize;
+
+#[test]
+fn replay_reproduces_the_execution_from_a_snapshot() {
+    let mut test_context = TestBuilder::new(2322u64);
+
+    // The contract logs its state at the key of its id, the timestamp of the current
+    // block and its base asset balance, then overwrites the state slot
+    let program = vec![
+        op::srw(0x10, 0x11, RegId::FP),
+        op::bhei(0x11),
+        op::time(0x12, 0x11),
+        op::addi(0x13, RegId::FP, ContractId::LEN as Immediate12),
+        op::bal(0x14, 0x13, RegId::FP),
+        op::log(0x10, 0x12, 0x14, RegId::ZERO),
+        op::sww(RegId::FP, 0x15, 0x12),
+        op::ret(RegId::ONE),
+    ];
+    let contract_id = test_context
+        .setup_contract(program, Some((AssetId::BASE, 1000)), None)
+        .contract_id;
+    // An untouched contract isn't part of the snapshot
+    let untouched = test_context
+        .setup_contract(vec![op::ret(RegId::ONE)], None, None)
+        .contract_id;
+
+    let (script, _) = script_with_data_offset!(
+        data_offset,
+        vec![
+            op::movi(0x10, data_offset as Immediate18),
+            op::movi(0x11, (data_offset as usize + Call::LEN) as Immediate18),
+            op::call(0x10, RegId::ZERO, 0x11, RegId::CGAS),
+            op::ret(RegId::ONE),
+        ],
+        test_context.get_tx_params().tx_offset()
+    );
+    let script_data = Call::new(contract_id, 0, 0)
+        .to_bytes()
+        .into_iter()
+        .chain(*AssetId::BASE)
+        .collect();
+    let tx = test_context
+        .start_script(script, script_data)
+        .gas_limit(1_000_000)
+        .contract_input(contract_id)
+        .fee_input()
+        .contract_output(&contract_id)
+        .build();
+
+    let mut storage = test_context.get_storage().clone();
+    let height = storage.block_height().unwrap();
+    let key = Bytes32::new(*contract_id);
+    let mut value = Bytes32::zeroed();
+    value[7] = 0x2a;
+    storage
+        .merkle_contract_state_insert(&contract_id, &key, &value)
+        .unwrap();
+    storage.set_timestamp(height, 1234);
+
+    let consensus_params = ConsensusParameters::standard();
+    let snapshot = Snapshot::capture(
+        &storage,
+        tx.transaction().clone().into(),
+        consensus_params.clone(),
+    )
+    .expect("Failed to capture the snapshot");
+
+    assert_eq!(snapshot.block_height, height);
+    assert_eq!(snapshot.blocks.len(), 1);
+    assert_eq!(snapshot.blocks[0].timestamp, 1234);
+    assert!(!snapshot.contracts.contains_key(&untouched));
+    let contract = &snapshot.contracts[&contract_id];
+    assert_eq!(contract.state.get(&key), Some(&value));
+    assert_eq!(contract.balances.get(&AssetId::BASE), Some(&1000));
+    // The capture doesn't write to the storage
+    assert_eq!(
+        storage
+            .merkle_contract_state(&contract_id, &key)
+            .unwrap()
+            .as_deref(),
+        Some(&value)
+    );
+
+    let json = serde_json::to_string(&snapshot).expect("Failed to serialize");
+    let snapshot: Snapshot = serde_json::from_str(&json).expect("Failed to deserialize");
+
+    let replay = snapshot.replay().expect("Failed to replay");
+    let logged = replay
+        .receipts()
+        .iter()
+        .find_map(|receipt| match receipt {
+            Receipt::Log { ra, rb, rc, .. } => Some((*ra, *rb, *rc)),
+            _ => None,
+        })
+        .expect("Missing log");
+    assert_eq!(logged, (0x2a, 1234, 1000));
+
+    let mut transactor =
+        Transactor::<_, Script>::new(storage, InterpreterParams::from(&consensus_params));
+    transactor.transact(tx);
+    let transition = transactor.to_owned_state_transition().unwrap();
+    assert_eq!(replay.state(), transition.state());
+    assert_eq!(replay.receipts(), transition.receipts());
+
+    // The trace covers the script and the contract
+    let tracer = JsonLinesTracer::new(Vec::new());
+    let traced = snapshot
+        .replay_traced(tracer.clone())
+        .expect("Failed to replay");
+    assert_eq!(traced, replay);
+    let steps = tracer.with_writer(|trace| trace.split(|b| *b == b'\n').count() - 1);
+    assert_eq!(steps, 4 + 8);
+}
+
+#[test]
+fn replay_rejects_mint_transactions() {
+    let height = 1.into();
+    let snapshot = Snapshot {
+        consensus_parameters: ConsensusParameters::standard(),
+        block_height: height,
+        coinbase: Default::default(),
+        blocks: vec![],
+        contracts: Default::default(),
+        transaction: Transaction::mint(
+            TxPointer::new(height, 0),
+            Default::default(),
+            Default::default(),
+            0,
+            AssetId::BASE,
+        )
+        .into(),
+    };
+
+    assert!(matches!(
+        snapshot.replay(),
+        Err(ReplayError::UnexpectedMint)
+    ));
+}
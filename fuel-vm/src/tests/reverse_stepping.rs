@@ -0,0 +1,223 @@
+use alloc::vec;
+
+use crate::{
+    interpreter::InterpreterParams,
+    prelude::*,
+    script_with_data_offset,
+    state::Trigger,
+    storage::InterpreterStorage,
+    util::test_helpers::{
+        checked_script,
+        TestBuilder,
+    },
+};
+use fuel_asm::{
+    op,
+    RegId,
+};
+use fuel_tx::ConsensusParameters;
+use fuel_types::canonical::Serialize;
+
+const GAS_LIMIT: Word = 1_000_000;
+
+fn slot<S: InterpreterStorage>(
+    vm: &Interpreter<S, Script>,
+    contract_id: &ContractId,
+) -> Option<Bytes32> {
+    AsRef::<S>::as_ref(vm)
+        .merkle_contract_state(contract_id, &Bytes32::zeroed())
+        .unwrap()
+        .map(|value| value.into_owned())
+}
+
+#[test]
+fn step_back_reverts_instructions() {
+    let mut vm = Interpreter::<_, Script>::with_memory_storage().add_recording();
+    vm.record_history(100);
+
+    let tx = checked_script(
+        vec![
+            op::movi(0x10, 8),
+            op::aloc(0x10),
+            op::movi(0x11, 42),
+            op::sw(RegId::HP, 0x11, 0),
+            op::log(0x11, RegId::ZERO, RegId::ZERO, RegId::ZERO),
+            op::ret(RegId::ONE),
+        ],
+        GAS_LIMIT,
+    );
+    vm.set_breakpoint(Breakpoint::script(5));
+
+    let state = vm
+        .transact(tx)
+        .map(ProgramState::from)
+        .expect("Failed to execute script!");
+    assert_eq!(state, Breakpoint::script(5));
+    assert_eq!(vm.history_len(), 5);
+
+    let hp = vm.registers()[RegId::HP] as usize;
+    let memory = |vm: &Interpreter<_, Script>| vm.memory()[hp + 7];
+    assert_eq!(memory(&vm), 42);
+
+    // The log and the receipt of the suspension are reverted
+    let state = vm.step_back().expect("Failed to step back");
+    assert_eq!(
+        state,
+        Some(ProgramState::RunProgram(Breakpoint::script(4).into()))
+    );
+    assert!(vm.receipts().is_empty());
+
+    let state = vm.step_back().expect("Failed to step back");
+    assert_eq!(
+        state,
+        Some(ProgramState::RunProgram(Breakpoint::script(3).into()))
+    );
+    assert_eq!(memory(&vm), 0);
+    assert_eq!(vm.registers()[0x11], 42);
+    assert_eq!(vm.history_len(), 3);
+
+    // The reverted instructions are executed again
+    let state = vm.resume().expect("Failed to resume");
+    assert_eq!(state, Breakpoint::script(5));
+    assert_eq!(memory(&vm), 42);
+    assert_eq!(vm.history_len(), 5);
+
+    let state = vm.resume().expect("Failed to resume");
+    assert_eq!(state, ProgramState::Return(1));
+}
+
+#[test]
+fn step_back_from_a_panic() {
+    let mut vm = Interpreter::<_, Script>::with_memory_storage().add_recording();
+    vm.record_history(100);
+
+    let tx = checked_script(
+        vec![
+            op::movi(0x10, 8),
+            op::div(0x11, 0x10, RegId::ZERO),
+            op::ret(RegId::ONE),
+        ],
+        GAS_LIMIT,
+    );
+
+    let state = vm
+        .transact(tx)
+        .map(ProgramState::from)
+        .expect("Failed to execute script!");
+    assert_eq!(state, ProgramState::Revert(0));
+
+    // Back to the faulting instruction, before the panic receipt
+    let state = vm.step_back().expect("Failed to step back");
+    assert_eq!(
+        state,
+        Some(ProgramState::RunProgram(Breakpoint::script(1).into()))
+    );
+    assert!(vm.receipts().is_empty());
+    assert_eq!(vm.registers()[0x10], 8);
+
+    let state = vm.step_back().expect("Failed to step back");
+    assert_eq!(
+        state,
+        Some(ProgramState::RunProgram(Breakpoint::script(0).into()))
+    );
+    assert_eq!(vm.registers()[0x10], 0);
+
+    assert_eq!(vm.step_back().expect("Failed to step back"), None);
+}
+
+#[test]
+fn history_is_bounded() {
+    let mut vm = Interpreter::<_, Script>::with_memory_storage().add_recording();
+    vm.record_history(2);
+
+    let tx = checked_script(
+        vec![
+            op::movi(0x10, 1),
+            op::movi(0x11, 2),
+            op::movi(0x12, 3),
+            op::ret(RegId::ONE),
+        ],
+        GAS_LIMIT,
+    );
+    vm.transact(tx).expect("Failed to execute script!");
+    assert_eq!(vm.history_len(), 2);
+
+    vm.step_back().expect("Failed to step back");
+    let state = vm.step_back().expect("Failed to step back");
+    assert_eq!(
+        state,
+        Some(ProgramState::RunProgram(Breakpoint::script(2).into()))
+    );
+    assert_eq!(vm.step_back().expect("Failed to step back"), None);
+}
+
+#[test]
+fn reverse_continue_to_a_storage_write() {
+    let mut test_context = TestBuilder::new(2322u64);
+
+    let program = vec![
+        // Allocate 32 bytes for the zeroed state key
+        op::movi(0x15, Bytes32::LEN as u32),
+        op::aloc(0x15),
+        op::movi(0x10, 7),
+        op::sww(RegId::HP, 0x29, 0x10),
+        op::noop(),
+        op::ret(RegId::ONE),
+    ];
+    let contract_id = test_context.setup_contract(program, None, None).contract_id;
+
+    let (script, _) = script_with_data_offset!(
+        data_offset,
+        vec![
+            op::movi(0x10, data_offset as Immediate18),
+            op::call(0x10, RegId::ZERO, RegId::ZERO, RegId::CGAS),
+            op::ret(RegId::ONE),
+        ],
+        test_context.get_tx_params().tx_offset()
+    );
+    let script_data = Call::new(contract_id, 0, 0).to_bytes();
+    let tx = test_context
+        .start_script(script, script_data)
+        .gas_limit(GAS_LIMIT)
+        .contract_input(contract_id)
+        .fee_input()
+        .contract_output(&contract_id)
+        .build();
+
+    let mut vm = Interpreter::<_, Script>::with_storage(
+        test_context.get_storage().clone(),
+        InterpreterParams::from(&ConsensusParameters::standard()),
+    )
+    .add_recording();
+    vm.record_history(1000);
+
+    let state = vm
+        .transact(tx)
+        .map(ProgramState::from)
+        .expect("Failed to execute script!");
+    assert_eq!(state, ProgramState::Return(1));
+
+    assert!(slot(&vm, &contract_id).is_some());
+
+    vm.watch_storage(contract_id, Bytes32::zeroed());
+    let state = vm.reverse_continue().expect("Failed to reverse");
+    assert_eq!(
+        state,
+        Some(ProgramState::RunProgram(DebugEval::Trigger(
+            Breakpoint::new(contract_id, 3),
+            Trigger::Storage {
+                contract: contract_id,
+                key: Bytes32::zeroed(),
+            }
+        )))
+    );
+    assert_eq!(slot(&vm, &contract_id), None);
+
+    // Without other breakpoints, the whole history is reverted
+    let state = vm.reverse_continue().expect("Failed to reverse");
+    assert_eq!(
+        state,
+        Some(ProgramState::RunProgram(Breakpoint::script(0).into()))
+    );
+    assert_eq!(vm.history_len(), 0);
+}
@@ -0,0 +1,167 @@
+use alloc::vec;
+
+use crate::{
+    interpreter::{
+        storage_access::{
+            AccessKind,
+            StorageAccess,
+            StorageAccessLog,
+            StorageAccessTracer,
+        },
+        InterpreterParams,
+    },
+    prelude::*,
+    script_with_data_offset,
+    util::test_helpers::TestBuilder,
+};
+use fuel_asm::{
+    op,
+    RegId,
+};
+use fuel_tx::{
+    ConsensusParameters,
+    Contract,
+};
+use fuel_types::canonical::Serialize;
+
+#[test]
+fn access_tracer_receives_code_state_and_balance_accesses() {
+    let mut test_context = TestBuilder::new(2322u64);
+    let gas_limit = 1_000_000;
+
+    let program = vec![
+        // Allocate 32 bytes for the zeroed state key and `sub_id`
+        op::movi(0x15, Bytes32::LEN as u32),
+        op::aloc(0x15),
+        op::movi(0x10, 7),
+        op::sww(RegId::HP, 0x29, 0x10),
+        op::srw(0x11, 0x29, RegId::HP),
+        op::movi(0x12, 5),
+        op::mint(0x12, RegId::HP),
+        op::ret(RegId::ONE),
+    ];
+    let contract_id = test_context.setup_contract(program, None, None).contract_id;
+    let state_key = (&contract_id, &Bytes32::zeroed()).into();
+    let balance_key = (&contract_id, &contract_id.asset_id(&Bytes32::zeroed())).into();
+
+    let (script, _) = script_with_data_offset!(
+        data_offset,
+        vec![
+            op::movi(0x10, data_offset as Immediate18),
+            op::call(0x10, RegId::ZERO, RegId::ZERO, RegId::CGAS),
+            op::ret(RegId::ONE),
+        ],
+        test_context.get_tx_params().tx_offset()
+    );
+    let script_data = Call::new(contract_id, 0, 0).to_bytes();
+    let tx = test_context
+        .start_script(script, script_data)
+        .gas_limit(gas_limit)
+        .contract_input(contract_id)
+        .fee_input()
+        .contract_output(&contract_id)
+        .build();
+
+    let mut interpreter = Interpreter::<_, Script>::with_storage(
+        test_context.get_storage().clone(),
+        InterpreterParams::from(&ConsensusParameters::standard()),
+    )
+    .add_access_tracer(StorageAccessLog::default());
+    let state = interpreter.transact(tx).expect("Failed to transact");
+    assert!(!state.should_revert());
+    let (interpreter, log) = interpreter.remove_access_tracer();
+
+    let code = interpreter
+        .as_ref()
+        .storage_contract(&contract_id)
+        .unwrap()
+        .map(|code| code.into_owned());
+    assert!(code.is_some());
+    assert!(log
+        .contract_code
+        .iter()
+        .all(|access| access.key == contract_id && access.kind == AccessKind::Read));
+    assert!(log.contract_code.iter().any(|access| access.after == code));
+
+    let written = log.contract_state[0].after;
+    assert!(written.is_some());
+    assert_eq!(
+        log.contract_state,
+        vec![
+            StorageAccess {
+                kind: AccessKind::Write,
+                key: state_key,
+                before: None,
+                after: written,
+            },
+            StorageAccess {
+                kind: AccessKind::Read,
+                key: state_key,
+                before: written,
+                after: written,
+            },
+        ]
+    );
+
+    assert_eq!(
+        log.contract_balance.last(),
+        Some(&StorageAccess {
+            kind: AccessKind::Write,
+            key: balance_key,
+            before: None,
+            after: Some(5),
+        })
+    );
+}
+
+/// Keeps the contracts whose code was read, without loading the code.
+#[derive(Default)]
+struct CodeReads(Vec<ContractId>);
+
+impl StorageAccessTracer for CodeReads {
+    fn contract_code_read(
+        &mut self,
+        key: ContractId,
+        _code: &mut dyn FnMut() -> Option<Contract>,
+    ) {
+        self.0.push(key)
+    }
+}
+
+#[test]
+fn code_reads_report_the_key_without_the_code() {
+    let mut test_context = TestBuilder::new(2322u64);
+
+    let contract_id = test_context
+        .setup_contract(vec![op::ret(RegId::ONE)], None, None)
+        .contract_id;
+
+    let (script, _) = script_with_data_offset!(
+        data_offset,
+        vec![
+            op::movi(0x10, data_offset as Immediate18),
+            op::call(0x10, RegId::ZERO, RegId::ZERO, RegId::CGAS),
+            op::ret(RegId::ONE),
+        ],
+        test_context.get_tx_params().tx_offset()
+    );
+    let tx = test_context
+        .start_script(script, Call::new(contract_id, 0, 0).to_bytes())
+        .gas_limit(1_000_000)
+        .contract_input(contract_id)
+        .fee_input()
+        .contract_output(&contract_id)
+        .build();
+
+    let mut interpreter = Interpreter::<_, Script>::with_storage(
+        test_context.get_storage().clone(),
+        InterpreterParams::from(&ConsensusParameters::standard()),
+    )
+    .add_access_tracer(CodeReads::default());
+    let state = interpreter.transact(tx).expect("Failed to transact");
+    assert!(!state.should_revert());
+
+    let (_, reads) = interpreter.remove_access_tracer();
+    assert!(!reads.0.is_empty());
+    assert!(reads.0.iter().all(|key| *key == contract_id));
+}
@@ -13,7 +13,9 @@ use crate::{
         Interpreter,
     },
     state::{
+        DryRun,
         ProgramState,
+        ScriptGasEstimate,
         StateTransition,
         StateTransitionRef,
     },
@@ -22,6 +24,7 @@ use crate::{
 
 use crate::interpreter::InterpreterParams;
 use fuel_tx::{
+    ConsensusParameters,
     Create,
     GasCosts,
     Receipt,
@@ -163,6 +166,23 @@ where
     }
 }
 
+impl<S> Transactor<S, Script>
+where
+    S: InterpreterStorage,
+{
+    /// Estimate the gas limit of the script transaction, and set it as the gas limit
+    /// of `tx`.
+    ///
+    /// See [`Interpreter::estimate_script_gas`].
+    pub fn estimate_script_gas(
+        &self,
+        tx: &mut Script,
+        consensus_params: &ConsensusParameters,
+    ) -> Result<ScriptGasEstimate, InterpreterError<S::DataError>> {
+        self.interpreter.estimate_script_gas(tx, consensus_params)
+    }
+}
+
 impl<S, Tx> Transactor<S, Tx>
 where
     S: InterpreterStorage,
@@ -182,6 +202,21 @@ where
     Tx: ExecutableTransaction,
     <Tx as IntoChecked>::Metadata: CheckedMetadata,
 {
+    /// Execute a transaction without applying its writes to the storage, and return
+    /// them along with the state transition.
+    ///
+    /// See [`Interpreter::dry_run`].
+    pub fn dry_run(
+        &self,
+        tx: Checked<Tx>,
+    ) -> Result<DryRun<Tx>, InterpreterError<S::DataError>> {
+        Interpreter::dry_run(
+            self.interpreter.as_ref(),
+            tx,
+            self.interpreter.interpreter_params().clone(),
+        )
+    }
+
     /// Execute a transaction, and return the new state of the transactor
     pub fn transact(&mut self, tx: Checked<Tx>) -> &mut Self {
         match self.interpreter.transact(tx) {
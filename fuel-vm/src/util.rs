@@ -566,6 +566,19 @@ pub mod test_helpers {
         }
     }
 
+    /// A script transaction running `program`, paying its fee with a random input and
+    /// checked against the standard consensus parameters.
+    pub fn checked_script(program: Vec<Instruction>, gas_limit: Word) -> Checked<Script> {
+        let consensus_params = ConsensusParameters::standard();
+
+        TransactionBuilder::script(program.into_iter().collect(), vec![])
+            .gas_limit(gas_limit)
+            .add_random_fee_input()
+            .finalize()
+            .into_checked(Default::default(), &consensus_params)
+            .expect("failed to generate checked tx")
+    }
+
     pub fn check_expected_reason_for_instructions(
         instructions: Vec<Instruction>,
         expected_reason: PanicReason,